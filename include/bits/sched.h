@@ -0,0 +1,17 @@
+#ifndef _BITS_SCHED_H
+#define _BITS_SCHED_H
+
+#define __CPU_WORD(cpu) ((cpu) / (8 * sizeof(unsigned long)))
+#define __CPU_MASK(cpu) (1UL << ((cpu) % (8 * sizeof(unsigned long))))
+
+#define CPU_ZERO(set) \
+    do { \
+        unsigned long __i; \
+        for (__i = 0; __i < sizeof((set)->__bits) / sizeof(unsigned long); __i++) \
+            (set)->__bits[__i] = 0; \
+    } while (0)
+#define CPU_SET(cpu, set) ((set)->__bits[__CPU_WORD(cpu)] |= __CPU_MASK(cpu))
+#define CPU_CLR(cpu, set) ((set)->__bits[__CPU_WORD(cpu)] &= ~__CPU_MASK(cpu))
+#define CPU_ISSET(cpu, set) (((set)->__bits[__CPU_WORD(cpu)] & __CPU_MASK(cpu)) != 0)
+
+#endif
@@ -0,0 +1,10 @@
+#ifndef _BITS_STDLIB_H
+#define _BITS_STDLIB_H
+
+// Not correctly rounded for long double: this parses to double precision
+// and widens the result. Digits beyond the 53 bits of a double are rounded
+// away, and numbers outside the range of a double overflow to infinity or
+// underflow to zero with ERANGE, even where a long double could hold them.
+long double strtold(const char *nptr, char **endptr);
+
+#endif /* _BITS_STDLIB_H */
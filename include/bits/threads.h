@@ -0,0 +1,7 @@
+#ifndef _BITS_THREADS_H
+#define _BITS_THREADS_H
+
+#define ONCE_FLAG_INIT PTHREAD_ONCE_INIT
+#define thread_local _Thread_local
+
+#endif
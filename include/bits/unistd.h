@@ -5,5 +5,6 @@
 
 int execl(const char *path, const char* argv0, ...);
 int execle(const char *path, const char* argv0, ...);
+int execlp(const char *file, const char* argv0, ...);
 
 #endif
@@ -20,11 +20,11 @@
 #define SHRT_MIN (-SHRT_MAX - 1)
 
 // TODO: These might not be accurate on all platforms
-#define SSIZE_MAX ((1 << 64 - 1) - 1)
+#define SSIZE_MAX LONG_MAX
 #define UCHAR_MAX 255
-#define UINT_MAX ((1 << 32) - 1)
-#define ULLONG_MAX ((1 << 64) - 1)
-#define ULONG_MAX ((1 << 64) - 1)
+#define UINT_MAX (INT_MAX * 2U + 1U)
+#define ULLONG_MAX (LLONG_MAX * 2ULL + 1ULL)
+#define ULONG_MAX (LONG_MAX * 2UL + 1UL)
 #define USHRT_MAX ((1 << 16) - 1)
 #define WORD_BIT 32
 
@@ -13,4 +13,55 @@ void *memalign(size_t alignment, size_t size);
 void *realloc(void *ptr, size_t size);
 void *valloc(size_t size);
 
+// Implemented in src/header/malloc
+
+#define M_TRIM_THRESHOLD (-1)
+#define M_GRANULARITY (-2)
+#define M_MMAP_THRESHOLD (-3)
+
+struct mallinfo {
+    int arena;
+    int ordblks;
+    int smblks;
+    int hblks;
+    int hblkhd;
+    int usmblks;
+    int fsmblks;
+    int uordblks;
+    int fordblks;
+    int keepcost;
+};
+
+struct mallinfo2 {
+    size_t arena;
+    size_t ordblks;
+    size_t smblks;
+    size_t hblks;
+    size_t hblkhd;
+    size_t usmblks;
+    size_t fsmblks;
+    size_t uordblks;
+    size_t fordblks;
+    size_t keepcost;
+};
+
+struct mallinfo mallinfo(void);
+struct mallinfo2 mallinfo2(void);
+void malloc_stats(void);
+int malloc_trim(size_t pad);
+size_t malloc_usable_size(void *ptr);
+int mallopt(int param, int value);
+
+// relibc extension: called after every allocation made through malloc and
+// friends. The hooks must not allocate themselves.
+struct relibc_alloc_hooks {
+    void (*malloc)(void *ptr, size_t size, void *data);
+    void (*realloc)(void *old, void *ptr, size_t size, void *data);
+    void (*free)(void *ptr, void *data);
+    void *data;
+};
+
+// Pass NULL to remove the hooks
+void relibc_set_alloc_hooks(const struct relibc_alloc_hooks *hooks);
+
 #endif
@@ -0,0 +1,9 @@
+double strtod(const char *nptr, char **endptr);
+
+// There is no long double in Rust, so this parses to double precision and
+// widens the result, see the limitations in bits/stdlib.h. The parser in
+// strtod.rs would need a wider mantissa and exponent range than a u64 and
+// its big decimal give it for an 80 or 128 bit long double.
+long double strtold(const char *nptr, char **endptr) {
+    return strtod(nptr, endptr);
+}
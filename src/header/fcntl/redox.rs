@@ -17,4 +17,6 @@ pub const O_DIRECTORY: c_int = 0x1000_0000;
 pub const O_PATH: c_int = 0x2000_0000;
 pub const O_SYMLINK: c_int = 0x4000_0000;
 pub const O_NOFOLLOW: c_int = 0x8000_0000;
+// There are no controlling terminals to acquire
+pub const O_NOCTTY: c_int = 0;
 pub const O_ACCMODE: c_int = O_RDONLY | O_WRONLY | O_RDWR;
@@ -1,6 +1,7 @@
 use header::ctype;
 use header::errno::*;
 use header::stdlib::*;
+use header::wchar::narrow_strto;
 use platform;
 use platform::types::*;
 
@@ -32,7 +33,7 @@ pub unsafe extern "C" fn strtoimax(
 ) -> intmax_t {
     strto_impl!(
         intmax_t,
-        false,
+        true,
         intmax_t::max_value(),
         intmax_t::min_value(),
         s,
@@ -58,22 +59,20 @@ pub unsafe extern "C" fn strtoumax(
     )
 }
 
-#[allow(unused)]
-// #[no_mangle]
-pub extern "C" fn wcstoimax(
+#[no_mangle]
+pub unsafe extern "C" fn wcstoimax(
     nptr: *const wchar_t,
     endptr: *mut *mut wchar_t,
     base: c_int,
 ) -> intmax_t {
-    unimplemented!();
+    narrow_strto(nptr, endptr, |s, end| strtoimax(s, end, base))
 }
 
-#[allow(unused)]
-// #[no_mangle]
-pub extern "C" fn wcstoumax(
+#[no_mangle]
+pub unsafe extern "C" fn wcstoumax(
     nptr: *const wchar_t,
     endptr: *mut *mut wchar_t,
     base: c_int,
 ) -> uintmax_t {
-    unimplemented!();
+    narrow_strto(nptr, endptr, |s, end| strtoumax(s, end, base))
 }
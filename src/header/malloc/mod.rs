@@ -0,0 +1,103 @@
+//! malloc.h implementation for Redox, following the GNU and dlmalloc extensions
+
+use core::fmt::Write;
+
+use platform;
+use platform::types::*;
+use platform::AllocHooks;
+
+pub const M_TRIM_THRESHOLD: c_int = -1;
+pub const M_GRANULARITY: c_int = -2;
+pub const M_MMAP_THRESHOLD: c_int = -3;
+
+#[repr(C)]
+pub struct mallinfo {
+    pub arena: c_int,
+    pub ordblks: c_int,
+    pub smblks: c_int,
+    pub hblks: c_int,
+    pub hblkhd: c_int,
+    pub usmblks: c_int,
+    pub fsmblks: c_int,
+    pub uordblks: c_int,
+    pub fordblks: c_int,
+    pub keepcost: c_int,
+}
+
+#[repr(C)]
+pub struct mallinfo2 {
+    pub arena: size_t,
+    pub ordblks: size_t,
+    pub smblks: size_t,
+    pub hblks: size_t,
+    pub hblkhd: size_t,
+    pub usmblks: size_t,
+    pub fsmblks: size_t,
+    pub uordblks: size_t,
+    pub fordblks: size_t,
+    pub keepcost: size_t,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mallinfo() -> mallinfo {
+    // Like glibc, values that do not fit simply wrap around
+    let info = platform::alloc_info();
+    mallinfo {
+        arena: info.arena as c_int,
+        ordblks: info.ordblks as c_int,
+        smblks: info.smblks as c_int,
+        hblks: info.hblks as c_int,
+        hblkhd: info.hblkhd as c_int,
+        usmblks: info.usmblks as c_int,
+        fsmblks: info.fsmblks as c_int,
+        uordblks: info.uordblks as c_int,
+        fordblks: info.fordblks as c_int,
+        keepcost: info.keepcost as c_int,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mallinfo2() -> mallinfo2 {
+    let info = platform::alloc_info();
+    mallinfo2 {
+        arena: info.arena,
+        ordblks: info.ordblks,
+        smblks: info.smblks,
+        hblks: info.hblks,
+        hblkhd: info.hblkhd,
+        usmblks: info.usmblks,
+        fsmblks: info.fsmblks,
+        uordblks: info.uordblks,
+        fordblks: info.fordblks,
+        keepcost: info.keepcost,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn malloc_stats() {
+    let info = platform::alloc_info();
+    let mut w = platform::FileWriter(2);
+    let _ = writeln!(w, "max system bytes = {:>10}", info.usmblks);
+    let _ = writeln!(w, "system bytes     = {:>10}", info.arena + info.hblkhd);
+    let _ = writeln!(w, "in use bytes     = {:>10}", info.uordblks);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn malloc_trim(pad: size_t) -> c_int {
+    platform::alloc_trim(pad)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn malloc_usable_size(ptr: *mut c_void) -> size_t {
+    platform::alloc_usable_size(ptr)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mallopt(param: c_int, value: c_int) -> c_int {
+    platform::alloc_set_option(param, value)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn relibc_set_alloc_hooks(hooks: *const AllocHooks) {
+    platform::alloc_set_hooks(hooks);
+}
@@ -10,21 +10,28 @@ pub mod fnmatch;
 pub mod grp;
 pub mod inttypes;
 pub mod locale;
+pub mod malloc;
 pub mod netdb;
 pub mod netinet_in;
-//pub mod pthread;
+pub mod pthread;
+pub mod pty;
 pub mod pwd;
+pub mod sched;
 pub mod semaphore;
 pub mod setjmp;
 pub mod sgtty;
 pub mod signal;
+pub mod spawn;
+pub mod stdatomic;
 pub mod stdio;
 pub mod stdlib;
 pub mod string;
 pub mod strings;
+pub mod sys_auxv;
 pub mod sys_file;
 pub mod sys_ioctl;
 pub mod sys_mman;
+pub mod sys_random;
 pub mod sys_resource;
 pub mod sys_select;
 pub mod sys_socket;
@@ -35,6 +42,7 @@ pub mod sys_un;
 pub mod sys_utsname;
 pub mod sys_wait;
 pub mod termios;
+pub mod threads;
 pub mod time;
 pub mod unistd;
 pub mod utime;
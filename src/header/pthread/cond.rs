@@ -0,0 +1,193 @@
+use core::intrinsics;
+
+use header::errno::*;
+use header::time::constants::{CLOCK_MONOTONIC, CLOCK_REALTIME};
+use header::time::timespec;
+use platform;
+use platform::types::*;
+use sync;
+
+use super::{
+    pthread_cond_t, pthread_condattr_t, pthread_mutex_lock, pthread_mutex_t,
+    pthread_mutex_unlock, PTHREAD_PROCESS_PRIVATE, PTHREAD_PROCESS_SHARED,
+};
+
+/// What is in a pthread_cond_t, which is bigger to be compatible with glibc.
+/// PTHREAD_COND_INITIALIZER zeroes all of it, which is CLOCK_REALTIME.
+#[repr(C)]
+struct Cond {
+    seq: c_int,
+    clock: clockid_t,
+}
+
+/// What is in a pthread_condattr_t, which is only 4 bytes
+#[repr(C)]
+struct CondAttr {
+    pshared: u8,
+    clock: u8,
+    _reserved: [u8; 2],
+}
+
+impl Cond {
+    unsafe fn from_raw<'a>(cond: *mut pthread_cond_t) -> &'a mut Cond {
+        &mut *(cond as *mut Cond)
+    }
+}
+
+impl CondAttr {
+    unsafe fn from_raw<'a>(attr: *const pthread_condattr_t) -> &'a mut CondAttr {
+        &mut *(attr as *mut CondAttr)
+    }
+}
+
+unsafe fn wait(
+    cond: *mut pthread_cond_t,
+    mutex: *mut pthread_mutex_t,
+    abstime: Option<&timespec>,
+) -> c_int {
+    let cond = Cond::from_raw(cond);
+    let abstime =
+        abstime.map(|abstime| &*(abstime as *const _ as *const platform::types::timespec));
+
+    // Any signal after this point changes the sequence, so the futex wait
+    // below cannot miss it
+    let seq = intrinsics::atomic_load(&cond.seq);
+
+    let res = pthread_mutex_unlock(mutex);
+    if res != 0 {
+        return res;
+    }
+
+    let mut timed_out = false;
+    match abstime {
+        Some(abstime) => match sync::remaining(cond.clock, abstime) {
+            Some(timeout) => sync::futex_wait(&mut cond.seq, seq, Some(&timeout)),
+            None => timed_out = true,
+        },
+        None => sync::futex_wait(&mut cond.seq, seq, None),
+    }
+
+    pthread_mutex_lock(mutex);
+
+    let expired = |abstime| sync::remaining(cond.clock, abstime).is_none();
+    if timed_out || abstime.map_or(false, expired) {
+        ETIMEDOUT
+    } else {
+        0
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_cond_broadcast(cond: *mut pthread_cond_t) -> c_int {
+    let cond = Cond::from_raw(cond);
+    intrinsics::atomic_xadd(&mut cond.seq, 1);
+    sync::futex_wake(&mut cond.seq, c_int::max_value());
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn pthread_cond_destroy(cond: *mut pthread_cond_t) -> c_int {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_cond_init(
+    cond: *mut pthread_cond_t,
+    attr: *const pthread_condattr_t,
+) -> c_int {
+    *Cond::from_raw(cond) = Cond {
+        seq: 0,
+        clock: if attr.is_null() {
+            CLOCK_REALTIME
+        } else {
+            CondAttr::from_raw(attr).clock as clockid_t
+        },
+    };
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_cond_signal(cond: *mut pthread_cond_t) -> c_int {
+    let cond = Cond::from_raw(cond);
+    intrinsics::atomic_xadd(&mut cond.seq, 1);
+    sync::futex_wake(&mut cond.seq, 1);
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_cond_timedwait(
+    cond: *mut pthread_cond_t,
+    mutex: *mut pthread_mutex_t,
+    abstime: *const timespec,
+) -> c_int {
+    wait(cond, mutex, Some(&*abstime))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_cond_wait(
+    cond: *mut pthread_cond_t,
+    mutex: *mut pthread_mutex_t,
+) -> c_int {
+    wait(cond, mutex, None)
+}
+
+#[no_mangle]
+pub extern "C" fn pthread_condattr_destroy(attr: *mut pthread_condattr_t) -> c_int {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_condattr_getclock(
+    attr: *const pthread_condattr_t,
+    clock_id: *mut clockid_t,
+) -> c_int {
+    *clock_id = CondAttr::from_raw(attr).clock as clockid_t;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_condattr_getpshared(
+    attr: *const pthread_condattr_t,
+    pshared: *mut c_int,
+) -> c_int {
+    *pshared = CondAttr::from_raw(attr).pshared as c_int;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_condattr_init(attr: *mut pthread_condattr_t) -> c_int {
+    *CondAttr::from_raw(attr) = CondAttr {
+        pshared: PTHREAD_PROCESS_PRIVATE as u8,
+        clock: CLOCK_REALTIME as u8,
+        _reserved: [0; 2],
+    };
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_condattr_setclock(
+    attr: *mut pthread_condattr_t,
+    clock_id: clockid_t,
+) -> c_int {
+    match clock_id {
+        CLOCK_REALTIME | CLOCK_MONOTONIC => {
+            CondAttr::from_raw(attr).clock = clock_id as u8;
+            0
+        }
+        _ => EINVAL,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_condattr_setpshared(
+    attr: *mut pthread_condattr_t,
+    pshared: c_int,
+) -> c_int {
+    match pshared {
+        PTHREAD_PROCESS_PRIVATE | PTHREAD_PROCESS_SHARED => {
+            CondAttr::from_raw(attr).pshared = pshared as u8;
+            0
+        }
+        _ => EINVAL,
+    }
+}
@@ -0,0 +1,654 @@
+//! pthread.h implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/pthread.h.html
+
+use core::sync::atomic::spin_loop_hint;
+use core::{intrinsics, ptr};
+
+use header::errno::*;
+use header::sched::{sched_param, SCHED_FIFO, SCHED_OTHER, SCHED_RR};
+use platform;
+use platform::types::*;
+use platform::{Pal, Sys};
+use sync;
+
+pub use self::cond::*;
+pub use self::mutex::*;
+pub use self::thread::*;
+pub use self::tls::*;
+
+mod cond;
+mod mutex;
+mod thread;
+mod tls;
+
+pub const PTHREAD_BARRIER_SERIAL_THREAD: c_int = -1;
+
+pub const PTHREAD_CREATE_JOINABLE: c_int = 0;
+pub const PTHREAD_CREATE_DETACHED: c_int = 1;
+
+pub const PTHREAD_INHERIT_SCHED: c_int = 0;
+pub const PTHREAD_EXPLICIT_SCHED: c_int = 1;
+
+pub const PTHREAD_PROCESS_PRIVATE: c_int = 0;
+pub const PTHREAD_PROCESS_SHARED: c_int = 1;
+
+pub const PTHREAD_SCOPE_SYSTEM: c_int = 0;
+pub const PTHREAD_SCOPE_PROCESS: c_int = 1;
+
+pub const PTHREAD_STACK_MIN: usize = 16384;
+
+pub const PTHREAD_KEYS_MAX: usize = 128;
+pub const PTHREAD_DESTRUCTOR_ITERATIONS: c_int = 4;
+
+pub const PTHREAD_MUTEX_NORMAL: c_int = 0;
+pub const PTHREAD_MUTEX_RECURSIVE: c_int = 1;
+pub const PTHREAD_MUTEX_ERRORCHECK: c_int = 2;
+pub const PTHREAD_MUTEX_DEFAULT: c_int = PTHREAD_MUTEX_NORMAL;
+
+pub const PTHREAD_PRIO_NONE: c_int = 0;
+pub const PTHREAD_PRIO_INHERIT: c_int = 1;
+pub const PTHREAD_PRIO_PROTECT: c_int = 2;
+
+const PAGE_SIZE: usize = 4096;
+const DEFAULT_STACK_SIZE: usize = 2 * 1024 * 1024;
+
+pub type pthread_t = *mut c_void;
+pub type pthread_key_t = c_uint;
+pub type pthread_once_t = c_int;
+pub type pthread_spinlock_t = c_int;
+
+#[repr(C)]
+pub struct pthread_attr_t {
+    pub detachstate: c_int,
+    pub inheritsched: c_int,
+    pub schedpolicy: c_int,
+    pub scope: c_int,
+    pub guardsize: usize,
+    pub stackaddr: *mut c_void,
+    pub stacksize: usize,
+    pub param: sched_param,
+}
+
+#[repr(C)]
+pub struct pthread_barrier_t {
+    pub count: c_uint,
+    pub waiting: c_uint,
+    pub generation: c_int,
+    pub pshared: c_int,
+}
+
+#[repr(C)]
+pub struct pthread_barrierattr_t {
+    pub pshared: c_int,
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub union pthread_mutex_t {
+    pub size: [c_char; 40usize],
+    pub align: c_long,
+}
+impl Clone for pthread_mutex_t {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub union pthread_mutexattr_t {
+    pub size: [c_char; 4usize],
+    pub align: c_int,
+}
+impl Clone for pthread_mutexattr_t {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub union pthread_cond_t {
+    pub size: [c_char; 48usize],
+    pub align: c_longlong,
+}
+impl Clone for pthread_cond_t {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub union pthread_condattr_t {
+    pub size: [c_char; 4usize],
+    pub align: c_int,
+}
+impl Clone for pthread_condattr_t {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub union pthread_rwlock_t {
+    pub size: [c_char; 56usize],
+    pub align: c_long,
+}
+impl Clone for pthread_rwlock_t {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub union pthread_rwlockattr_t {
+    pub size: [c_char; 8usize],
+    pub align: c_long,
+}
+impl Clone for pthread_rwlockattr_t {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn pthread_attr_destroy(attr: *mut pthread_attr_t) -> c_int {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_getdetachstate(
+    attr: *const pthread_attr_t,
+    detachstate: *mut c_int,
+) -> c_int {
+    *detachstate = (*attr).detachstate;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_getguardsize(
+    attr: *const pthread_attr_t,
+    guardsize: *mut usize,
+) -> c_int {
+    *guardsize = (*attr).guardsize;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_getinheritsched(
+    attr: *const pthread_attr_t,
+    inheritsched: *mut c_int,
+) -> c_int {
+    *inheritsched = (*attr).inheritsched;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_getschedparam(
+    attr: *const pthread_attr_t,
+    param: *mut sched_param,
+) -> c_int {
+    *param = (*attr).param;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_getschedpolicy(
+    attr: *const pthread_attr_t,
+    policy: *mut c_int,
+) -> c_int {
+    *policy = (*attr).schedpolicy;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_getscope(
+    attr: *const pthread_attr_t,
+    contentionscope: *mut c_int,
+) -> c_int {
+    *contentionscope = (*attr).scope;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_getstackaddr(
+    attr: *const pthread_attr_t,
+    stackaddr: *mut *mut c_void,
+) -> c_int {
+    *stackaddr = (*attr).stackaddr;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_getstacksize(
+    attr: *const pthread_attr_t,
+    stacksize: *mut usize,
+) -> c_int {
+    *stacksize = (*attr).stacksize;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_init(attr: *mut pthread_attr_t) -> c_int {
+    *attr = pthread_attr_t {
+        detachstate: PTHREAD_CREATE_JOINABLE,
+        inheritsched: PTHREAD_INHERIT_SCHED,
+        schedpolicy: SCHED_OTHER,
+        scope: PTHREAD_SCOPE_SYSTEM,
+        guardsize: PAGE_SIZE,
+        stackaddr: ptr::null_mut(),
+        stacksize: DEFAULT_STACK_SIZE,
+        param: sched_param::default(),
+    };
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_setdetachstate(
+    attr: *mut pthread_attr_t,
+    detachstate: c_int,
+) -> c_int {
+    match detachstate {
+        PTHREAD_CREATE_JOINABLE | PTHREAD_CREATE_DETACHED => {
+            (*attr).detachstate = detachstate;
+            0
+        }
+        _ => EINVAL,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_setguardsize(
+    attr: *mut pthread_attr_t,
+    guardsize: usize,
+) -> c_int {
+    (*attr).guardsize = guardsize;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_setinheritsched(
+    attr: *mut pthread_attr_t,
+    inheritsched: c_int,
+) -> c_int {
+    match inheritsched {
+        PTHREAD_INHERIT_SCHED | PTHREAD_EXPLICIT_SCHED => {
+            (*attr).inheritsched = inheritsched;
+            0
+        }
+        _ => EINVAL,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_setschedparam(
+    attr: *mut pthread_attr_t,
+    param: *const sched_param,
+) -> c_int {
+    (*attr).param = *param;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_setschedpolicy(
+    attr: *mut pthread_attr_t,
+    policy: c_int,
+) -> c_int {
+    match policy {
+        SCHED_OTHER | SCHED_FIFO | SCHED_RR => {
+            (*attr).schedpolicy = policy;
+            0
+        }
+        _ => EINVAL,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_setscope(
+    attr: *mut pthread_attr_t,
+    contentionscope: c_int,
+) -> c_int {
+    match contentionscope {
+        PTHREAD_SCOPE_SYSTEM => {
+            (*attr).scope = contentionscope;
+            0
+        }
+        // Every thread is a kernel thread, there is no process scope
+        PTHREAD_SCOPE_PROCESS => EOPNOTSUPP,
+        _ => EINVAL,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_setstackaddr(
+    attr: *mut pthread_attr_t,
+    stackaddr: *mut c_void,
+) -> c_int {
+    (*attr).stackaddr = stackaddr;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_setstacksize(
+    attr: *mut pthread_attr_t,
+    stacksize: usize,
+) -> c_int {
+    if stacksize < PTHREAD_STACK_MIN {
+        return EINVAL;
+    }
+    (*attr).stacksize = stacksize;
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn pthread_barrier_destroy(barrier: *mut pthread_barrier_t) -> c_int {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_barrier_init(
+    barrier: *mut pthread_barrier_t,
+    attr: *const pthread_barrierattr_t,
+    count: c_uint,
+) -> c_int {
+    if count == 0 {
+        return EINVAL;
+    }
+    *barrier = pthread_barrier_t {
+        count,
+        waiting: 0,
+        generation: 0,
+        pshared: if attr.is_null() {
+            PTHREAD_PROCESS_PRIVATE
+        } else {
+            (*attr).pshared
+        },
+    };
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_barrier_wait(barrier: *mut pthread_barrier_t) -> c_int {
+    let barrier = &mut *barrier;
+    let generation = intrinsics::atomic_load(&barrier.generation);
+
+    if intrinsics::atomic_xadd(&mut barrier.waiting, 1) + 1 == barrier.count {
+        // Last one in: reset for the next round before releasing the others,
+        // they cannot re-enter until the generation changes
+        intrinsics::atomic_store(&mut barrier.waiting, 0);
+        intrinsics::atomic_xadd(&mut barrier.generation, 1);
+        Sys::futex(&mut barrier.generation, FUTEX_WAKE, c_int::max_value(), ptr::null());
+        return PTHREAD_BARRIER_SERIAL_THREAD;
+    }
+
+    while intrinsics::atomic_load(&barrier.generation) == generation {
+        Sys::futex(&mut barrier.generation, FUTEX_WAIT, generation, ptr::null());
+    }
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn pthread_barrierattr_destroy(attr: *mut pthread_barrierattr_t) -> c_int {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_barrierattr_getpshared(
+    attr: *const pthread_barrierattr_t,
+    pshared: *mut c_int,
+) -> c_int {
+    *pshared = (*attr).pshared;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_barrierattr_init(attr: *mut pthread_barrierattr_t) -> c_int {
+    (*attr).pshared = PTHREAD_PROCESS_PRIVATE;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_barrierattr_setpshared(
+    attr: *mut pthread_barrierattr_t,
+    pshared: c_int,
+) -> c_int {
+    match pshared {
+        PTHREAD_PROCESS_PRIVATE | PTHREAD_PROCESS_SHARED => {
+            (*attr).pshared = pshared;
+            0
+        }
+        _ => EINVAL,
+    }
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_cancel(thread: pthread_t) -> c_int {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_cleanup_push(routine: *mut c_void, arg: *mut c_void) {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_cleanup_pop(execute: c_int) {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_getconcurrency() -> c_int {
+    unimplemented!();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_getschedparam(
+    thread: pthread_t,
+    policy: *mut c_int,
+    param: *mut sched_param,
+) -> c_int {
+    let tid = thread::tid(thread);
+    let res = Sys::sched_getscheduler(tid);
+    if res < 0 {
+        return platform::errno;
+    }
+    if Sys::sched_getparam(tid, param as *mut platform::types::sched_param) < 0 {
+        return platform::errno;
+    }
+    *policy = res;
+    0
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_mutex_getprioceiling(
+    mutex: *const pthread_mutex_t,
+    prioceiling: *mut c_int,
+) -> c_int {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_mutex_setprioceiling(
+    mutex: *mut pthread_mutex_t,
+    prioceiling: c_int,
+    old_ceiling: *mut c_int,
+) -> c_int {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_mutexattr_getprioceiling(
+    attr: *const pthread_mutexattr_t,
+    prioceiling: *mut c_int,
+) -> c_int {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_mutexattr_setprioceiling(
+    attr: *mut pthread_mutexattr_t,
+    prioceiling: c_int,
+) -> c_int {
+    unimplemented!();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_once(
+    once_control: *mut pthread_once_t,
+    init_routine: Option<unsafe extern "C" fn()>,
+) -> c_int {
+    const INCOMPLETE: c_int = 0;
+    const RUNNING: c_int = 1;
+    const COMPLETE: c_int = 2;
+
+    loop {
+        match intrinsics::atomic_cxchg_acq(once_control, INCOMPLETE, RUNNING) {
+            (_, true) => {
+                if let Some(init_routine) = init_routine {
+                    init_routine();
+                }
+                intrinsics::atomic_store_rel(once_control, COMPLETE);
+                sync::futex_wake(once_control, c_int::max_value());
+                return 0;
+            }
+            (COMPLETE, false) => return 0,
+            (state, false) => sync::futex_wait(once_control, state, None),
+        }
+    }
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_rwlock_destroy(rwlock: *mut pthread_rwlock_t) -> c_int {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_rwlock_init(
+    rwlock: *mut pthread_rwlock_t,
+    attr: *const pthread_rwlockattr_t,
+) -> c_int {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_rwlock_rdlock(rwlock: *mut pthread_rwlock_t) -> c_int {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_rwlock_tryrdlock(rwlock: *mut pthread_rwlock_t) -> c_int {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_rwlock_trywrlock(rwlock: *mut pthread_rwlock_t) -> c_int {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_rwlock_unlock(rwlock: *mut pthread_rwlock_t) -> c_int {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_rwlock_wrlock(rwlock: *mut pthread_rwlock_t) -> c_int {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_rwlockattr_destroy(rwlock: *mut pthread_rwlockattr_t) -> c_int {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_rwlockattr_getpshared(
+    rwlock: *const pthread_rwlockattr_t,
+    pshared: *mut c_int,
+) -> c_int {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_rwlockattr_init(rwlock: *mut pthread_rwlockattr_t) -> c_int {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_rwlockattr_setpshared(
+    rwlock: *mut pthread_rwlockattr_t,
+    pshared: c_int,
+) -> c_int {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_setcancelstate(state: c_int, oldstate: *mut c_int) -> c_int {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_setcanceltype(type_: c_int, oldtype: *mut c_int) -> c_int {
+    unimplemented!();
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_setconcurrency(new_level: c_int) -> c_int {
+    unimplemented!();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_setschedparam(
+    thread: pthread_t,
+    policy: c_int,
+    param: *const sched_param,
+) -> c_int {
+    let param = param as *const platform::types::sched_param;
+    if Sys::sched_setscheduler(thread::tid(thread), policy, param) < 0 {
+        return platform::errno;
+    }
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn pthread_spin_destroy(lock: *mut pthread_spinlock_t) -> c_int {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_spin_init(lock: *mut pthread_spinlock_t, pshared: c_int) -> c_int {
+    // A spinlock is a plain word in memory, so it works across processes as is
+    *lock = 0;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_spin_lock(lock: *mut pthread_spinlock_t) -> c_int {
+    while !intrinsics::atomic_cxchg_acq(lock, 0, 1).1 {
+        while intrinsics::atomic_load_relaxed(lock) != 0 {
+            spin_loop_hint();
+        }
+    }
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_spin_trylock(lock: *mut pthread_spinlock_t) -> c_int {
+    if intrinsics::atomic_cxchg_acq(lock, 0, 1).1 {
+        0
+    } else {
+        EBUSY
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_spin_unlock(lock: *mut pthread_spinlock_t) -> c_int {
+    intrinsics::atomic_store_rel(lock, 0);
+    0
+}
+
+// #[no_mangle]
+pub extern "C" fn pthread_testcancel() {
+    unimplemented!();
+}
@@ -0,0 +1,225 @@
+use header::errno::*;
+use header::time::timespec;
+use platform;
+use platform::types::*;
+use sync;
+
+use super::thread;
+use super::{
+    pthread_mutex_t, pthread_mutexattr_t, PTHREAD_MUTEX_DEFAULT, PTHREAD_MUTEX_ERRORCHECK,
+    PTHREAD_MUTEX_NORMAL, PTHREAD_MUTEX_RECURSIVE, PTHREAD_PRIO_NONE, PTHREAD_PROCESS_PRIVATE,
+    PTHREAD_PROCESS_SHARED,
+};
+
+/// What is in a pthread_mutex_t, which is bigger to be compatible with glibc.
+/// PTHREAD_MUTEX_INITIALIZER zeroes all of it.
+#[repr(C)]
+struct Mutex {
+    lock: c_int,
+    kind: c_int,
+    owner: pid_t,
+    count: c_uint,
+}
+
+/// What is in a pthread_mutexattr_t, which is only 4 bytes
+#[repr(C)]
+struct MutexAttr {
+    kind: u8,
+    pshared: u8,
+    protocol: u8,
+    _reserved: u8,
+}
+
+impl Mutex {
+    unsafe fn from_raw<'a>(mutex: *mut pthread_mutex_t) -> &'a mut Mutex {
+        &mut *(mutex as *mut Mutex)
+    }
+}
+
+impl MutexAttr {
+    unsafe fn from_raw<'a>(attr: *const pthread_mutexattr_t) -> &'a mut MutexAttr {
+        &mut *(attr as *mut MutexAttr)
+    }
+}
+
+unsafe fn lock(mutex: *mut pthread_mutex_t, abstime: Option<&timespec>) -> c_int {
+    let mutex = Mutex::from_raw(mutex);
+    let tid = thread::current_tid();
+
+    if mutex.kind != PTHREAD_MUTEX_NORMAL && mutex.owner == tid {
+        if mutex.kind == PTHREAD_MUTEX_RECURSIVE {
+            mutex.count += 1;
+            return 0;
+        }
+        return EDEADLK;
+    }
+
+    let abstime =
+        abstime.map(|abstime| &*(abstime as *const _ as *const platform::types::timespec));
+    if !sync::futex_lock(&mut mutex.lock, abstime) {
+        return ETIMEDOUT;
+    }
+    mutex.owner = tid;
+    mutex.count = 1;
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn pthread_mutex_destroy(mutex: *mut pthread_mutex_t) -> c_int {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutex_init(
+    mutex: *mut pthread_mutex_t,
+    attr: *const pthread_mutexattr_t,
+) -> c_int {
+    *Mutex::from_raw(mutex) = Mutex {
+        lock: 0,
+        kind: if attr.is_null() {
+            PTHREAD_MUTEX_DEFAULT
+        } else {
+            MutexAttr::from_raw(attr).kind as c_int
+        },
+        owner: 0,
+        count: 0,
+    };
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutex_lock(mutex: *mut pthread_mutex_t) -> c_int {
+    lock(mutex, None)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutex_timedlock(
+    mutex: *mut pthread_mutex_t,
+    abstime: *const timespec,
+) -> c_int {
+    lock(mutex, Some(&*abstime))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutex_trylock(mutex: *mut pthread_mutex_t) -> c_int {
+    let mutex = Mutex::from_raw(mutex);
+    let tid = thread::current_tid();
+
+    if mutex.kind == PTHREAD_MUTEX_RECURSIVE && mutex.owner == tid {
+        mutex.count += 1;
+        return 0;
+    }
+
+    if !sync::futex_trylock(&mut mutex.lock) {
+        return EBUSY;
+    }
+    mutex.owner = tid;
+    mutex.count = 1;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutex_unlock(mutex: *mut pthread_mutex_t) -> c_int {
+    let mutex = Mutex::from_raw(mutex);
+
+    if mutex.kind != PTHREAD_MUTEX_NORMAL {
+        if mutex.owner != thread::current_tid() {
+            return EPERM;
+        }
+        if mutex.count > 1 {
+            mutex.count -= 1;
+            return 0;
+        }
+    }
+
+    mutex.owner = 0;
+    mutex.count = 0;
+    sync::futex_unlock(&mut mutex.lock);
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn pthread_mutexattr_destroy(attr: *mut pthread_mutexattr_t) -> c_int {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutexattr_getprotocol(
+    attr: *const pthread_mutexattr_t,
+    protocol: *mut c_int,
+) -> c_int {
+    *protocol = MutexAttr::from_raw(attr).protocol as c_int;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutexattr_getpshared(
+    attr: *const pthread_mutexattr_t,
+    pshared: *mut c_int,
+) -> c_int {
+    *pshared = MutexAttr::from_raw(attr).pshared as c_int;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutexattr_gettype(
+    attr: *const pthread_mutexattr_t,
+    type_: *mut c_int,
+) -> c_int {
+    *type_ = MutexAttr::from_raw(attr).kind as c_int;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutexattr_init(attr: *mut pthread_mutexattr_t) -> c_int {
+    *MutexAttr::from_raw(attr) = MutexAttr {
+        kind: PTHREAD_MUTEX_DEFAULT as u8,
+        pshared: PTHREAD_PROCESS_PRIVATE as u8,
+        protocol: PTHREAD_PRIO_NONE as u8,
+        _reserved: 0,
+    };
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutexattr_setprotocol(
+    attr: *mut pthread_mutexattr_t,
+    protocol: c_int,
+) -> c_int {
+    match protocol {
+        PTHREAD_PRIO_NONE => {
+            MutexAttr::from_raw(attr).protocol = protocol as u8;
+            0
+        }
+        // Priority inheritance and protection need kernel support
+        _ => EOPNOTSUPP,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutexattr_setpshared(
+    attr: *mut pthread_mutexattr_t,
+    pshared: c_int,
+) -> c_int {
+    match pshared {
+        PTHREAD_PROCESS_PRIVATE | PTHREAD_PROCESS_SHARED => {
+            MutexAttr::from_raw(attr).pshared = pshared as u8;
+            0
+        }
+        _ => EINVAL,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutexattr_settype(
+    attr: *mut pthread_mutexattr_t,
+    type_: c_int,
+) -> c_int {
+    match type_ {
+        PTHREAD_MUTEX_NORMAL | PTHREAD_MUTEX_RECURSIVE | PTHREAD_MUTEX_ERRORCHECK => {
+            MutexAttr::from_raw(attr).kind = type_ as u8;
+            0
+        }
+        _ => EINVAL,
+    }
+}
@@ -0,0 +1,295 @@
+use alloc::boxed::Box;
+use alloc::btree_set::BTreeSet;
+use alloc::Vec;
+use core::{intrinsics, mem, ptr};
+
+use header::errno::*;
+use header::sys_mman::{MAP_ANONYMOUS, MAP_PRIVATE, PROT_NONE, PROT_READ, PROT_WRITE};
+use platform;
+use platform::types::*;
+use platform::{Pal, Sys};
+use sync::{self, Mutex};
+
+use super::tls;
+use super::{
+    pthread_attr_init, pthread_attr_t, pthread_t, PAGE_SIZE, PTHREAD_CREATE_DETACHED,
+    PTHREAD_EXPLICIT_SCHED, PTHREAD_KEYS_MAX,
+};
+
+const JOINABLE: c_int = 0;
+const DETACHED: c_int = 1;
+const EXITED: c_int = 2;
+
+pub struct Thread {
+    /// Nonzero while the thread runs, cleared and woken up by the kernel
+    /// (see Pal::clone_thread) once it is off its stack for good, or by
+    /// pthread_exit for the main thread
+    alive: c_int,
+    state: c_int,
+    pub tid: pid_t,
+    start_routine: Option<unsafe extern "C" fn(arg1: *mut c_void) -> *mut c_void>,
+    arg: *mut c_void,
+    retval: *mut c_void,
+    /// Mapped by pthread_create with the guard below it, or null if the stack
+    /// is not ours to free
+    stack: *mut c_void,
+    stack_size: usize,
+    pub specific: [*mut c_void; PTHREAD_KEYS_MAX],
+}
+
+impl Thread {
+    fn new(tid: pid_t) -> Self {
+        Thread {
+            alive: 1,
+            state: JOINABLE,
+            tid,
+            start_routine: None,
+            arg: ptr::null_mut(),
+            retval: ptr::null_mut(),
+            stack: ptr::null_mut(),
+            stack_size: 0,
+            specific: [ptr::null_mut(); PTHREAD_KEYS_MAX],
+        }
+    }
+}
+
+lazy_static! {
+    // The threads that are running, for pthread_key_delete to go through
+    pub static ref THREADS: Mutex<BTreeSet<usize>> = Mutex::new(BTreeSet::new());
+}
+
+// Detached threads that have exited, but may still be running on their stacks
+static ZOMBIES: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+#[thread_local]
+static mut CURRENT: *mut Thread = ptr::null_mut();
+
+pub fn current() -> *mut Thread {
+    unsafe {
+        if CURRENT.is_null() {
+            // The main thread, which was not started by pthread_create
+            let thread = Box::into_raw(Box::new(Thread::new(Sys::gettid())));
+            THREADS.lock().insert(thread as usize);
+            CURRENT = thread;
+        }
+        CURRENT
+    }
+}
+
+/// The kernel id of the calling thread, without asking the kernel
+pub fn current_tid() -> pid_t {
+    unsafe { (*current()).tid }
+}
+
+/// The thread that called fork is the only one in the child, under a new id
+pub unsafe fn fork_child() {
+    if !CURRENT.is_null() {
+        (*CURRENT).tid = Sys::gettid();
+    }
+}
+
+unsafe fn wait_exit(thread: *mut Thread) {
+    loop {
+        let alive = intrinsics::atomic_load(&(*thread).alive);
+        if alive == 0 {
+            break;
+        }
+        sync::futex_wait(&mut (*thread).alive, alive, None);
+    }
+}
+
+unsafe fn free_thread(thread: *mut Thread) {
+    if !(*thread).stack.is_null() {
+        Sys::munmap((*thread).stack, (*thread).stack_size);
+    }
+    drop(Box::from_raw(thread));
+}
+
+unsafe fn reap_zombies() {
+    ZOMBIES.lock().retain(|&thread| {
+        let thread = thread as *mut Thread;
+        if intrinsics::atomic_load(&(*thread).alive) == 0 {
+            free_thread(thread);
+            false
+        } else {
+            true
+        }
+    });
+}
+
+extern "C" fn thread_start(arg: *mut c_void) {
+    let thread = arg as *mut Thread;
+    unsafe {
+        (*thread).tid = Sys::gettid();
+        CURRENT = thread;
+        THREADS.lock().insert(thread as usize);
+
+        let start_routine = (*thread).start_routine.unwrap();
+        pthread_exit(start_routine((*thread).arg));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_create(
+    thread: *mut pthread_t,
+    attr: *const pthread_attr_t,
+    start_routine: Option<unsafe extern "C" fn(arg1: *mut c_void) -> *mut c_void>,
+    arg: *mut c_void,
+) -> c_int {
+    if start_routine.is_none() {
+        return EINVAL;
+    }
+
+    reap_zombies();
+
+    let mut default_attr: pthread_attr_t = mem::uninitialized();
+    let attr = if attr.is_null() {
+        pthread_attr_init(&mut default_attr);
+        &default_attr
+    } else {
+        &*attr
+    };
+
+    let mut new = Thread::new(0);
+    new.start_routine = start_routine;
+    new.arg = arg;
+    if attr.detachstate == PTHREAD_CREATE_DETACHED {
+        new.state = DETACHED;
+    }
+
+    let stack_top = if attr.stackaddr.is_null() {
+        let guard = (attr.guardsize + PAGE_SIZE - 1) & !(PAGE_SIZE - 1);
+        let size = guard + attr.stacksize;
+        let stack = Sys::mmap(
+            ptr::null_mut(),
+            size,
+            PROT_READ | PROT_WRITE,
+            MAP_PRIVATE | MAP_ANONYMOUS,
+            -1,
+            0,
+        );
+        if stack as usize == !0 {
+            return EAGAIN;
+        }
+        // Where memory cannot be protected, the guard is only left unused
+        if guard > 0 && Sys::mprotect(stack, guard, PROT_NONE) < 0 && platform::errno != ENOSYS {
+            Sys::munmap(stack, size);
+            return EAGAIN;
+        }
+        new.stack = stack;
+        new.stack_size = size;
+        stack as usize + size
+    } else {
+        attr.stackaddr as usize + attr.stacksize
+    };
+
+    let new = Box::into_raw(Box::new(new));
+    let tid = Sys::clone_thread(
+        (stack_top & !0xF) as *mut c_void,
+        thread_start,
+        new as *mut c_void,
+        &mut (*new).alive,
+    );
+    if tid < 0 {
+        free_thread(new);
+        return EAGAIN;
+    }
+
+    // A detached thread may be gone already, but a joinable one has to wait
+    // for us to join it
+    if attr.detachstate != PTHREAD_CREATE_DETACHED {
+        (*new).tid = tid;
+    }
+    if attr.inheritsched == PTHREAD_EXPLICIT_SCHED {
+        let param = &attr.param as *const _ as *const sched_param;
+        Sys::sched_setscheduler(tid, attr.schedpolicy, param);
+    }
+
+    *thread = new as pthread_t;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_detach(thread: pthread_t) -> c_int {
+    let thread = thread as *mut Thread;
+    let (state, detached) = intrinsics::atomic_cxchg(&mut (*thread).state, JOINABLE, DETACHED);
+    if detached {
+        return 0;
+    }
+    match state {
+        DETACHED => EINVAL,
+        _ => {
+            // Already exited, so nobody else is going to free it
+            wait_exit(thread);
+            free_thread(thread);
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn pthread_equal(t1: pthread_t, t2: pthread_t) -> c_int {
+    (t1 == t2) as c_int
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_exit(value_ptr: *mut c_void) -> ! {
+    let thread = current();
+
+    tls::run_destructors(thread);
+    THREADS.lock().remove(&(thread as usize));
+
+    (*thread).retval = value_ptr;
+    if !intrinsics::atomic_cxchg(&mut (*thread).state, JOINABLE, EXITED).1 {
+        // Nobody is going to join a detached thread, so leave it for the next
+        // pthread_create to free once the kernel is done with the stack
+        ZOMBIES.lock().push(thread as usize);
+    }
+    if (*thread).start_routine.is_none() {
+        // The kernel does not know about the main thread's block, which may
+        // be freed as soon as this is cleared
+        intrinsics::atomic_store(&mut (*thread).alive, 0);
+        sync::futex_wake(&mut (*thread).alive, c_int::max_value());
+    }
+
+    platform::alloc_thread_exit();
+    Sys::exit_thread();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_getcpuclockid(
+    thread: pthread_t,
+    clock_id: *mut clockid_t,
+) -> c_int {
+    if Sys::thread_getcpuclockid((*(thread as *mut Thread)).tid, clock_id) < 0 {
+        return platform::errno;
+    }
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_join(thread: pthread_t, value_ptr: *mut *mut c_void) -> c_int {
+    let thread = thread as *mut Thread;
+    if thread == current() {
+        return EDEADLK;
+    }
+    if intrinsics::atomic_load(&(*thread).state) == DETACHED {
+        return EINVAL;
+    }
+
+    wait_exit(thread);
+    if !value_ptr.is_null() {
+        *value_ptr = (*thread).retval;
+    }
+    free_thread(thread);
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn pthread_self() -> pthread_t {
+    current() as pthread_t
+}
+
+pub unsafe fn tid(thread: pthread_t) -> pid_t {
+    (*(thread as *mut Thread)).tid
+}
@@ -0,0 +1,96 @@
+use core::ptr;
+
+use header::errno::*;
+use platform::types::*;
+use sync::Mutex;
+
+use super::thread::{self, Thread, THREADS};
+use super::{pthread_key_t, PTHREAD_DESTRUCTOR_ITERATIONS, PTHREAD_KEYS_MAX};
+
+#[derive(Clone, Copy)]
+struct Key {
+    in_use: bool,
+    destructor: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+}
+
+static KEYS: Mutex<[Key; PTHREAD_KEYS_MAX]> = Mutex::new(
+    [Key {
+        in_use: false,
+        destructor: None,
+    }; PTHREAD_KEYS_MAX],
+);
+
+pub unsafe fn run_destructors(thread: *mut Thread) {
+    for _ in 0..PTHREAD_DESTRUCTOR_ITERATIONS {
+        let mut called = false;
+        for key in 0..PTHREAD_KEYS_MAX {
+            let value = (*thread).specific[key];
+            if value.is_null() {
+                continue;
+            }
+            (*thread).specific[key] = ptr::null_mut();
+
+            // Don't hold the lock while the destructor runs, it may well
+            // create or delete keys itself
+            let destructor = KEYS.lock()[key].destructor;
+            if let Some(destructor) = destructor {
+                destructor(value);
+                called = true;
+            }
+        }
+        if !called {
+            break;
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_getspecific(key: pthread_key_t) -> *mut c_void {
+    match (*thread::current()).specific.get(key as usize) {
+        Some(value) => *value,
+        None => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_key_create(
+    key: *mut pthread_key_t,
+    destructor: Option<unsafe extern "C" fn(arg1: *mut c_void)>,
+) -> c_int {
+    let mut keys = KEYS.lock();
+    match keys.iter().position(|key| !key.in_use) {
+        Some(free) => {
+            keys[free] = Key {
+                in_use: true,
+                destructor,
+            };
+            *key = free as pthread_key_t;
+            0
+        }
+        None => EAGAIN,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_key_delete(key: pthread_key_t) -> c_int {
+    let mut keys = KEYS.lock();
+    if !keys.get(key as usize).map_or(false, |key| key.in_use) {
+        return EINVAL;
+    }
+    keys[key as usize].in_use = false;
+
+    // A future key with the same index must not see old values
+    for thread in THREADS.lock().iter() {
+        (*(*thread as *mut Thread)).specific[key as usize] = ptr::null_mut();
+    }
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_setspecific(key: pthread_key_t, value: *const c_void) -> c_int {
+    if !KEYS.lock().get(key as usize).map_or(false, |key| key.in_use) {
+        return EINVAL;
+    }
+    (*thread::current()).specific[key as usize] = value as *mut c_void;
+    0
+}
@@ -0,0 +1,44 @@
+use core::fmt::Write;
+
+use header::errno::ERANGE;
+use header::sys_ioctl::{TIOCGPTN, TIOCSCTTY, TIOCSPTLCK, TIOCSWINSZ};
+use platform;
+use platform::types::*;
+use platform::{Pal, StringWriter, Sys};
+
+pub const MASTER: &[u8] = b"/dev/ptmx\0";
+
+pub unsafe fn unlock(fd: c_int) -> c_int {
+    let mut lock: c_int = 0;
+    Sys::ioctl(fd, TIOCSPTLCK, &mut lock as *mut c_int as *mut c_void)
+}
+
+/// Write the nul terminated path of the slave side of `fd` to `buf`
+pub unsafe fn slave_name(fd: c_int, buf: &mut [u8]) -> c_int {
+    let mut number: c_uint = 0;
+    if Sys::ioctl(fd, TIOCGPTN, &mut number as *mut c_uint as *mut c_void) < 0 {
+        return -1;
+    }
+
+    let mut path = [0; 32];
+    let _ = write!(
+        StringWriter(path.as_mut_ptr(), path.len()),
+        "/dev/pts/{}",
+        number
+    );
+    let len = path.iter().position(|&b| b == 0).unwrap_or(path.len()) + 1;
+    if len > buf.len() {
+        platform::errno = ERANGE;
+        return -1;
+    }
+    buf[..len].copy_from_slice(&path[..len]);
+    0
+}
+
+pub unsafe fn set_winsize(fd: c_int, winp: *const winsize) -> c_int {
+    Sys::ioctl(fd, TIOCSWINSZ, winp as *mut c_void)
+}
+
+pub unsafe fn set_controlling_tty(fd: c_int) -> c_int {
+    Sys::ioctl(fd, TIOCSCTTY, 0 as *mut c_void)
+}
@@ -0,0 +1,117 @@
+//! pty.h implementation for Redox, following the BSD openpty(3) interface
+
+use c_str::CStr;
+use header::fcntl::{O_NOCTTY, O_RDWR};
+use header::stdlib::{posix_openpt, ptsname_r, unlockpt};
+use header::string::strcpy;
+use header::termios::{tcsetattr, termios, TCSANOW};
+use header::unistd::{_exit, close, dup2, fork, setsid};
+use platform::types::*;
+use platform::{Pal, Sys};
+
+#[cfg(target_os = "linux")]
+#[path = "linux.rs"]
+pub mod sys;
+
+#[cfg(target_os = "redox")]
+#[path = "redox.rs"]
+pub mod sys;
+
+/// Long enough for the path of any slave device
+pub const PTSNAME_LEN: usize = 32;
+
+#[no_mangle]
+pub unsafe extern "C" fn forkpty(
+    amaster: *mut c_int,
+    name: *mut c_char,
+    termp: *const termios,
+    winp: *const winsize,
+) -> pid_t {
+    let mut master = 0;
+    let mut slave = 0;
+    if openpty(&mut master, &mut slave, name, termp, winp) < 0 {
+        return -1;
+    }
+
+    let pid = fork();
+    match pid {
+        -1 => {
+            close(master);
+            close(slave);
+        }
+        0 => {
+            close(master);
+            if login_tty(slave) < 0 {
+                _exit(1);
+            }
+        }
+        _ => {
+            close(slave);
+            *amaster = master;
+        }
+    }
+    pid
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn login_tty(fd: c_int) -> c_int {
+    setsid();
+    if sys::set_controlling_tty(fd) < 0 {
+        return -1;
+    }
+
+    for stdio in 0..3 {
+        if dup2(fd, stdio) < 0 {
+            return -1;
+        }
+    }
+    if fd > 2 {
+        close(fd);
+    }
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn openpty(
+    amaster: *mut c_int,
+    aslave: *mut c_int,
+    name: *mut c_char,
+    termp: *const termios,
+    winp: *const winsize,
+) -> c_int {
+    let master = posix_openpt(O_RDWR | O_NOCTTY);
+    if master < 0 {
+        return -1;
+    }
+
+    let mut slave_name = [0; PTSNAME_LEN];
+    if unlockpt(master) < 0 || ptsname_r(master, slave_name.as_mut_ptr(), PTSNAME_LEN) != 0 {
+        close(master);
+        return -1;
+    }
+
+    let slave = Sys::open(
+        CStr::from_ptr(slave_name.as_ptr()),
+        O_RDWR | O_NOCTTY,
+        0,
+    );
+    if slave < 0 {
+        close(master);
+        return -1;
+    }
+
+    if (!termp.is_null() && tcsetattr(slave, TCSANOW as c_int, termp as *mut termios) < 0)
+        || (!winp.is_null() && sys::set_winsize(slave, winp) < 0)
+    {
+        close(slave);
+        close(master);
+        return -1;
+    }
+
+    if !name.is_null() {
+        strcpy(name, slave_name.as_ptr());
+    }
+    *amaster = master;
+    *aslave = slave;
+    0
+}
@@ -0,0 +1,45 @@
+use header::errno::ERANGE;
+use platform;
+use platform::types::*;
+use platform::{Pal, Sys};
+
+pub const MASTER: &[u8] = b"pty:\0";
+
+/// Handled by Sys::ioctl, which takes Linux numbers
+const TIOCSWINSZ: c_ulong = 0x5414;
+
+/// The slave side can be opened as soon as the master is
+pub unsafe fn unlock(_fd: c_int) -> c_int {
+    0
+}
+
+/// Write the nul terminated path of the slave side of `fd` to `buf`, which
+/// is the path of the master itself
+pub unsafe fn slave_name(fd: c_int, buf: &mut [u8]) -> c_int {
+    if buf.is_empty() {
+        platform::errno = ERANGE;
+        return -1;
+    }
+
+    let len = buf.len() - 1;
+    let count = Sys::fpath(fd, &mut buf[..len]);
+    if count < 0 {
+        return -1;
+    }
+    if count as usize == len {
+        // The path may not have fit
+        platform::errno = ERANGE;
+        return -1;
+    }
+    buf[count as usize] = 0;
+    0
+}
+
+pub unsafe fn set_winsize(fd: c_int, winp: *const winsize) -> c_int {
+    Sys::ioctl(fd, TIOCSWINSZ, winp as *mut c_void)
+}
+
+/// There are no controlling terminals
+pub unsafe fn set_controlling_tty(_fd: c_int) -> c_int {
+    0
+}
@@ -0,0 +1,93 @@
+//! sched implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/sched.h.html
+
+use core::{mem, ptr};
+
+use header::time::timespec;
+use platform;
+use platform::types::*;
+use platform::{Pal, Sys};
+
+pub const SCHED_OTHER: c_int = 0;
+pub const SCHED_FIFO: c_int = 1;
+pub const SCHED_RR: c_int = 2;
+
+pub const CPU_SETSIZE: c_int = 1024;
+
+// Need both here and in platform because cbindgen :(
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct sched_param {
+    pub sched_priority: c_int,
+}
+
+#[repr(C)]
+pub struct cpu_set_t {
+    pub __bits: [c_ulong; CPU_SETSIZE as usize / (8 * mem::size_of::<c_ulong>())],
+}
+
+#[no_mangle]
+pub extern "C" fn sched_get_priority_max(policy: c_int) -> c_int {
+    Sys::sched_get_priority_max(policy)
+}
+
+#[no_mangle]
+pub extern "C" fn sched_get_priority_min(policy: c_int) -> c_int {
+    Sys::sched_get_priority_min(policy)
+}
+
+#[no_mangle]
+pub extern "C" fn sched_getaffinity(pid: pid_t, cpusetsize: size_t, mask: *mut cpu_set_t) -> c_int {
+    Sys::sched_getaffinity(pid, cpusetsize, mask as *mut platform::types::cpu_set_t)
+}
+
+#[no_mangle]
+pub extern "C" fn sched_getcpu() -> c_int {
+    let mut cpu = 0;
+    if Sys::getcpu(&mut cpu, ptr::null_mut()) < 0 {
+        return -1;
+    }
+    cpu as c_int
+}
+
+#[no_mangle]
+pub extern "C" fn sched_getparam(pid: pid_t, param: *mut sched_param) -> c_int {
+    Sys::sched_getparam(pid, param as *mut platform::types::sched_param)
+}
+
+#[no_mangle]
+pub extern "C" fn sched_getscheduler(pid: pid_t) -> c_int {
+    Sys::sched_getscheduler(pid)
+}
+
+#[no_mangle]
+pub extern "C" fn sched_rr_get_interval(pid: pid_t, interval: *mut timespec) -> c_int {
+    Sys::sched_rr_get_interval(pid, interval as *mut platform::types::timespec)
+}
+
+#[no_mangle]
+pub extern "C" fn sched_setaffinity(
+    pid: pid_t,
+    cpusetsize: size_t,
+    mask: *const cpu_set_t,
+) -> c_int {
+    Sys::sched_setaffinity(pid, cpusetsize, mask as *const platform::types::cpu_set_t)
+}
+
+#[no_mangle]
+pub extern "C" fn sched_setparam(pid: pid_t, param: *const sched_param) -> c_int {
+    Sys::sched_setparam(pid, param as *const platform::types::sched_param)
+}
+
+#[no_mangle]
+pub extern "C" fn sched_setscheduler(
+    pid: pid_t,
+    policy: c_int,
+    param: *const sched_param,
+) -> c_int {
+    Sys::sched_setscheduler(pid, policy, param as *const platform::types::sched_param)
+}
+
+#[no_mangle]
+pub extern "C" fn sched_yield() -> c_int {
+    Sys::sched_yield()
+}
@@ -0,0 +1,503 @@
+//! spawn.h implementation, following http://pubs.opengroup.org/onlinepubs/9699919799/basedefs/spawn.h.html
+
+use alloc::boxed::Box;
+use alloc::Vec;
+use core::{mem, ptr};
+
+use c_str::CStr;
+use header::errno::*;
+use header::fcntl::{FD_CLOEXEC, F_GETFD, F_SETFD};
+use header::sched::sched_param;
+use header::signal::{self, sigset_t, NSIG, SIG_BLOCK, SIG_SETMASK};
+use header::sys_mman::{MAP_ANONYMOUS, MAP_PRIVATE, PROT_READ, PROT_WRITE};
+use header::unistd::{exec_search, setsid};
+use platform;
+use platform::types::*;
+use platform::{Pal, PalSignal, Sys};
+
+pub const POSIX_SPAWN_RESETIDS: c_short = 0x01;
+pub const POSIX_SPAWN_SETPGROUP: c_short = 0x02;
+pub const POSIX_SPAWN_SETSIGDEF: c_short = 0x04;
+pub const POSIX_SPAWN_SETSIGMASK: c_short = 0x08;
+pub const POSIX_SPAWN_SETSCHEDPARAM: c_short = 0x10;
+pub const POSIX_SPAWN_SETSCHEDULER: c_short = 0x20;
+pub const POSIX_SPAWN_USEVFORK: c_short = 0x40;
+pub const POSIX_SPAWN_SETSID: c_short = 0x80;
+
+const ALL_FLAGS: c_short = POSIX_SPAWN_RESETIDS
+    | POSIX_SPAWN_SETPGROUP
+    | POSIX_SPAWN_SETSIGDEF
+    | POSIX_SPAWN_SETSIGMASK
+    | POSIX_SPAWN_SETSCHEDPARAM
+    | POSIX_SPAWN_SETSCHEDULER
+    | POSIX_SPAWN_USEVFORK
+    | POSIX_SPAWN_SETSID;
+
+/// What the child runs on until it execs
+const STACK_SIZE: usize = 64 * 1024;
+
+#[repr(C)]
+pub struct posix_spawnattr_t {
+    pub flags: c_short,
+    pub pgroup: pid_t,
+    pub sigdefault: sigset_t,
+    pub sigmask: sigset_t,
+    pub schedpolicy: c_int,
+    pub schedparam: sched_param,
+}
+
+#[repr(C)]
+pub struct posix_spawn_file_actions_t {
+    /// A Vec<FileAction>, done in order in the child
+    pub actions: *mut c_void,
+}
+
+enum FileAction {
+    Close(c_int),
+    Dup2(c_int, c_int),
+    Open(c_int, Box<CStr>, c_int, mode_t),
+    Chdir(Box<CStr>),
+}
+
+unsafe fn push_action(file_actions: *mut posix_spawn_file_actions_t, action: FileAction) -> c_int {
+    let actions = &mut *((*file_actions).actions as *mut Vec<FileAction>);
+    actions.push(action);
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn_file_actions_init(
+    file_actions: *mut posix_spawn_file_actions_t,
+) -> c_int {
+    let actions: Box<Vec<FileAction>> = Box::new(Vec::new());
+    (*file_actions).actions = Box::into_raw(actions) as *mut c_void;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn_file_actions_destroy(
+    file_actions: *mut posix_spawn_file_actions_t,
+) -> c_int {
+    drop(Box::from_raw(
+        (*file_actions).actions as *mut Vec<FileAction>,
+    ));
+    (*file_actions).actions = ptr::null_mut();
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn_file_actions_addchdir_np(
+    file_actions: *mut posix_spawn_file_actions_t,
+    path: *const c_char,
+) -> c_int {
+    let path = CStr::from_ptr(path).into();
+    push_action(file_actions, FileAction::Chdir(path))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn_file_actions_addclose(
+    file_actions: *mut posix_spawn_file_actions_t,
+    fildes: c_int,
+) -> c_int {
+    if fildes < 0 {
+        return EBADF;
+    }
+    push_action(file_actions, FileAction::Close(fildes))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn_file_actions_adddup2(
+    file_actions: *mut posix_spawn_file_actions_t,
+    fildes: c_int,
+    newfildes: c_int,
+) -> c_int {
+    if fildes < 0 || newfildes < 0 {
+        return EBADF;
+    }
+    push_action(file_actions, FileAction::Dup2(fildes, newfildes))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn_file_actions_addopen(
+    file_actions: *mut posix_spawn_file_actions_t,
+    fildes: c_int,
+    path: *const c_char,
+    oflag: c_int,
+    mode: mode_t,
+) -> c_int {
+    if fildes < 0 {
+        return EBADF;
+    }
+    let path = CStr::from_ptr(path).into();
+    push_action(file_actions, FileAction::Open(fildes, path, oflag, mode))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_init(attr: *mut posix_spawnattr_t) -> c_int {
+    *attr = mem::zeroed();
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn posix_spawnattr_destroy(_attr: *mut posix_spawnattr_t) -> c_int {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_getflags(
+    attr: *const posix_spawnattr_t,
+    flags: *mut c_short,
+) -> c_int {
+    *flags = (*attr).flags;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_getpgroup(
+    attr: *const posix_spawnattr_t,
+    pgroup: *mut pid_t,
+) -> c_int {
+    *pgroup = (*attr).pgroup;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_getschedparam(
+    attr: *const posix_spawnattr_t,
+    param: *mut sched_param,
+) -> c_int {
+    ptr::copy_nonoverlapping(&(*attr).schedparam, param, 1);
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_getschedpolicy(
+    attr: *const posix_spawnattr_t,
+    policy: *mut c_int,
+) -> c_int {
+    *policy = (*attr).schedpolicy;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_getsigdefault(
+    attr: *const posix_spawnattr_t,
+    sigdefault: *mut sigset_t,
+) -> c_int {
+    *sigdefault = (*attr).sigdefault;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_getsigmask(
+    attr: *const posix_spawnattr_t,
+    sigmask: *mut sigset_t,
+) -> c_int {
+    *sigmask = (*attr).sigmask;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_setflags(
+    attr: *mut posix_spawnattr_t,
+    flags: c_short,
+) -> c_int {
+    if flags & !ALL_FLAGS != 0 {
+        return EINVAL;
+    }
+    (*attr).flags = flags;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_setpgroup(
+    attr: *mut posix_spawnattr_t,
+    pgroup: pid_t,
+) -> c_int {
+    (*attr).pgroup = pgroup;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_setschedparam(
+    attr: *mut posix_spawnattr_t,
+    param: *const sched_param,
+) -> c_int {
+    ptr::copy_nonoverlapping(param, &mut (*attr).schedparam, 1);
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_setschedpolicy(
+    attr: *mut posix_spawnattr_t,
+    policy: c_int,
+) -> c_int {
+    (*attr).schedpolicy = policy;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_setsigdefault(
+    attr: *mut posix_spawnattr_t,
+    sigdefault: *const sigset_t,
+) -> c_int {
+    (*attr).sigdefault = *sigdefault;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_setsigmask(
+    attr: *mut posix_spawnattr_t,
+    sigmask: *const sigset_t,
+) -> c_int {
+    (*attr).sigmask = *sigmask;
+    0
+}
+
+/// Everything the child needs, shared with the parent until the child execs
+struct Child {
+    path: *const c_char,
+    file_actions: *const posix_spawn_file_actions_t,
+    attr: *const posix_spawnattr_t,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+    search: bool,
+    /// The signal mask of the parent from before it blocked everything
+    sigmask: sigset_t,
+    /// Why the child could not exec, if it could not
+    error: c_int,
+}
+
+/// Put the signals of the child back to their defaults where the attributes
+/// ask for it, and where a handler of the parent would otherwise run in the
+/// child
+unsafe fn reset_signals(attr: Option<&posix_spawnattr_t>) {
+    let sigdefault = match attr {
+        Some(attr) if attr.flags & POSIX_SPAWN_SETSIGDEF != 0 => attr.sigdefault,
+        _ => 0,
+    };
+
+    let default: signal::sigaction = mem::zeroed();
+    for sig in 1..NSIG as c_int + 1 {
+        let mut old: signal::sigaction = mem::zeroed();
+        if signal::sigaction(sig, ptr::null(), &mut old) < 0 {
+            continue;
+        }
+        let handler = old.sa_handler.map_or(0, |handler| handler as usize);
+        // Neither default nor ignored
+        let caught = handler > 1;
+        if sigdefault & (1 << (sig - 1)) != 0 || caught {
+            signal::sigaction(sig, &default, ptr::null_mut());
+        }
+    }
+}
+
+/// Do what the attributes and file actions say, returning an error number
+/// if something fails
+unsafe fn setup(child: &Child) -> c_int {
+    let attr = if child.attr.is_null() {
+        None
+    } else {
+        Some(&*child.attr)
+    };
+    let flags = attr.map_or(0, |attr| attr.flags);
+
+    reset_signals(attr);
+
+    if let Some(attr) = attr {
+        if flags & POSIX_SPAWN_SETSID != 0 && setsid() < 0 {
+            return platform::errno;
+        }
+        if flags & POSIX_SPAWN_SETPGROUP != 0 && Sys::setpgid(0, attr.pgroup) < 0 {
+            return platform::errno;
+        }
+        let param = &attr.schedparam as *const _ as *const platform::types::sched_param;
+        let ret = if flags & POSIX_SPAWN_SETSCHEDULER != 0 {
+            Sys::sched_setscheduler(0, attr.schedpolicy, param)
+        } else if flags & POSIX_SPAWN_SETSCHEDPARAM != 0 {
+            Sys::sched_setparam(0, param)
+        } else {
+            0
+        };
+        if ret < 0 {
+            return platform::errno;
+        }
+        // Make the effective ids the real ones
+        if flags & POSIX_SPAWN_RESETIDS != 0
+            && (Sys::setregid(!0, Sys::getgid()) < 0 || Sys::setreuid(!0, Sys::getuid()) < 0)
+        {
+            return platform::errno;
+        }
+    }
+
+    if !child.file_actions.is_null() {
+        let actions = &*((*child.file_actions).actions as *const Vec<FileAction>);
+        for action in actions {
+            let ret = match *action {
+                // Closing a file that is not open is fine
+                FileAction::Close(fildes) => {
+                    Sys::close(fildes);
+                    0
+                }
+                // Keep the file open across exec
+                FileAction::Dup2(fildes, newfildes) if fildes == newfildes => {
+                    let fd_flags = Sys::fcntl(fildes, F_GETFD, 0);
+                    if fd_flags < 0 {
+                        -1
+                    } else {
+                        Sys::fcntl(fildes, F_SETFD, fd_flags & !FD_CLOEXEC)
+                    }
+                }
+                FileAction::Dup2(fildes, newfildes) => Sys::dup2(fildes, newfildes),
+                FileAction::Open(fildes, ref path, oflag, mode) => {
+                    let opened = Sys::open(path, oflag, mode);
+                    if opened < 0 || opened == fildes {
+                        opened
+                    } else {
+                        let ret = Sys::dup2(opened, fildes);
+                        Sys::close(opened);
+                        ret
+                    }
+                }
+                FileAction::Chdir(ref path) => Sys::chdir(path),
+            };
+            if ret < 0 {
+                return platform::errno;
+            }
+        }
+    }
+
+    let sigmask = if flags & POSIX_SPAWN_SETSIGMASK != 0 {
+        attr.map_or(child.sigmask, |attr| attr.sigmask)
+    } else {
+        child.sigmask
+    };
+    Sys::sigprocmask(SIG_SETMASK, &sigmask, ptr::null_mut());
+    0
+}
+
+extern "C" fn child_start(arg: *mut c_void) {
+    unsafe {
+        let child = &mut *(arg as *mut Child);
+        child.error = setup(child);
+        if child.error == 0 {
+            if child.search {
+                exec_search(child.path, child.argv, child.envp);
+            } else {
+                Sys::execve(CStr::from_ptr(child.path), child.argv, child.envp);
+            }
+            child.error = platform::errno;
+        }
+        Sys::exit(127);
+    }
+}
+
+/// Why spawn failed, as an error number
+pub enum SpawnError {
+    /// No child could be made
+    NoChild(c_int),
+    /// The child was made, but failed before it could exec or in exec. It
+    /// has been reaped.
+    Child(c_int),
+}
+
+/// posix_spawn, or posix_spawnp if `search` is set, returning the pid of the
+/// child
+pub unsafe fn spawn(
+    path: *const c_char,
+    file_actions: *const posix_spawn_file_actions_t,
+    attr: *const posix_spawnattr_t,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+    search: bool,
+) -> Result<pid_t, SpawnError> {
+    let stack = Sys::mmap(
+        ptr::null_mut(),
+        STACK_SIZE,
+        PROT_READ | PROT_WRITE,
+        MAP_PRIVATE | MAP_ANONYMOUS,
+        -1,
+        0,
+    );
+    if stack as usize == !0 {
+        return Err(SpawnError::NoChild(EAGAIN));
+    }
+
+    // Shared with the child, which runs on the stack mapped above while this
+    // one is suspended
+    let child = Box::into_raw(Box::new(Child {
+        path,
+        file_actions,
+        attr,
+        argv,
+        envp,
+        search,
+        sigmask: 0,
+        error: 0,
+    }));
+
+    // No handler of the parent may run in the child before it has reset them
+    let all: sigset_t = !0;
+    Sys::sigprocmask(SIG_BLOCK, &all, &mut (*child).sigmask);
+
+    let stack_top = (stack as usize + STACK_SIZE) & !0xF;
+    let child_pid = Sys::clone_vfork(stack_top as *mut c_void, child_start, child as *mut c_void);
+    let error = if child_pid < 0 {
+        platform::errno
+    } else {
+        (*child).error
+    };
+
+    Sys::sigprocmask(SIG_SETMASK, &(*child).sigmask, ptr::null_mut());
+    Sys::munmap(stack, STACK_SIZE);
+    drop(Box::from_raw(child));
+
+    if child_pid < 0 {
+        return Err(SpawnError::NoChild(error));
+    }
+    if error != 0 {
+        let mut status = 0;
+        Sys::waitpid(child_pid, &mut status, 0);
+        return Err(SpawnError::Child(error));
+    }
+    Ok(child_pid)
+}
+
+/// What posix_spawn returns for what spawn did
+unsafe fn spawn_result(pid: *mut pid_t, result: Result<pid_t, SpawnError>) -> c_int {
+    match result {
+        Ok(child_pid) => {
+            if !pid.is_null() {
+                *pid = child_pid;
+            }
+            0
+        }
+        Err(SpawnError::NoChild(error)) | Err(SpawnError::Child(error)) => error,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn(
+    pid: *mut pid_t,
+    path: *const c_char,
+    file_actions: *const posix_spawn_file_actions_t,
+    attr: *const posix_spawnattr_t,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+) -> c_int {
+    let result = spawn(path, file_actions, attr, argv, envp, false);
+    spawn_result(pid, result)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnp(
+    pid: *mut pid_t,
+    file: *const c_char,
+    file_actions: *const posix_spawn_file_actions_t,
+    attr: *const posix_spawnattr_t,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+) -> c_int {
+    let result = spawn(file, file_actions, attr, argv, envp, true);
+    spawn_result(pid, result)
+}
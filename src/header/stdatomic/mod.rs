@@ -0,0 +1,149 @@
+//! Out of line atomics backing stdatomic.h, following
+//! https://gcc.gnu.org/wiki/Atomic/GCCMM/LIbrary
+//!
+//! The compiler emits calls to these for objects it cannot operate on with
+//! native instructions, like large `_Atomic` structs. Each such object is
+//! guarded by a lock picked from a table by its address. Objects that
+//! __atomic_is_lock_free reports as lock free use the same native
+//! instructions the compiler would, so these mix with inline atomics on the
+//! same object.
+
+use core::{intrinsics, ptr, slice};
+
+use platform::types::*;
+use sync;
+
+const LOCK_COUNT: usize = 64;
+
+static mut LOCKS: [c_int; LOCK_COUNT] = [0; LOCK_COUNT];
+
+unsafe fn lock_for(addr: *const c_void) -> *mut c_int {
+    // Objects large enough to end up here are at least word aligned, so the
+    // lowest bits carry little information
+    let addr = addr as usize;
+    &mut LOCKS[((addr >> 4) ^ (addr >> 10)) % LOCK_COUNT]
+}
+
+/// Return `$body` from the calling function with `$t` as the unsigned type of
+/// `$size` bytes, if objects of that size at `$addr` are lock free
+macro_rules! lock_free {
+    ($size:expr, $addr:expr, $t:ident => $body:expr) => {
+        if __atomic_is_lock_free($size, $addr as *const c_void) {
+            match $size {
+                1 => {
+                    type $t = u8;
+                    return $body;
+                }
+                2 => {
+                    type $t = u16;
+                    return $body;
+                }
+                4 => {
+                    type $t = u32;
+                    return $body;
+                }
+                _ => {
+                    type $t = u64;
+                    return $body;
+                }
+            }
+        }
+    };
+}
+
+unsafe fn with_lock<T, F: FnOnce() -> T>(addr: *const c_void, f: F) -> T {
+    let lock = lock_for(addr);
+    sync::futex_lock(lock, None);
+    let res = f();
+    sync::futex_unlock(lock);
+    res
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn __atomic_compare_exchange(
+    size: size_t,
+    ptr: *mut c_void,
+    expected: *mut c_void,
+    desired: *mut c_void,
+    success: c_int,
+    failure: c_int,
+) -> bool {
+    lock_free!(size, ptr, T => {
+        let (old, ok) = intrinsics::atomic_cxchg(
+            ptr as *mut T,
+            ptr::read_unaligned(expected as *const T),
+            ptr::read_unaligned(desired as *const T),
+        );
+        if !ok {
+            ptr::write_unaligned(expected as *mut T, old);
+        }
+        ok
+    });
+    with_lock(ptr, || {
+        let current = slice::from_raw_parts(ptr as *const u8, size);
+        let expected = slice::from_raw_parts_mut(expected as *mut u8, size);
+        if current == expected {
+            ptr::copy_nonoverlapping(desired as *const u8, ptr as *mut u8, size);
+            true
+        } else {
+            expected.copy_from_slice(current);
+            false
+        }
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn __atomic_exchange(
+    size: size_t,
+    ptr: *mut c_void,
+    val: *mut c_void,
+    ret: *mut c_void,
+    model: c_int,
+) {
+    lock_free!(size, ptr, T => {
+        let old = intrinsics::atomic_xchg(ptr as *mut T, ptr::read_unaligned(val as *const T));
+        ptr::write_unaligned(ret as *mut T, old);
+    });
+    with_lock(ptr, || {
+        ptr::copy_nonoverlapping(ptr as *const u8, ret as *mut u8, size);
+        ptr::copy_nonoverlapping(val as *const u8, ptr as *mut u8, size);
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn __atomic_is_lock_free(size: size_t, ptr: *const c_void) -> bool {
+    match size {
+        1 | 2 | 4 | 8 => ptr as usize % size == 0,
+        _ => false,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn __atomic_load(
+    size: size_t,
+    src: *mut c_void,
+    dest: *mut c_void,
+    model: c_int,
+) {
+    lock_free!(size, src, T => {
+        ptr::write_unaligned(dest as *mut T, intrinsics::atomic_load(src as *const T));
+    });
+    with_lock(src, || {
+        ptr::copy_nonoverlapping(src as *const u8, dest as *mut u8, size);
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn __atomic_store(
+    size: size_t,
+    dest: *mut c_void,
+    src: *mut c_void,
+    model: c_int,
+) {
+    lock_free!(size, dest, T => {
+        intrinsics::atomic_store(dest as *mut T, ptr::read_unaligned(src as *const T));
+    });
+    with_lock(dest, || {
+        ptr::copy_nonoverlapping(src as *const u8, dest as *mut u8, size);
+    })
+}
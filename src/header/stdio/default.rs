@@ -26,6 +26,7 @@ lazy_static! {
         buf_char: -1,
         unget: UNGET,
         lock: AtomicBool::new(false),
+        pid: None,
     });
 
     #[allow(non_upper_case_globals)]
@@ -38,6 +39,7 @@ lazy_static! {
         buf_char: b'\n' as i8,
         unget: 0,
         lock: AtomicBool::new(false),
+        pid: None,
     });
 
     #[allow(non_upper_case_globals)]
@@ -50,6 +52,7 @@ lazy_static! {
         buf_char: -1,
         unget: 0,
         lock: AtomicBool::new(false),
+        pid: None,
     });
 }
 
@@ -75,6 +75,7 @@ pub unsafe fn _fdopen(fd: c_int, mode: *const c_char) -> Option<*mut FILE> {
         (*f).buf_char = -1;
         (*f).unget = UNGET;
         (*f).lock = AtomicBool::new(false);
+        (*f).pid = None;
         Some(f)
     }
 }
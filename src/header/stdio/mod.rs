@@ -4,12 +4,16 @@ use alloc::vec::Vec;
 use core::fmt::Write as WriteFmt;
 use core::fmt::{self, Error};
 use core::sync::atomic::{AtomicBool, Ordering};
-use core::{ptr, str};
+use core::{mem, ptr, str};
 use va_list::VaList as va_list;
 
 use c_str::CStr;
 use header::errno::{self, STR_ERROR};
 use header::fcntl;
+use header::spawn::{
+    posix_spawn, posix_spawn_file_actions_adddup2, posix_spawn_file_actions_destroy,
+    posix_spawn_file_actions_init, posix_spawn_file_actions_t,
+};
 use header::stdlib::mkstemp;
 use header::string::strlen;
 use platform;
@@ -42,6 +46,8 @@ pub struct FILE {
     buf_char: i8,
     lock: AtomicBool,
     unget: usize,
+    /// The child at the other end of a stream from popen
+    pid: Option<pid_t>,
 }
 
 impl FILE {
@@ -727,9 +733,24 @@ pub extern "C" fn getw(stream: &mut FILE) -> c_int {
     }
 }
 
-// #[no_mangle]
-pub extern "C" fn pclose(_stream: &mut FILE) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn pclose(stream: &mut FILE) -> c_int {
+    let pid = match stream.pid {
+        Some(pid) => pid,
+        None => {
+            errno = errno::ECHILD;
+            return -1;
+        }
+    };
+    fclose(stream);
+
+    let mut status = 0;
+    while Sys::waitpid(pid, &mut status, 0) < 0 {
+        if errno != errno::EINTR {
+            return -1;
+        }
+    }
+    status
 }
 
 #[no_mangle]
@@ -746,9 +767,72 @@ pub unsafe extern "C" fn perror(s: *const c_char) {
     }
 }
 
-// #[no_mangle]
-pub extern "C" fn popen(_command: *const c_char, _mode: *const c_char) -> *mut FILE {
-    unimplemented!();
+/// Run `command` with the shell, with a pipe from its standard output for
+/// mode "r" or to its standard input for mode "w"
+#[no_mangle]
+pub unsafe extern "C" fn popen(command: *const c_char, mode: *const c_char) -> *mut FILE {
+    let (stream_mode, child_fd) = match *mode as u8 {
+        b'r' => (b"r\0", 1),
+        b'w' => (b"w\0", 0),
+        _ => {
+            errno = errno::EINVAL;
+            return ptr::null_mut();
+        }
+    };
+
+    let mut fds = [-1; 2];
+    if Sys::pipe(&mut fds) < 0 {
+        return ptr::null_mut();
+    }
+    let (ours, theirs) = if child_fd == 0 {
+        (fds[1], fds[0])
+    } else {
+        (fds[0], fds[1])
+    };
+    // Streams from popen are not inherited by later children, as POSIX asks,
+    // so "e" makes no difference
+    Sys::fcntl(ours, fcntl::F_SETFD, fcntl::FD_CLOEXEC);
+    Sys::fcntl(theirs, fcntl::F_SETFD, fcntl::FD_CLOEXEC);
+
+    // Even if the pipe end already is child_fd, this keeps it open on exec
+    let mut file_actions: posix_spawn_file_actions_t = mem::uninitialized();
+    posix_spawn_file_actions_init(&mut file_actions);
+    posix_spawn_file_actions_adddup2(&mut file_actions, theirs, child_fd);
+
+    let args = [
+        b"sh\0".as_ptr() as *mut c_char,
+        b"-c\0".as_ptr() as *mut c_char,
+        command as *mut c_char,
+        ptr::null_mut(),
+    ];
+    let mut pid = 0;
+    let err = posix_spawn(
+        &mut pid,
+        b"/bin/sh\0".as_ptr() as *const c_char,
+        &file_actions,
+        ptr::null(),
+        args.as_ptr(),
+        platform::environ,
+    );
+    posix_spawn_file_actions_destroy(&mut file_actions);
+    Sys::close(theirs);
+    if err != 0 {
+        Sys::close(ours);
+        errno = err;
+        return ptr::null_mut();
+    }
+
+    match helpers::_fdopen(ours, stream_mode.as_ptr() as *const c_char) {
+        Some(stream) => {
+            (*stream).pid = Some(pid);
+            stream
+        }
+        None => {
+            Sys::close(ours);
+            Sys::waitpid(pid, ptr::null_mut(), 0);
+            ptr::null_mut()
+        }
+    }
 }
 
 /// Put a character `c` into `stream`
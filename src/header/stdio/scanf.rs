@@ -1,5 +1,6 @@
 use alloc::String;
 use alloc::Vec;
+use header::stdlib::strtod;
 use platform::types::*;
 use platform::Read;
 use va_list::VaList;
@@ -149,8 +150,8 @@ unsafe fn inner_scanf<R: Read>(
                         return Ok(matched);
                     }
                 }
-                b'd' | b'i' | b'o' | b'u' | b'x' | b'X' | b'f' | b'e' | b'g' | b'E' | b'a'
-                | b'p' => {
+                b'd' | b'i' | b'o' | b'u' | b'x' | b'X' | b'f' | b'F' | b'e' | b'E' | b'g'
+                | b'G' | b'a' | b'A' | b'p' => {
                     while (byte as char).is_whitespace() {
                         if !read!() {
                             return Ok(matched);
@@ -160,7 +161,10 @@ unsafe fn inner_scanf<R: Read>(
                     let pointer = c == b'p';
                     // Pointers aren't automatic, but we do want to parse "0x"
                     let auto = c == b'i' || pointer;
-                    let float = c == b'f' || c == b'e' || c == b'g' || c == b'E' || c == b'a';
+                    let float = match c {
+                        b'f' | b'F' | b'e' | b'E' | b'g' | b'G' | b'a' | b'A' => true,
+                        _ => false,
+                    };
 
                     let mut radix = match c {
                         b'o' => 8,
@@ -169,12 +173,28 @@ unsafe fn inner_scanf<R: Read>(
                     };
 
                     let mut n = String::new();
-                    let mut dot = false;
 
-                    while width.map(|w| w > 0).unwrap_or(true)
+                    if float {
+                        // Take everything that could still turn out to be a number
+                        while width.map(|w| w > 0).unwrap_or(true) {
+                            n.push(byte as char);
+                            if !strtod::is_prefix(n.as_bytes()) {
+                                n.pop();
+                                break;
+                            }
+                            width = width.map(|w| w - 1);
+                            if width.map(|w| w > 0).unwrap_or(true) {
+                                if !read!() {
+                                    break;
+                                }
+                            }
+                        }
+                    }
+
+                    while !float
+                        && width.map(|w| w > 0).unwrap_or(true)
                         && ((byte >= b'0' && byte <= b'7')
                             || (radix >= 10 && (byte >= b'8' && byte <= b'9'))
-                            || (float && !dot && byte == b'.')
                             || (radix == 16
                                 && ((byte >= b'a' && byte <= b'f')
                                     || (byte >= b'A' && byte <= b'F'))))
@@ -204,10 +224,6 @@ unsafe fn inner_scanf<R: Read>(
                             }
                             continue;
                         }
-                        if byte == b'.' {
-                            // Don't allow another dot
-                            dot = true;
-                        }
                         n.push(byte as char);
                         width = width.map(|w| w - 1);
                         if width.map(|w| w > 0).unwrap_or(true) {
@@ -218,22 +234,22 @@ unsafe fn inner_scanf<R: Read>(
                     }
 
                     macro_rules! parse_type {
-                        (noformat $type:ident) => {{
-                            let n = if n.is_empty() {
-                                0 as $type
-                            } else {
-                                n.parse::<$type>().map_err(|_| 0)?
-                            };
+                        (float $type:ident) => {{
+                            let parsed = strtod::parse::<$type>(n.as_bytes());
+                            if parsed.len != n.len() {
+                                // Something like "1e" or "0x" that never finished
+                                return Ok(matched);
+                            }
                             if !ignore {
-                                *ap.get::<*mut $type>() = n;
+                                *ap.get::<*mut $type>() = parsed.value;
                                 matched += 1;
                             }
                         }};
                         (c_double) => {
-                            parse_type!(noformat c_double);
+                            parse_type!(float c_double);
                         };
                         (c_float) => {
-                            parse_type!(noformat c_float);
+                            parse_type!(float c_float);
                         };
                         ($type:ident) => {
                             parse_type!($type, $type);
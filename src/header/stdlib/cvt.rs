@@ -0,0 +1,172 @@
+//! The digits behind ecvt, fcvt and gcvt, generated by core::fmt just like
+//! the digits printf writes
+
+use core::fmt::{self, Write};
+use core::str;
+
+use platform;
+use platform::types::*;
+
+/// More digits than this say nothing more about a double
+pub const NDIGIT_MAX: c_int = 17;
+/// Room for every integer digit of the largest double, NDIGIT_MAX
+/// fractional digits and a nul
+pub const CVT_BUF_LEN: usize = 309 + NDIGIT_MAX as usize + 1;
+
+/// Decimal digits with an implied decimal point
+pub struct Digits {
+    buf: [u8; CVT_BUF_LEN],
+    len: usize,
+    /// Where the decimal point goes, counted from the first digit
+    pub decpt: c_int,
+}
+
+impl Digits {
+    fn new() -> Self {
+        Digits {
+            buf: [0; CVT_BUF_LEN],
+            len: 0,
+            decpt: 0,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Take out `count` digits, starting with the one at `at`
+    fn remove(&mut self, at: usize, count: usize) {
+        for i in at..self.len - count {
+            self.buf[i] = self.buf[i + count];
+        }
+        self.len -= count;
+    }
+
+    fn push_zeros(&mut self, count: usize) {
+        for _ in 0..count {
+            self.buf[self.len] = b'0';
+            self.len += 1;
+        }
+    }
+}
+
+impl fmt::Write for Digits {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let s = s.as_bytes();
+        if self.len + s.len() > self.buf.len() {
+            return Err(fmt::Error);
+        }
+        self.buf[self.len..self.len + s.len()].copy_from_slice(s);
+        self.len += s.len();
+        Ok(())
+    }
+}
+
+/// The first `ndigit` significant digits of the finite, positive `value`
+pub fn exponential(value: c_double, ndigit: usize) -> Digits {
+    let mut digits = Digits::new();
+    if ndigit == 0 {
+        // Nothing to show, but the decimal point still has a place
+        digits.decpt = exponential(value, NDIGIT_MAX as usize).decpt;
+        return digits;
+    }
+
+    // Comes out as d.ddde-x
+    let _ = write!(digits, "{:.*e}", ndigit - 1, value);
+    let e = digits.as_bytes().iter().position(|&c| c == b'e').unwrap();
+    let exponent = str::from_utf8(&digits.buf[e + 1..digits.len])
+        .unwrap()
+        .parse::<c_int>()
+        .unwrap();
+    digits.len = e;
+    if ndigit > 1 {
+        digits.remove(1, 1);
+    }
+    digits.decpt = exponent + 1;
+    digits
+}
+
+/// The digits of the finite, positive `value` rounded to `ndigit` places
+/// after the decimal point, or before it if `ndigit` is negative
+pub fn fixed(value: c_double, ndigit: c_int) -> Digits {
+    if ndigit < 0 && value >= 1.0 {
+        // Keep at least one digit, however far to the left the rounding is
+        let decpt = exponential(value, NDIGIT_MAX as usize).decpt;
+        let mut digits = exponential(value, (decpt + ndigit).max(1) as usize);
+        let missing = digits.decpt as usize - digits.len;
+        digits.push_zeros(missing);
+        return digits;
+    }
+
+    let mut digits = Digits::new();
+    let _ = write!(digits, "{:.*}", ndigit.max(0) as usize, value);
+    let point = digits.as_bytes().iter().position(|&c| c == b'.');
+    match point {
+        Some(point) => {
+            digits.remove(point, 1);
+            digits.decpt = point as c_int;
+
+            // Leading zeros only say where the decimal point is
+            if value != 0.0 {
+                let zeros = digits.as_bytes().iter().take_while(|&&c| c == b'0').count();
+                digits.remove(0, zeros);
+                digits.decpt -= zeros as c_int;
+            }
+        }
+        None => digits.decpt = digits.len as c_int,
+    }
+    digits
+}
+
+/// Write `value` to `buf` as printf's %g would with the given precision,
+/// which must be at least one
+pub unsafe fn general(value: c_double, precision: c_int, buf: *mut u8) {
+    let mut w = platform::UnsafeStringWriter(buf);
+    if value.is_sign_negative() && !value.is_nan() {
+        w.write(b"-");
+    }
+    if value.is_nan() {
+        w.write(b"nan");
+        return;
+    } else if value.is_infinite() {
+        w.write(b"inf");
+        return;
+    }
+
+    let digits = exponential(value.abs(), precision as usize);
+    let decpt = digits.decpt;
+    let mut digits = digits.as_bytes();
+    while digits.len() > 1 && digits[digits.len() - 1] == b'0' {
+        digits = &digits[..digits.len() - 1];
+    }
+
+    let exponent = decpt - 1;
+    if exponent < -4 || exponent >= precision {
+        w.write(&digits[..1]);
+        if digits.len() > 1 {
+            w.write(b".");
+            w.write(&digits[1..]);
+        }
+        let _ = write!(
+            w,
+            "e{}{:02}",
+            if exponent < 0 { '-' } else { '+' },
+            exponent.abs()
+        );
+    } else if decpt <= 0 {
+        w.write(b"0.");
+        for _ in decpt..0 {
+            w.write(b"0");
+        }
+        w.write(digits);
+    } else if decpt as usize >= digits.len() {
+        w.write(digits);
+        for _ in digits.len()..decpt as usize {
+            w.write(b"0");
+        }
+    } else {
+        w.write(&digits[..decpt as usize]);
+        w.write(b".");
+        w.write(&digits[decpt as usize..]);
+    }
+}
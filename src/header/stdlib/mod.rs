@@ -1,34 +1,52 @@
 //! stdlib implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/stdlib.h.html
 
-use core::{intrinsics, iter, mem, ptr};
+use alloc::Vec;
+use core::{intrinsics, iter, mem, ptr, slice};
 use rand::distributions::Alphanumeric;
 use rand::prng::XorShiftRng;
-use rand::rngs::JitterRng;
 use rand::{Rng, SeedableRng};
 
 use c_str::CStr;
 use header::errno::*;
 use header::fcntl::*;
+use header::signal::{self, sigset_t, SIGCHLD, SIGINT, SIGQUIT, SIG_BLOCK, SIG_SETMASK};
+use header::spawn::{
+    posix_spawnattr_destroy, posix_spawnattr_init, posix_spawnattr_setflags,
+    posix_spawnattr_setsigdefault, posix_spawnattr_setsigmask, posix_spawnattr_t, spawn,
+    SpawnError, POSIX_SPAWN_SETSIGDEF, POSIX_SPAWN_SETSIGMASK,
+};
 use header::string::*;
-use header::time::constants::CLOCK_MONOTONIC;
 use header::wchar::*;
-use header::{ctype, errno, unistd};
+use header::{ctype, errno, pty, sys_random};
 use platform;
 use platform::types::*;
 use platform::{Pal, Sys};
 
+mod cvt;
+mod rand48;
+mod random;
 mod sort;
+pub mod strtod;
 
 pub const EXIT_FAILURE: c_int = 1;
 pub const EXIT_SUCCESS: c_int = 0;
 pub const RAND_MAX: c_int = 2147483647;
 
+/// Longest path realpath produces, including the terminating nul
+const PATH_MAX: usize = 4096;
+/// Number of symbolic links realpath follows before giving up with ELOOP
+const SYMLOOP_MAX: usize = 40;
+/// What the handler of an ignored signal is set to
+const SIG_IGN: usize = 1;
+
 //Maximum number of bytes in a multibyte character for the current locale
 pub const MB_CUR_MAX: c_int = 4;
 //Maximum number of bytes in a multibyte characters for any locale
 pub const MB_LEN_MAX: c_int = 4;
 
 static mut ATEXIT_FUNCS: [Option<extern "C" fn()>; 32] = [None; 32];
+/// The result of ecvt and fcvt
+static mut CVT_BUF: [u8; cvt::CVT_BUF_LEN] = [0; cvt::CVT_BUF_LEN];
 static mut RNG: Option<XorShiftRng> = None;
 
 #[no_mangle]
@@ -77,6 +95,38 @@ pub unsafe extern "C" fn aligned_alloc(alignment: size_t, size: size_t) -> *mut
     memalign(alignment, size)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn arc4random() -> uint32_t {
+    let mut value = 0;
+    arc4random_buf(&mut value as *mut uint32_t as *mut c_void, mem::size_of::<uint32_t>());
+    value
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn arc4random_buf(buf: *mut c_void, nbytes: size_t) {
+    // There is no way to report failure, and going on without randomness
+    // would be worse than not going on at all
+    if !sys_random::fill(slice::from_raw_parts_mut(buf as *mut u8, nbytes)) {
+        abort();
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn arc4random_uniform(upper_bound: uint32_t) -> uint32_t {
+    if upper_bound < 2 {
+        return 0;
+    }
+    // Values below 2^32 % upper_bound would make the low remainders more
+    // likely, so draw again until we get one that is not
+    let min = upper_bound.wrapping_neg() % upper_bound;
+    loop {
+        let value = arc4random();
+        if value >= min {
+            return value % upper_bound;
+        }
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn atexit(func: Option<extern "C" fn()>) -> c_int {
     for i in 0..ATEXIT_FUNCS.len() {
@@ -181,6 +231,11 @@ pub unsafe extern "C" fn calloc(nelem: size_t, elsize: size_t) -> *mut c_void {
     ptr
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn canonicalize_file_name(name: *const c_char) -> *mut c_char {
+    realpath(name, ptr::null_mut())
+}
+
 #[repr(C)]
 pub struct div_t {
     quot: c_int,
@@ -195,24 +250,76 @@ pub extern "C" fn div(numer: c_int, denom: c_int) -> div_t {
     }
 }
 
-// #[no_mangle]
-pub extern "C" fn drand48() -> c_double {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn drand48() -> c_double {
+    rand48::to_double(rand48::next(rand48::XSUBI.as_mut_ptr()))
 }
 
-// #[no_mangle]
-pub extern "C" fn ecvt(
+#[no_mangle]
+pub unsafe extern "C" fn ecvt(
     value: c_double,
     ndigit: c_int,
     decpt: *mut c_int,
     sign: *mut c_int,
 ) -> *mut c_char {
-    unimplemented!();
+    let buf = CVT_BUF.as_mut_ptr() as *mut c_char;
+    ecvt_r(value, ndigit, decpt, sign, buf, CVT_BUF.len());
+    buf
 }
 
-// #[no_mangle]
-pub extern "C" fn erand(xsubi: [c_ushort; 3]) -> c_double {
-    unimplemented!();
+/// Store the digits of `value` in `buf`, for ecvt_r and fcvt_r
+unsafe fn cvt_r<F: FnOnce(c_double) -> cvt::Digits>(
+    value: c_double,
+    decpt: *mut c_int,
+    sign: *mut c_int,
+    buf: *mut c_char,
+    len: size_t,
+    digits: F,
+) -> c_int {
+    if buf.is_null() {
+        platform::errno = EINVAL;
+        return -1;
+    }
+
+    *sign = (value.is_sign_negative() && !value.is_nan()) as c_int;
+    if value.is_finite() {
+        let digits = digits(value.abs());
+        *decpt = digits.decpt;
+        copy_digits(digits.as_bytes(), buf, len)
+    } else {
+        *decpt = 0;
+        copy_digits(if value.is_nan() { b"nan" } else { b"inf" }, buf, len)
+    }
+}
+
+unsafe fn copy_digits(digits: &[u8], buf: *mut c_char, len: size_t) -> c_int {
+    if digits.len() >= len {
+        platform::errno = ERANGE;
+        return -1;
+    }
+    ptr::copy_nonoverlapping(digits.as_ptr() as *const c_char, buf, digits.len());
+    *buf.offset(digits.len() as isize) = 0;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ecvt_r(
+    value: c_double,
+    ndigit: c_int,
+    decpt: *mut c_int,
+    sign: *mut c_int,
+    buf: *mut c_char,
+    len: size_t,
+) -> c_int {
+    let ndigit = ndigit.max(0).min(cvt::NDIGIT_MAX) as usize;
+    cvt_r(value, decpt, sign, buf, len, |value| {
+        cvt::exponential(value, ndigit)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn erand48(xsubi: *mut c_ushort) -> c_double {
+    rand48::to_double(rand48::next(xsubi))
 }
 
 #[no_mangle]
@@ -223,17 +330,34 @@ pub unsafe extern "C" fn exit(status: c_int) {
         }
     }
 
+    platform::alloc_leak_report();
+
     Sys::exit(status);
 }
 
-// #[no_mangle]
-pub extern "C" fn fcvt(
+#[no_mangle]
+pub unsafe extern "C" fn fcvt(
     value: c_double,
     ndigit: c_int,
     decpt: *mut c_int,
     sign: *mut c_int,
 ) -> *mut c_char {
-    unimplemented!();
+    let buf = CVT_BUF.as_mut_ptr() as *mut c_char;
+    fcvt_r(value, ndigit, decpt, sign, buf, CVT_BUF.len());
+    buf
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn fcvt_r(
+    value: c_double,
+    ndigit: c_int,
+    decpt: *mut c_int,
+    sign: *mut c_int,
+    buf: *mut c_char,
+    len: size_t,
+) -> c_int {
+    let ndigit = ndigit.min(cvt::NDIGIT_MAX);
+    cvt_r(value, decpt, sign, buf, len, |value| cvt::fixed(value, ndigit))
 }
 
 #[no_mangle]
@@ -241,9 +365,10 @@ pub unsafe extern "C" fn free(ptr: *mut c_void) {
     platform::free(ptr);
 }
 
-// #[no_mangle]
-pub extern "C" fn gcvt(value: c_double, ndigit: c_int, buf: *mut c_char) -> *mut c_char {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn gcvt(value: c_double, ndigit: c_int, buf: *mut c_char) -> *mut c_char {
+    cvt::general(value, ndigit.max(1).min(cvt::NDIGIT_MAX), buf as *mut u8);
+    buf
 }
 
 unsafe fn find_env(search: *const c_char) -> Option<(usize, *mut c_char)> {
@@ -286,33 +411,93 @@ pub unsafe extern "C" fn getenv(name: *const c_char) -> *mut c_char {
     find_env(name).map(|val| val.1).unwrap_or(ptr::null_mut())
 }
 
-// #[no_mangle]
-pub extern "C" fn getsubopt(
+#[no_mangle]
+pub unsafe extern "C" fn getsubopt(
     optionp: *mut *mut c_char,
     tokens: *const *mut c_char,
     valuep: *mut *mut c_char,
 ) -> c_int {
-    unimplemented!();
+    let option = *optionp;
+    if *option == 0 {
+        return -1;
+    }
+
+    // Cut the suboption off and move on to the next one
+    let end = option.offset(strcspn(option, ",\0".as_ptr() as *const c_char) as isize);
+    *optionp = if *end == 0 {
+        end
+    } else {
+        *end = 0;
+        end.offset(1)
+    };
+
+    let name_len = strcspn(option, "=\0".as_ptr() as *const c_char);
+    let mut i = 0;
+    while !(*tokens.offset(i)).is_null() {
+        let token = *tokens.offset(i);
+        if strlen(token) == name_len && strncmp(token, option, name_len) == 0 {
+            *valuep = if *option.offset(name_len as isize) == 0 {
+                ptr::null_mut()
+            } else {
+                option.offset(name_len as isize + 1)
+            };
+            return i as c_int;
+        }
+        i += 1;
+    }
+
+    // Hand back the whole thing, for the caller to complain about
+    *valuep = option;
+    -1
 }
 
-// #[no_mangle]
-pub extern "C" fn grantpt(fildes: c_int) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn grantpt(fildes: c_int) -> c_int {
+    // The slave device already belongs to whoever opened the master, so all
+    // there is left to do is to make sure that this is a master
+    let mut name = [0; pty::PTSNAME_LEN];
+    pty::sys::slave_name(fildes, &mut name)
 }
 
-// #[no_mangle]
-pub extern "C" fn initstate(seec: c_uint, state: *mut c_char, size: size_t) -> *mut c_char {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn initstate(seed: c_uint, state: *mut c_char, size: size_t) -> *mut c_char {
+    let kind = match random::kind(size) {
+        Some(kind) => kind,
+        None => {
+            platform::errno = EINVAL;
+            return ptr::null_mut();
+        }
+    };
+    let mut rng = random::lock();
+    let old = rng.save();
+    rng.init(seed, state, kind);
+    old
 }
 
-// #[no_mangle]
-pub extern "C" fn jrand48(xsubi: [c_ushort; 3]) -> c_long {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn jrand48(xsubi: *mut c_ushort) -> c_long {
+    rand48::to_signed(rand48::next(xsubi))
 }
 
-// #[no_mangle]
-pub extern "C" fn l64a(value: c_long) -> *mut c_char {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn l64a(value: c_long) -> *mut c_char {
+    static mut BUF: [c_char; 7] = [0; 7];
+
+    // The inverse of a64l, least significant digit first
+    let mut value = value as u32;
+    let mut i = 0;
+    while value != 0 {
+        let digit = (value & 0x3F) as u8;
+        BUF[i] = match digit {
+            0...11 => b'.' + digit,
+            12...37 => b'A' + digit - 12,
+            _ => b'a' + digit - 38,
+        } as c_char;
+        value >>= 6;
+        i += 1;
+    }
+    BUF[i] = 0;
+    BUF.as_mut_ptr()
 }
 
 #[no_mangle]
@@ -320,9 +505,11 @@ pub extern "C" fn labs(i: c_long) -> c_long {
     i.abs()
 }
 
-// #[no_mangle]
-pub extern "C" fn lcong48(param: [c_ushort; 7]) {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn lcong48(param: *mut c_ushort) {
+    rand48::set(rand48::XSUBI.as_mut_ptr(), rand48::get(param));
+    rand48::A = rand48::get(param.offset(3));
+    rand48::C = *param.offset(6);
 }
 
 #[repr(C)]
@@ -339,9 +526,9 @@ pub extern "C" fn ldiv(numer: c_long, denom: c_long) -> ldiv_t {
     }
 }
 
-// #[no_mangle]
-pub extern "C" fn lrand48() -> c_long {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn lrand48() -> c_long {
+    rand48::to_unsigned(rand48::next(rand48::XSUBI.as_mut_ptr()))
 }
 
 #[no_mangle]
@@ -405,8 +592,11 @@ where
         }
     }
 
-    let mut rng = JitterRng::new_with_timer(get_nstime);
-    rng.test_timer();
+    let mut seed = [0; 16];
+    if !sys_random::fill(&mut seed) {
+        return None;
+    }
+    let mut rng = XorShiftRng::from_seed(seed);
 
     for _ in 0..100 {
         let char_iter = iter::repeat(())
@@ -449,12 +639,6 @@ pub extern "C" fn mktemp(name: *mut c_char) -> *mut c_char {
     name
 }
 
-fn get_nstime() -> u64 {
-    let mut ts: timespec = unsafe { mem::uninitialized() };
-    Sys::clock_gettime(CLOCK_MONOTONIC, &mut ts);
-    ts.tv_nsec as u64
-}
-
 #[no_mangle]
 pub extern "C" fn mkostemps(name: *mut c_char, suffix_len: c_int, mut flags: c_int) -> c_int {
     flags &= !O_ACCMODE;
@@ -485,19 +669,39 @@ pub extern "C" fn mkstemps(name: *mut c_char, suffix_len: c_int) -> c_int {
     mkostemps(name, suffix_len, 0)
 }
 
-// #[no_mangle]
-pub extern "C" fn mrand48() -> c_long {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn mrand48() -> c_long {
+    rand48::to_signed(rand48::next(rand48::XSUBI.as_mut_ptr()))
 }
 
-// #[no_mangle]
-pub extern "C" fn nrand48(xsubi: [c_ushort; 3]) -> c_long {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn nrand48(xsubi: *mut c_ushort) -> c_long {
+    rand48::to_unsigned(rand48::next(xsubi))
 }
 
-// #[no_mangle]
-pub extern "C" fn ptsname(fildes: c_int) -> *mut c_char {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn posix_openpt(oflag: c_int) -> c_int {
+    Sys::open(CStr::from_bytes_with_nul_unchecked(pty::sys::MASTER), oflag, 0)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ptsname(fildes: c_int) -> *mut c_char {
+    static mut NAME: [c_char; pty::PTSNAME_LEN] = [0; pty::PTSNAME_LEN];
+    if ptsname_r(fildes, NAME.as_mut_ptr(), NAME.len()) == 0 {
+        NAME.as_mut_ptr()
+    } else {
+        ptr::null_mut()
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ptsname_r(fildes: c_int, buf: *mut c_char, buflen: size_t) -> c_int {
+    let buf = slice::from_raw_parts_mut(buf as *mut u8, buflen);
+    if pty::sys::slave_name(fildes, buf) < 0 {
+        platform::errno
+    } else {
+        0
+    }
 }
 
 #[no_mangle]
@@ -550,14 +754,22 @@ pub unsafe extern "C" fn rand() -> c_int {
     }
 }
 
-// #[no_mangle]
-pub extern "C" fn rand_r(seed: *mut c_uint) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn rand_r(seed: *mut c_uint) -> c_int {
+    // Three steps of an LCG, keeping only the better high bits of each
+    let step = |seed: *mut c_uint| {
+        *seed = (*seed).wrapping_mul(1103515245).wrapping_add(12345);
+        *seed / 65536
+    };
+    let mut result = step(seed) % 2048;
+    result = (result << 10) ^ (step(seed) % 1024);
+    result = (result << 10) ^ (step(seed) % 1024);
+    result as c_int
 }
 
-// #[no_mangle]
-pub extern "C" fn random() -> c_long {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn random() -> c_long {
+    random::lock().next()
 }
 
 #[no_mangle]
@@ -565,14 +777,168 @@ pub unsafe extern "C" fn realloc(ptr: *mut c_void, size: size_t) -> *mut c_void
     platform::realloc(ptr, size)
 }
 
-// #[no_mangle]
-pub extern "C" fn realpath(file_name: *const c_char, resolved_name: *mut c_char) -> *mut c_char {
-    unimplemented!();
+/// Fail with ENOTDIR unless `path` (empty for the root) is a directory
+unsafe fn check_dir(path: &[u8]) -> Result<(), c_int> {
+    let mut path = path.to_vec();
+    if path.is_empty() {
+        path.push(b'/');
+    }
+    path.push(0);
+
+    let fd = Sys::open(CStr::from_bytes_with_nul_unchecked(&path), O_PATH | O_DIRECTORY, 0);
+    if fd < 0 {
+        return Err(platform::errno);
+    }
+    Sys::close(fd);
+    Ok(())
 }
 
-// #[no_mangle]
-pub extern "C" fn seed48(seed16v: [c_ushort; 3]) -> c_ushort {
-    unimplemented!();
+/// Turn `path` into an absolute path without any `.` or `..` components,
+/// repeated slashes or symbolic links
+unsafe fn resolve_path(path: &[u8]) -> Result<Vec<u8>, c_int> {
+    if path.is_empty() {
+        return Err(ENOENT);
+    }
+
+    // Resolved so far, without the trailing slash, so the root is empty
+    let mut resolved = Vec::new();
+    if path[0] != b'/' {
+        let mut cwd = [0; PATH_MAX];
+        if Sys::getcwd(cwd.as_mut_ptr(), PATH_MAX).is_null() {
+            return Err(platform::errno);
+        }
+        let len = strlen(cwd.as_ptr());
+        if len > 1 {
+            resolved.extend(cwd[..len].iter().map(|&c| c as u8));
+        }
+    }
+
+    let mut rest = path.to_vec();
+    let mut pos = 0;
+    let mut links = 0;
+    // Set when the last component has to be a directory, as something
+    // follows it, but no lookup through it has made sure of that yet
+    let mut needs_dir = false;
+
+    loop {
+        while pos < rest.len() && rest[pos] == b'/' {
+            pos += 1;
+        }
+        if pos == rest.len() {
+            break;
+        }
+        let start = pos;
+        while pos < rest.len() && rest[pos] != b'/' {
+            pos += 1;
+        }
+
+        match &rest[start..pos] {
+            b"." | b".." => {
+                if needs_dir {
+                    check_dir(&resolved)?;
+                    needs_dir = false;
+                }
+                if &rest[start..pos] == b".." {
+                    let parent = resolved.iter().rposition(|&b| b == b'/').unwrap_or(0);
+                    resolved.truncate(parent);
+                }
+                continue;
+            }
+            _ => (),
+        }
+
+        let parent_len = resolved.len();
+        resolved.push(b'/');
+        resolved.extend_from_slice(&rest[start..pos]);
+        if resolved.len() >= PATH_MAX {
+            return Err(ENAMETOOLONG);
+        }
+
+        resolved.push(0);
+        let mut target = [0; PATH_MAX];
+        let count = Sys::readlink(CStr::from_bytes_with_nul_unchecked(&resolved), &mut target);
+        resolved.pop();
+
+        if count >= 0 {
+            links += 1;
+            if links > SYMLOOP_MAX {
+                return Err(ELOOP);
+            }
+            if count as usize == PATH_MAX {
+                return Err(ENAMETOOLONG);
+            }
+
+            // Carry on with the target of the link in place of its name
+            let target = &target[..count as usize];
+            resolved.truncate(parent_len);
+            if target.first() == Some(&b'/') {
+                resolved.clear();
+            }
+            let mut next = target.to_vec();
+            next.push(b'/');
+            next.extend_from_slice(&rest[pos..]);
+            rest = next;
+            pos = 0;
+            needs_dir = false;
+        } else if platform::errno == EINVAL {
+            needs_dir = pos < rest.len();
+        } else {
+            return Err(platform::errno);
+        }
+    }
+
+    if needs_dir {
+        check_dir(&resolved)?;
+    }
+    if resolved.is_empty() {
+        resolved.push(b'/');
+    }
+    Ok(resolved)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn realpath(
+    file_name: *const c_char,
+    resolved_name: *mut c_char,
+) -> *mut c_char {
+    if file_name.is_null() {
+        platform::errno = EINVAL;
+        return ptr::null_mut();
+    }
+
+    let mut path = match resolve_path(CStr::from_ptr(file_name).to_bytes()) {
+        Ok(path) => path,
+        Err(err) => {
+            platform::errno = err;
+            return ptr::null_mut();
+        }
+    };
+    path.push(0);
+    if path.len() > PATH_MAX {
+        platform::errno = ENAMETOOLONG;
+        return ptr::null_mut();
+    }
+
+    let buf = if resolved_name.is_null() {
+        let buf = platform::alloc(path.len()) as *mut c_char;
+        if buf.is_null() {
+            platform::errno = ENOMEM;
+            return ptr::null_mut();
+        }
+        buf
+    } else {
+        resolved_name
+    };
+    ptr::copy_nonoverlapping(path.as_ptr() as *const c_char, buf, path.len());
+    buf
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn seed48(seed16v: *mut c_ushort) -> *mut c_ushort {
+    static mut OLD: [c_ushort; 3] = [0; 3];
+    OLD = rand48::XSUBI;
+    rand48::reset(rand48::get(seed16v));
+    OLD.as_mut_ptr()
 }
 
 #[no_mangle]
@@ -647,9 +1013,16 @@ pub extern "C" fn setkey(key: *const c_char) {
     unimplemented!();
 }
 
-// #[no_mangle]
-pub extern "C" fn setstate(state: *const c_char) -> *mut c_char {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn setstate(state: *mut c_char) -> *mut c_char {
+    let mut rng = random::lock();
+    let old = rng.save();
+    if rng.load(state) {
+        old
+    } else {
+        platform::errno = EINVAL;
+        ptr::null_mut()
+    }
 }
 
 #[no_mangle]
@@ -657,57 +1030,25 @@ pub unsafe extern "C" fn srand(seed: c_uint) {
     RNG = Some(XorShiftRng::from_seed([seed as u8; 16]));
 }
 
-// #[no_mangle]
-pub extern "C" fn srand48(seed: c_long) {
-    unimplemented!();
-}
-
-// #[no_mangle]
-pub extern "C" fn srandom(seed: c_uint) {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn srand48(seed: c_long) {
+    rand48::seed(seed);
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn strtod(mut s: *const c_char, endptr: *mut *mut c_char) -> c_double {
-    while ctype::isspace(*s as c_int) != 0 {
-        s = s.offset(1);
-    }
-
-    let mut result = 0.0;
-    let mut radix = 10;
-
-    let negative = match *s as u8 {
-        b'-' => {
-            s = s.offset(1);
-            true
-        }
-        b'+' => {
-            s = s.offset(1);
-            false
-        }
-        _ => false,
-    };
-
-    if *s as u8 == b'0' && *s.offset(1) as u8 == b'x' {
-        s = s.offset(2);
-        radix = 16;
-    }
+pub unsafe extern "C" fn srandom(seed: c_uint) {
+    random::lock().seed(seed);
+}
 
-    while let Some(digit) = (*s as u8 as char).to_digit(radix) {
-        result *= radix as c_double;
-        result += digit as c_double;
-        s = s.offset(1);
+unsafe fn strto_float<F: strtod::Float>(s: *const c_char, endptr: *mut *mut c_char) -> F {
+    let mut start = s;
+    while ctype::isspace(*start as c_int) != 0 {
+        start = start.offset(1);
     }
 
-    if *s as u8 == b'.' {
-        s = s.offset(1);
-
-        let mut i = 1.0;
-        while let Some(digit) = (*s as u8 as char).to_digit(radix) {
-            i *= radix as c_double;
-            result += digit as c_double / i;
-            s = s.offset(1);
-        }
+    let parsed = strtod::parse::<F>(slice::from_raw_parts(start as *const u8, strlen(start)));
+    if parsed.out_of_range {
+        platform::errno = ERANGE;
     }
 
     if !endptr.is_null() {
@@ -715,14 +1056,23 @@ pub unsafe extern "C" fn strtod(mut s: *const c_char, endptr: *mut *mut c_char)
         // const input but mut output, yet the man page says
         // "stores the address of the first invalid character in *endptr"
         // so obviously it doesn't want us to clone it.
-        *endptr = s as *mut _;
+        *endptr = if parsed.len == 0 {
+            s as *mut _
+        } else {
+            start.offset(parsed.len as isize) as *mut _
+        };
     }
+    parsed.value
+}
 
-    if negative {
-        -result
-    } else {
-        result
-    }
+#[no_mangle]
+pub unsafe extern "C" fn strtod(s: *const c_char, endptr: *mut *mut c_char) -> c_double {
+    strto_float(s, endptr)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn strtof(s: *const c_char, endptr: *mut *mut c_char) -> c_float {
+    strto_float(s, endptr)
 }
 
 pub fn is_positive(ch: c_char) -> Option<(bool, isize)> {
@@ -734,49 +1084,31 @@ pub fn is_positive(ch: c_char) -> Option<(bool, isize)> {
     }
 }
 
-pub fn detect_base(s: *const c_char) -> Option<(c_int, isize)> {
-    let first = unsafe { *s } as u8;
-    match first {
-        0 => None,
-        b'0' => {
-            let second = unsafe { *s.offset(1) } as u8;
-            if second == b'X' || second == b'x' {
-                Some((16, 2))
-            } else if second >= b'0' && second <= b'7' {
-                Some((8, 1))
-            } else {
-                // in this case, the prefix (0) is going to be the number
-                Some((8, 0))
-            }
-        }
-        _ => Some((10, 0)),
-    }
-}
-
-pub unsafe fn convert_octal(s: *const c_char) -> Option<(c_ulong, isize, bool)> {
-    if *s != 0 && *s == b'0' as c_char {
-        if let Some((val, idx, overflow)) = convert_integer(s.offset(1), 8) {
-            Some((val, idx + 1, overflow))
-        } else {
-            // in case the prefix is not actually a prefix
-            Some((0, 1, false))
+/// The base a number is written in when strto* is asked to work it out
+pub unsafe fn detect_base(s: *const c_char) -> c_int {
+    if *s == b'0' as c_char {
+        match *s.offset(1) as u8 {
+            b'x' | b'X' => 16,
+            _ => 8,
         }
     } else {
-        None
+        10
     }
 }
 
-pub unsafe fn convert_hex(s: *const c_char) -> Option<(c_ulong, isize, bool)> {
-    if (*s != 0 && *s == b'0' as c_char)
-        && (*s.offset(1) != 0 && (*s.offset(1) == b'x' as c_char || *s.offset(1) == b'X' as c_char))
-    {
-        convert_integer(s.offset(2), 16).map(|(val, idx, overflow)| (val, idx + 2, overflow))
-    } else {
-        None
+pub unsafe fn convert_hex(s: *const c_char) -> Option<(uintmax_t, isize, bool)> {
+    if *s == b'0' as c_char && (*s.offset(1) == b'x' as c_char || *s.offset(1) == b'X' as c_char) {
+        if let Some((val, idx, overflow)) = convert_integer(s.offset(2), 16) {
+            return Some((val, idx + 2, overflow));
+        }
+        // in case the prefix is not actually a prefix, the 0 is the number
     }
+    convert_integer(s, 16)
 }
 
-pub fn convert_integer(s: *const c_char, base: c_int) -> Option<(c_ulong, isize, bool)> {
+/// Read digits in `base`, returning their value (saturated if it overflowed),
+/// their number and whether it overflowed
+pub fn convert_integer(s: *const c_char, base: c_int) -> Option<(uintmax_t, isize, bool)> {
     // -1 means the character is invalid
     #[cfg_attr(rustfmt, rustfmt_skip)]
     const LOOKUP_TABLE: [c_long; 256] = [
@@ -798,7 +1130,7 @@ pub fn convert_integer(s: *const c_char, base: c_int) -> Option<(c_ulong, isize,
         -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
     ];
 
-    let mut num: c_ulong = 0;
+    let mut num: uintmax_t = 0;
     let mut idx = 0;
     let mut overflowed = false;
 
@@ -811,15 +1143,12 @@ pub fn convert_integer(s: *const c_char, base: c_int) -> Option<(c_ulong, isize,
             break;
         } else {
             if let Some(res) = num
-                .checked_mul(base as c_ulong)
-                .and_then(|num| num.checked_add(val as c_ulong))
+                .checked_mul(base as uintmax_t)
+                .and_then(|num| num.checked_add(val as uintmax_t))
             {
                 num = res;
             } else {
-                unsafe {
-                    platform::errno = ERANGE;
-                }
-                num = c_ulong::max_value();
+                num = uintmax_t::max_value();
                 overflowed = true;
             }
 
@@ -869,8 +1198,16 @@ pub unsafe extern "C" fn strtoull(
     s: *const c_char,
     endptr: *mut *mut c_char,
     base: c_int,
-) -> c_ulong {
-    strtoul(s, endptr, base)
+) -> c_ulonglong {
+    strto_impl!(
+        c_ulonglong,
+        false,
+        c_ulonglong::max_value(),
+        c_ulonglong::min_value(),
+        s,
+        endptr,
+        base
+    )
 }
 
 #[no_mangle]
@@ -878,52 +1215,98 @@ pub unsafe extern "C" fn strtoll(
     s: *const c_char,
     endptr: *mut *mut c_char,
     base: c_int,
-) -> c_long {
-    strtol(s, endptr, base)
+) -> c_longlong {
+    strto_impl!(
+        c_longlong,
+        true,
+        c_longlong::max_value(),
+        c_longlong::min_value(),
+        s,
+        endptr,
+        base
+    )
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn system(command: *const c_char) -> c_int {
-    let child_pid = unistd::fork();
-    if child_pid == 0 {
-        let command_nonnull = if command.is_null() {
-            "exit 0\0".as_ptr()
-        } else {
-            command as *const u8
-        };
-
-        let shell = "/bin/sh\0".as_ptr();
-
-        let args = [
-            "sh\0".as_ptr(),
-            "-c\0".as_ptr(),
-            command_nonnull,
-            ptr::null(),
-        ];
-
-        unistd::execv(shell as *const c_char, args.as_ptr() as *const *mut c_char);
-
-        exit(127);
+    // Asks whether there is a shell
+    if command.is_null() {
+        return (system(b"exit 0\0".as_ptr() as *const c_char) == 0) as c_int;
+    }
 
-        unreachable!();
-    } else {
-        let mut wstatus = 0;
-        if Sys::waitpid(child_pid, &mut wstatus, 0) == !0 {
-            return -1;
+    // Only the child is interrupted from the terminal while it runs, and
+    // nothing but this reaps it
+    let mut ignore: signal::sigaction = mem::zeroed();
+    ignore.sa_handler = Some(mem::transmute(SIG_IGN));
+    let mut old_int: signal::sigaction = mem::zeroed();
+    let mut old_quit: signal::sigaction = mem::zeroed();
+    signal::sigaction(SIGINT as c_int, &ignore, &mut old_int);
+    signal::sigaction(SIGQUIT as c_int, &ignore, &mut old_quit);
+    let mut chld: sigset_t = 0;
+    let mut old_mask: sigset_t = 0;
+    signal::sigaddset(&mut chld, SIGCHLD as c_int);
+    signal::sigprocmask(SIG_BLOCK, &chld, &mut old_mask);
+
+    // The child gets back what was changed here
+    let mut attr: posix_spawnattr_t = mem::uninitialized();
+    posix_spawnattr_init(&mut attr);
+    let mut sigdefault: sigset_t = 0;
+    if old_int.sa_handler.map(|handler| handler as usize) != Some(SIG_IGN) {
+        signal::sigaddset(&mut sigdefault, SIGINT as c_int);
+    }
+    if old_quit.sa_handler.map(|handler| handler as usize) != Some(SIG_IGN) {
+        signal::sigaddset(&mut sigdefault, SIGQUIT as c_int);
+    }
+    posix_spawnattr_setsigdefault(&mut attr, &sigdefault);
+    posix_spawnattr_setsigmask(&mut attr, &old_mask);
+    posix_spawnattr_setflags(&mut attr, POSIX_SPAWN_SETSIGDEF | POSIX_SPAWN_SETSIGMASK);
+
+    let args = [
+        b"sh\0".as_ptr() as *mut c_char,
+        b"-c\0".as_ptr() as *mut c_char,
+        command as *mut c_char,
+        ptr::null_mut(),
+    ];
+    let result = spawn(
+        b"/bin/sh\0".as_ptr() as *const c_char,
+        ptr::null(),
+        &attr,
+        args.as_ptr(),
+        platform::environ,
+        false,
+    );
+    posix_spawnattr_destroy(&mut attr);
+
+    let mut status = -1;
+    match result {
+        Ok(pid) => {
+            while Sys::waitpid(pid, &mut status, 0) < 0 {
+                if platform::errno != EINTR {
+                    status = -1;
+                    break;
+                }
+            }
         }
-
-        wstatus
+        // As if the shell could not run the command
+        Err(SpawnError::Child(_)) => status = 127 << 8,
+        Err(SpawnError::NoChild(err)) => platform::errno = err,
     }
+
+    signal::sigaction(SIGINT as c_int, &old_int, ptr::null_mut());
+    signal::sigaction(SIGQUIT as c_int, &old_quit, ptr::null_mut());
+    signal::sigprocmask(SIG_SETMASK, &old_mask, ptr::null_mut());
+    status
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn ttyslot() -> c_int {
-    unimplemented!();
+    // There is no utmp database to find the terminal in
+    -1
 }
 
-// #[no_mangle]
-pub extern "C" fn unlockpt(fildes: c_int) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn unlockpt(fildes: c_int) -> c_int {
+    pty::sys::unlock(fildes)
 }
 
 #[no_mangle]
@@ -0,0 +1,55 @@
+//! The 48-bit linear congruential generator behind the drand48 family,
+//! X(n+1) = (a * X(n) + c) mod 2^48
+
+use platform::types::*;
+
+pub const DEFAULT_A: u64 = 0x5_DEEC_E66D;
+pub const DEFAULT_C: u16 = 0xB;
+const SEED_LOW: c_ushort = 0x330E;
+
+pub static mut A: u64 = DEFAULT_A;
+pub static mut C: u16 = DEFAULT_C;
+/// The state used by the functions that do not take one of their own
+pub static mut XSUBI: [c_ushort; 3] = [0; 3];
+
+pub unsafe fn get(xsubi: *const c_ushort) -> u64 {
+    *xsubi as u64 | (*xsubi.offset(1) as u64) << 16 | (*xsubi.offset(2) as u64) << 32
+}
+
+pub unsafe fn set(xsubi: *mut c_ushort, x: u64) {
+    *xsubi = x as c_ushort;
+    *xsubi.offset(1) = (x >> 16) as c_ushort;
+    *xsubi.offset(2) = (x >> 32) as c_ushort;
+}
+
+/// Advance the state in `xsubi` and return the new one
+pub unsafe fn next(xsubi: *mut c_ushort) -> u64 {
+    let x = A.wrapping_mul(get(xsubi)).wrapping_add(C as u64) & 0xFFFF_FFFF_FFFF;
+    set(xsubi, x);
+    x
+}
+
+pub unsafe fn reset(x: u64) {
+    set(XSUBI.as_mut_ptr(), x);
+    A = DEFAULT_A;
+    C = DEFAULT_C;
+}
+
+pub unsafe fn seed(seed: c_long) {
+    reset((seed as u32 as u64) << 16 | SEED_LOW as u64);
+}
+
+/// Uniformly distributed over [0.0, 1.0), using all 48 bits
+pub fn to_double(x: u64) -> c_double {
+    x as c_double / (1u64 << 48) as c_double
+}
+
+/// Uniformly distributed over [0, 2^31)
+pub fn to_unsigned(x: u64) -> c_long {
+    (x >> 17) as c_long
+}
+
+/// Uniformly distributed over [-2^31, 2^31)
+pub fn to_signed(x: u64) -> c_long {
+    (x >> 16) as u32 as i32 as c_long
+}
@@ -0,0 +1,153 @@
+//! The additive feedback generator behind random, producing the same
+//! sequences as glibc for the same seed and state size
+
+use platform::types::*;
+use sync::{Mutex, MutexGuard};
+
+const MAX_TYPES: usize = 5;
+/// Degree and separation of the generator used for each state size
+const KINDS: [(usize, usize); MAX_TYPES] = [(0, 0), (7, 3), (15, 1), (31, 3), (63, 1)];
+/// The smallest state array, in bytes, for each kind
+const SIZES: [usize; MAX_TYPES] = [8, 32, 64, 128, 256];
+const DEFAULT_KIND: usize = 3;
+
+pub struct State {
+    /// The array passed to initstate. Its first word records the kind of
+    /// generator and where it stands, for setstate to pick it up again.
+    buf: usize,
+    kind: usize,
+    front: usize,
+    rear: usize,
+}
+
+static STATE: Mutex<State> = Mutex::new(State {
+    buf: 0,
+    kind: DEFAULT_KIND,
+    front: 0,
+    rear: 0,
+});
+static mut DEFAULT_BUF: [i32; 1 + 31] = [0; 1 + 31];
+
+/// Lock the current state, seeding it with 1 if it has not been used yet
+pub fn lock() -> MutexGuard<'static, State> {
+    let mut state = STATE.lock();
+    if state.buf == 0 {
+        unsafe {
+            state.buf = DEFAULT_BUF.as_mut_ptr() as usize;
+            state.seed(1);
+        }
+    }
+    state
+}
+
+/// The kind of generator that fits in a state array of `size` bytes
+pub fn kind(size: size_t) -> Option<usize> {
+    SIZES.iter().rposition(|&min| size >= min)
+}
+
+impl State {
+    unsafe fn table(&self) -> *mut i32 {
+        (self.buf as *mut i32).offset(1)
+    }
+
+    fn degree(&self) -> usize {
+        KINDS[self.kind].0
+    }
+
+    fn separation(&self) -> usize {
+        KINDS[self.kind].1
+    }
+
+    pub unsafe fn seed(&mut self, seed: c_uint) {
+        let table = self.table();
+        let seed = if seed == 0 { 1 } else { seed };
+        *table = seed as i32;
+        if self.kind == 0 {
+            return;
+        }
+
+        // Fill the table with 16807 * x mod (2^31 - 1), computed by Schrage's
+        // method so that nothing overflows. Like glibc, this starts from the
+        // seed as a signed number.
+        let mut word = seed as i32 as i64;
+        for i in 1..self.degree() {
+            let hi = word / 127773;
+            let lo = word % 127773;
+            word = 16807 * lo - 2836 * hi;
+            if word < 0 {
+                word += 2147483647;
+            }
+            *table.offset(i as isize) = word as i32;
+        }
+        self.rear = 0;
+        self.front = self.separation();
+
+        // Get rid of the correlation with the seed
+        for _ in 0..10 * self.degree() {
+            self.next();
+        }
+    }
+
+    pub unsafe fn next(&mut self) -> c_long {
+        let table = self.table();
+        if self.kind == 0 {
+            let val = (*table as u32).wrapping_mul(1103515245).wrapping_add(12345) & 0x7FFF_FFFF;
+            *table = val as i32;
+            return val as c_long;
+        }
+
+        let front = table.offset(self.front as isize);
+        let val = (*front as u32).wrapping_add(*table.offset(self.rear as isize) as u32);
+        *front = val as i32;
+
+        let degree = self.degree();
+        self.front += 1;
+        self.rear += 1;
+        if self.front >= degree {
+            self.front = 0;
+        } else if self.rear >= degree {
+            self.rear = 0;
+        }
+        (val >> 1) as c_long
+    }
+
+    /// Record where the generator stands in its array and return the array
+    pub unsafe fn save(&mut self) -> *mut c_char {
+        let buf = self.buf as *mut i32;
+        *buf = if self.kind == 0 {
+            0
+        } else {
+            (MAX_TYPES * self.rear + self.kind) as i32
+        };
+        buf as *mut c_char
+    }
+
+    /// Switch to the array `buf`, as set up by `save`
+    pub unsafe fn load(&mut self, buf: *mut c_char) -> bool {
+        let word = *(buf as *const i32);
+        if word < 0 {
+            return false;
+        }
+        let kind = word as usize % MAX_TYPES;
+        let rear = word as usize / MAX_TYPES;
+        let (degree, separation) = KINDS[kind];
+        if kind != 0 && rear >= degree {
+            return false;
+        }
+
+        self.buf = buf as usize;
+        self.kind = kind;
+        if kind != 0 {
+            self.rear = rear;
+            self.front = (rear + separation) % degree;
+        }
+        true
+    }
+
+    /// Switch to the array `buf` of kind `kind` and seed it
+    pub unsafe fn init(&mut self, seed: c_uint, buf: *mut c_char, kind: usize) {
+        self.buf = buf as usize;
+        self.kind = kind;
+        self.seed(seed);
+    }
+}
@@ -0,0 +1,691 @@
+//! The parser behind strtod and friends. Results are correctly rounded: short
+//! inputs take Clinger's fast path, everything else is converted exactly
+//! using a big decimal, after Nigel Tao's "simple decimal conversion".
+
+use core::mem;
+use core::ops::{Div, Mul, Neg};
+
+pub trait Float: Copy + Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self> {
+    /// Explicitly stored mantissa bits
+    const MANTISSA_BITS: u32;
+    /// The exponent bias, negated
+    const MIN_EXPONENT: i32;
+    /// Biased exponent of infinity
+    const INFINITE_POWER: i32;
+    /// Largest power of ten that is exactly representable
+    const MAX_EXACT_POWER: i32;
+
+    const ZERO: Self;
+    const TEN: Self;
+    const INFINITY: Self;
+    const NAN: Self;
+
+    fn from_u64(x: u64) -> Self;
+    fn from_raw(bits: u64) -> Self;
+    fn to_raw(self) -> u64;
+}
+
+impl Float for f32 {
+    const MANTISSA_BITS: u32 = 23;
+    const MIN_EXPONENT: i32 = -127;
+    const INFINITE_POWER: i32 = 0xFF;
+    const MAX_EXACT_POWER: i32 = 10;
+
+    const ZERO: Self = 0.0;
+    const TEN: Self = 10.0;
+    const INFINITY: Self = 1.0 / 0.0;
+    const NAN: Self = 0.0 / 0.0;
+
+    fn from_u64(x: u64) -> Self {
+        x as f32
+    }
+    fn from_raw(bits: u64) -> Self {
+        unsafe { mem::transmute(bits as u32) }
+    }
+    fn to_raw(self) -> u64 {
+        unsafe { mem::transmute::<f32, u32>(self) as u64 }
+    }
+}
+
+impl Float for f64 {
+    const MANTISSA_BITS: u32 = 52;
+    const MIN_EXPONENT: i32 = -1023;
+    const INFINITE_POWER: i32 = 0x7FF;
+    const MAX_EXACT_POWER: i32 = 22;
+
+    const ZERO: Self = 0.0;
+    const TEN: Self = 10.0;
+    const INFINITY: Self = 1.0 / 0.0;
+    const NAN: Self = 0.0 / 0.0;
+
+    fn from_u64(x: u64) -> Self {
+        x as f64
+    }
+    fn from_raw(bits: u64) -> Self {
+        unsafe { mem::transmute(bits) }
+    }
+    fn to_raw(self) -> u64 {
+        unsafe { mem::transmute(self) }
+    }
+}
+
+pub struct Parsed<F> {
+    pub value: F,
+    /// Number of bytes that make up the number, 0 if there is none
+    pub len: usize,
+    /// Set if the value overflowed to infinity, or underflowed to zero or
+    /// a subnormal number
+    pub out_of_range: bool,
+}
+
+/// Parse the longest prefix of `s` that is a floating point number in any
+/// of the forms strtod accepts, not counting leading whitespace
+pub fn parse<F: Float>(s: &[u8]) -> Parsed<F> {
+    let (negative, sign_len) = match s.first() {
+        Some(&b'-') => (true, 1),
+        Some(&b'+') => (false, 1),
+        _ => (false, 0),
+    };
+    let rest = &s[sign_len..];
+
+    let (value, len, out_of_range) = if let Some((value, len)) = parse_special::<F>(rest) {
+        (value, len, false)
+    } else if let Some((value, len, out_of_range)) = parse_hex(rest) {
+        (value, len, out_of_range)
+    } else if let Some((value, len, out_of_range)) = parse_decimal(rest) {
+        (value, len, out_of_range)
+    } else {
+        return Parsed {
+            value: F::ZERO,
+            len: 0,
+            out_of_range: false,
+        };
+    };
+
+    Parsed {
+        value: if negative { -value } else { value },
+        len: sign_len + len,
+        out_of_range,
+    }
+}
+
+/// Whether `s` could still be extended into something `parse` takes in full,
+/// for scanf to know when to stop reading
+pub fn is_prefix(s: &[u8]) -> bool {
+    let s = match s.first() {
+        Some(&b'-') | Some(&b'+') => &s[1..],
+        _ => s,
+    };
+
+    if starts_with_ignore_case(b"infinity", s) || starts_with_ignore_case(b"nan", s) {
+        return true;
+    }
+    if starts_with_ignore_case(s, b"nan(") {
+        let inner = &s[4..];
+        return match inner.iter().position(|&c| !is_nan_char(c)) {
+            None => true,
+            Some(i) => inner[i] == b')' && i + 1 == inner.len(),
+        };
+    }
+
+    let (s, hex) = if s.len() >= 2 && s[0] == b'0' && (s[1] == b'x' || s[1] == b'X') {
+        (&s[2..], true)
+    } else {
+        (s, false)
+    };
+    let is_digit = |c: u8| if hex { is_hex_digit(c) } else { c.is_ascii_digit() };
+    let exponent = if hex { b'p' } else { b'e' };
+
+    let mut i = 0;
+    let mut digits = false;
+    while i < s.len() && is_digit(s[i]) {
+        i += 1;
+        digits = true;
+    }
+    if i < s.len() && s[i] == b'.' {
+        i += 1;
+        while i < s.len() && is_digit(s[i]) {
+            i += 1;
+            digits = true;
+        }
+    }
+    if i < s.len() && digits && s[i].to_ascii_lowercase() == exponent {
+        i += 1;
+        if i < s.len() && (s[i] == b'-' || s[i] == b'+') {
+            i += 1;
+        }
+        while i < s.len() && s[i].is_ascii_digit() {
+            i += 1;
+        }
+    }
+    i == s.len()
+}
+
+fn starts_with_ignore_case(s: &[u8], prefix: &[u8]) -> bool {
+    s.len() >= prefix.len()
+        && s.iter()
+            .zip(prefix)
+            .all(|(a, b)| a.to_ascii_lowercase() == b.to_ascii_lowercase())
+}
+
+fn is_nan_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_'
+}
+
+fn is_hex_digit(c: u8) -> bool {
+    (c as char).is_digit(16)
+}
+
+/// Infinity and NaN, with an optional payload for NaN that is ignored
+fn parse_special<F: Float>(s: &[u8]) -> Option<(F, usize)> {
+    if starts_with_ignore_case(s, b"infinity") {
+        Some((F::INFINITY, 8))
+    } else if starts_with_ignore_case(s, b"inf") {
+        Some((F::INFINITY, 3))
+    } else if starts_with_ignore_case(s, b"nan") {
+        let payload = s[3..].iter().skip(1).position(|&c| !is_nan_char(c));
+        let len = match payload {
+            Some(i) if s.get(3) == Some(&b'(') && s[4 + i] == b')' => 5 + i,
+            _ => 3,
+        };
+        Some((F::NAN, len))
+    } else {
+        None
+    }
+}
+
+/// Read the exponent at the start of `s` if it has one, returning its value
+/// (clamped to a range that does not overflow) and length
+fn parse_exponent(s: &[u8], marker: u8) -> (i64, usize) {
+    if s.first().map(|c| c.to_ascii_lowercase()) != Some(marker) {
+        return (0, 0);
+    }
+    let (negative, mut i) = match s.get(1) {
+        Some(&b'-') => (true, 2),
+        Some(&b'+') => (false, 2),
+        _ => (false, 1),
+    };
+
+    let start = i;
+    let mut exponent: i64 = 0;
+    while i < s.len() && s[i].is_ascii_digit() {
+        if exponent < 0x1_0000_0000 {
+            exponent = exponent * 10 + (s[i] - b'0') as i64;
+        }
+        i += 1;
+    }
+    if i == start {
+        // Just a letter following the number
+        (0, 0)
+    } else if negative {
+        (-exponent, i)
+    } else {
+        (exponent, i)
+    }
+}
+
+fn parse_hex<F: Float>(s: &[u8]) -> Option<(F, usize, bool)> {
+    if s.len() < 2 || s[0] != b'0' || (s[1] != b'x' && s[1] != b'X') {
+        return None;
+    }
+
+    // Keep the first 60 bits or so, which is plenty, and note whether there
+    // are any nonzero bits after that
+    let mut mantissa: u64 = 0;
+    let mut exponent: i64 = 0;
+    let mut sticky = false;
+    let mut digits = 0;
+    let mut i = 2;
+    let mut fraction = false;
+    loop {
+        match s.get(i) {
+            Some(&b'.') if !fraction => fraction = true,
+            Some(&c) if is_hex_digit(c) => {
+                let digit = (c as char).to_digit(16).unwrap() as u64;
+                if mantissa >> 60 == 0 {
+                    mantissa = mantissa << 4 | digit;
+                    if fraction {
+                        exponent -= 4;
+                    }
+                } else {
+                    sticky |= digit != 0;
+                    if !fraction {
+                        exponent += 4;
+                    }
+                }
+                digits += 1;
+            }
+            _ => break,
+        }
+        i += 1;
+    }
+    if digits == 0 {
+        // Only the 0 before the x is a number
+        return None;
+    }
+
+    let (binary_exponent, len) = parse_exponent(&s[i..], b'p');
+    let value = from_binary::<F>(mantissa, exponent + binary_exponent, sticky);
+    Some((value, i + len, out_of_range(value, mantissa != 0)))
+}
+
+/// Round mantissa * 2^exponent, plus a little more if `sticky` is set, to
+/// the nearest `F`
+fn from_binary<F: Float>(mantissa: u64, exponent: i64, sticky: bool) -> F {
+    if mantissa == 0 {
+        return F::ZERO;
+    }
+
+    // Normalize to 1.m * 2^exponent, with the 1 as the top bit
+    let zeros = mantissa.leading_zeros();
+    let mantissa = mantissa << zeros;
+    let biased = exponent + 63 - zeros as i64 - F::MIN_EXPONENT as i64;
+    if biased >= F::INFINITE_POWER as i64 {
+        return F::INFINITY;
+    }
+
+    // Subnormal numbers have fewer bits to spare
+    let shift = 63 - F::MANTISSA_BITS as i64 + if biased < 1 { 1 - biased } else { 0 };
+    let kept = if shift > 64 {
+        0
+    } else if shift == 64 {
+        // Everything is below the last bit, so it is at least half of it
+        (mantissa > 1 << 63 || sticky) as u64
+    } else {
+        let kept = mantissa >> shift;
+        let rest = mantissa & ((1 << shift) - 1);
+        let half = 1 << (shift - 1);
+        if rest > half || (rest == half && (sticky || kept & 1 == 1)) {
+            kept + 1
+        } else {
+            kept
+        }
+    };
+
+    // The implicit bit adds one to the exponent, and rounding up into the
+    // next power of two carries over into the exponent as it should
+    let bits = ((biased.max(1) - 1) as u64) << F::MANTISSA_BITS;
+    let bits = bits + kept;
+    if bits >= (F::INFINITE_POWER as u64) << F::MANTISSA_BITS {
+        F::INFINITY
+    } else {
+        F::from_raw(bits)
+    }
+}
+
+fn out_of_range<F: Float>(value: F, nonzero: bool) -> bool {
+    let bits = value.to_raw();
+    let exponent = (bits >> F::MANTISSA_BITS) & F::INFINITE_POWER as u64;
+    let mantissa = bits & ((1 << F::MANTISSA_BITS) - 1);
+    if exponent == F::INFINITE_POWER as u64 {
+        mantissa == 0
+    } else {
+        exponent == 0 && nonzero
+    }
+}
+
+fn parse_decimal<F: Float>(s: &[u8]) -> Option<(F, usize, bool)> {
+    let mut decimal = Decimal::new();
+    // The first 19 significant digits, for the fast path
+    let mut mantissa: u64 = 0;
+    let mut significant = 0;
+    let mut digits = 0;
+    let mut i = 0;
+    let mut fraction = false;
+    loop {
+        match s.get(i) {
+            Some(&b'.') if !fraction => fraction = true,
+            Some(&c) if c.is_ascii_digit() => {
+                let digit = c - b'0';
+                if decimal.num_digits == 0 && digit == 0 {
+                    // Leading zeros only move the decimal point
+                    if fraction {
+                        decimal.decimal_point -= 1;
+                    }
+                } else {
+                    if !fraction {
+                        decimal.decimal_point += 1;
+                    }
+                    decimal.add_digit(digit);
+                    if significant < 19 {
+                        mantissa = mantissa * 10 + digit as u64;
+                        significant += 1;
+                    }
+                }
+                digits += 1;
+            }
+            _ => break,
+        }
+        i += 1;
+    }
+    if digits == 0 {
+        return None;
+    }
+
+    let (exponent, len) = parse_exponent(&s[i..], b'e');
+    let len = i + len;
+    if decimal.num_digits == 0 {
+        return Some((F::ZERO, len, false));
+    }
+    let decimal_point = decimal.decimal_point as i64 + exponent;
+
+    // The value is exactly mantissa * 10^power if all digits fit
+    let power = decimal_point - significant as i64;
+    let exact = significant == decimal.num_digits && !decimal.truncated;
+    if exact
+        && mantissa <= 1 << (F::MANTISSA_BITS + 1)
+        && power.abs() <= F::MAX_EXACT_POWER as i64
+    {
+        let mut scale = F::from_u64(1);
+        for _ in 0..power.abs() {
+            scale = scale * F::TEN;
+        }
+        let value = if power < 0 {
+            F::from_u64(mantissa) / scale
+        } else {
+            F::from_u64(mantissa) * scale
+        };
+        return Some((value, len, false));
+    }
+
+    let value = if decimal_point < -400 {
+        F::ZERO
+    } else if decimal_point > 400 {
+        F::INFINITY
+    } else {
+        decimal.decimal_point = decimal_point as i32;
+        decimal.trim();
+        decimal.to_float()
+    };
+    Some((value, len, out_of_range(value, true)))
+}
+
+/// Number of new digits when shifting left by the index, and the digits of
+/// 5 to the power of the index. Shifting a number that sorts below those
+/// digits yields one digit less.
+const LEFT_SHIFTS: [(usize, &[u8]); 61] = [
+    (0, b""),
+    (1, b"5"),
+    (1, b"25"),
+    (1, b"125"),
+    (2, b"625"),
+    (2, b"3125"),
+    (2, b"15625"),
+    (3, b"78125"),
+    (3, b"390625"),
+    (3, b"1953125"),
+    (4, b"9765625"),
+    (4, b"48828125"),
+    (4, b"244140625"),
+    (4, b"1220703125"),
+    (5, b"6103515625"),
+    (5, b"30517578125"),
+    (5, b"152587890625"),
+    (6, b"762939453125"),
+    (6, b"3814697265625"),
+    (6, b"19073486328125"),
+    (7, b"95367431640625"),
+    (7, b"476837158203125"),
+    (7, b"2384185791015625"),
+    (7, b"11920928955078125"),
+    (8, b"59604644775390625"),
+    (8, b"298023223876953125"),
+    (8, b"1490116119384765625"),
+    (9, b"7450580596923828125"),
+    (9, b"37252902984619140625"),
+    (9, b"186264514923095703125"),
+    (10, b"931322574615478515625"),
+    (10, b"4656612873077392578125"),
+    (10, b"23283064365386962890625"),
+    (10, b"116415321826934814453125"),
+    (11, b"582076609134674072265625"),
+    (11, b"2910383045673370361328125"),
+    (11, b"14551915228366851806640625"),
+    (12, b"72759576141834259033203125"),
+    (12, b"363797880709171295166015625"),
+    (12, b"1818989403545856475830078125"),
+    (13, b"9094947017729282379150390625"),
+    (13, b"45474735088646411895751953125"),
+    (13, b"227373675443232059478759765625"),
+    (13, b"1136868377216160297393798828125"),
+    (14, b"5684341886080801486968994140625"),
+    (14, b"28421709430404007434844970703125"),
+    (14, b"142108547152020037174224853515625"),
+    (15, b"710542735760100185871124267578125"),
+    (15, b"3552713678800500929355621337890625"),
+    (15, b"17763568394002504646778106689453125"),
+    (16, b"88817841970012523233890533447265625"),
+    (16, b"444089209850062616169452667236328125"),
+    (16, b"2220446049250313080847263336181640625"),
+    (16, b"11102230246251565404236316680908203125"),
+    (17, b"55511151231257827021181583404541015625"),
+    (17, b"277555756156289135105907917022705078125"),
+    (17, b"1387778780781445675529539585113525390625"),
+    (18, b"6938893903907228377647697925567626953125"),
+    (18, b"34694469519536141888238489627838134765625"),
+    (18, b"173472347597680709441192448139190673828125"),
+    (19, b"867361737988403547205962240695953369140625"),
+];
+
+const MAX_SHIFT: usize = 60;
+/// The largest shift that cannot overflow for a given number of digits
+const SHIFTS: [usize; 19] = [
+    0, 3, 6, 9, 13, 16, 19, 23, 26, 29, 33, 36, 39, 43, 46, 49, 53, 56, 59,
+];
+
+fn shift_for(digits: usize) -> usize {
+    if digits < SHIFTS.len() {
+        SHIFTS[digits]
+    } else {
+        MAX_SHIFT
+    }
+}
+
+/// Enough for every digit to matter when rounding to the nearest f64
+const MAX_DIGITS: usize = 768;
+
+/// An arbitrary precision decimal 0.d1d2d3... * 10^decimal_point
+struct Decimal {
+    num_digits: usize,
+    decimal_point: i32,
+    /// Set if there were nonzero digits past what fits
+    truncated: bool,
+    digits: [u8; MAX_DIGITS],
+}
+
+impl Decimal {
+    fn new() -> Self {
+        Decimal {
+            num_digits: 0,
+            decimal_point: 0,
+            truncated: false,
+            digits: [0; MAX_DIGITS],
+        }
+    }
+
+    fn add_digit(&mut self, digit: u8) {
+        if self.num_digits < MAX_DIGITS {
+            self.digits[self.num_digits] = digit;
+            self.num_digits += 1;
+        } else if digit != 0 {
+            self.truncated = true;
+        }
+    }
+
+    fn trim(&mut self) {
+        while self.num_digits != 0 && self.digits[self.num_digits - 1] == 0 {
+            self.num_digits -= 1;
+        }
+    }
+
+    /// The integer part, rounded to nearest, ties to even
+    fn round(&self) -> u64 {
+        if self.num_digits == 0 || self.decimal_point < 0 {
+            return 0;
+        } else if self.decimal_point > 18 {
+            return !0;
+        }
+
+        let point = self.decimal_point as usize;
+        let mut n: u64 = 0;
+        for i in 0..point {
+            n *= 10;
+            if i < self.num_digits {
+                n += self.digits[i] as u64;
+            }
+        }
+        if point < self.num_digits {
+            let mut round_up = self.digits[point] >= 5;
+            if self.digits[point] == 5 && point + 1 == self.num_digits {
+                round_up = self.truncated || (point != 0 && self.digits[point - 1] & 1 != 0);
+            }
+            if round_up {
+                n += 1;
+            }
+        }
+        n
+    }
+
+    /// Multiply by 2^shift
+    fn left_shift(&mut self, shift: usize) {
+        if self.num_digits == 0 {
+            return;
+        }
+
+        let (mut new_digits, pow5) = LEFT_SHIFTS[shift];
+        for (i, &c) in pow5.iter().enumerate() {
+            let p5 = c - b'0';
+            if i >= self.num_digits || self.digits[i] < p5 {
+                new_digits -= 1;
+                break;
+            } else if self.digits[i] > p5 {
+                break;
+            }
+        }
+
+        let mut read = self.num_digits;
+        let mut write = self.num_digits + new_digits;
+        let mut n: u64 = 0;
+        while read != 0 {
+            read -= 1;
+            write -= 1;
+            n += (self.digits[read] as u64) << shift;
+            self.put_digit(write, (n % 10) as u8);
+            n /= 10;
+        }
+        while n > 0 {
+            write -= 1;
+            self.put_digit(write, (n % 10) as u8);
+            n /= 10;
+        }
+
+        self.num_digits = (self.num_digits + new_digits).min(MAX_DIGITS);
+        self.decimal_point += new_digits as i32;
+        self.trim();
+    }
+
+    fn put_digit(&mut self, i: usize, digit: u8) {
+        if i < MAX_DIGITS {
+            self.digits[i] = digit;
+        } else if digit != 0 {
+            self.truncated = true;
+        }
+    }
+
+    /// Divide by 2^shift
+    fn right_shift(&mut self, shift: usize) {
+        let mut read = 0;
+        let mut write = 0;
+        let mut n: u64 = 0;
+        while n >> shift == 0 {
+            if read < self.num_digits {
+                n = 10 * n + self.digits[read] as u64;
+                read += 1;
+            } else if n == 0 {
+                return;
+            } else {
+                while n >> shift == 0 {
+                    n *= 10;
+                    read += 1;
+                }
+                break;
+            }
+        }
+
+        self.decimal_point -= read as i32 - 1;
+        let mask = (1 << shift) - 1;
+        while read < self.num_digits {
+            let digit = (n >> shift) as u8;
+            n = 10 * (n & mask) + self.digits[read] as u64;
+            read += 1;
+            self.digits[write] = digit;
+            write += 1;
+        }
+        while n > 0 {
+            let digit = (n >> shift) as u8;
+            n = 10 * (n & mask);
+            if write < MAX_DIGITS {
+                self.digits[write] = digit;
+                write += 1;
+            } else if digit > 0 {
+                self.truncated = true;
+            }
+        }
+        self.num_digits = write;
+        self.trim();
+    }
+
+    fn to_float<F: Float>(&mut self) -> F {
+        // Scale to [1/2, 1), keeping track of the power of two
+        let mut exponent: i32 = 0;
+        while self.decimal_point > 0 {
+            let shift = shift_for(self.decimal_point as usize);
+            self.right_shift(shift);
+            exponent += shift as i32;
+        }
+        while self.decimal_point <= 0 {
+            let shift = if self.decimal_point == 0 {
+                match self.digits[0] {
+                    digit if digit >= 5 => break,
+                    0 | 1 => 2,
+                    _ => 1,
+                }
+            } else {
+                shift_for(-self.decimal_point as usize)
+            };
+            self.left_shift(shift);
+            exponent -= shift as i32;
+        }
+
+        // Now in [1, 2), as the binary format has it
+        exponent -= 1;
+        while exponent < F::MIN_EXPONENT + 1 {
+            let shift = ((F::MIN_EXPONENT + 1 - exponent) as usize).min(MAX_SHIFT);
+            self.right_shift(shift);
+            exponent += shift as i32;
+        }
+        if exponent - F::MIN_EXPONENT >= F::INFINITE_POWER {
+            return F::INFINITY;
+        }
+
+        self.left_shift(F::MANTISSA_BITS as usize + 1);
+        let mut mantissa = self.round();
+        if mantissa >= 1 << (F::MANTISSA_BITS + 1) {
+            // Rounding carried into the next power of two
+            self.right_shift(1);
+            exponent += 1;
+            mantissa = self.round();
+            if exponent - F::MIN_EXPONENT >= F::INFINITE_POWER {
+                return F::INFINITY;
+            }
+        }
+
+        let mut biased = exponent - F::MIN_EXPONENT;
+        if mantissa < 1 << F::MANTISSA_BITS {
+            // Subnormal
+            biased -= 1;
+        }
+        mantissa &= (1 << F::MANTISSA_BITS) - 1;
+        F::from_raw((biased as u64) << F::MANTISSA_BITS | mantissa)
+    }
+}
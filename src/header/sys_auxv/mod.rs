@@ -0,0 +1,68 @@
+//! sys/auxv.h implementation for Redox, following the Linux getauxval(3) interface
+
+use core::ptr;
+
+use header::errno::ENOENT;
+use platform;
+use platform::types::*;
+
+pub const AT_NULL: c_ulong = 0;
+pub const AT_IGNORE: c_ulong = 1;
+pub const AT_EXECFD: c_ulong = 2;
+pub const AT_PHDR: c_ulong = 3;
+pub const AT_PHENT: c_ulong = 4;
+pub const AT_PHNUM: c_ulong = 5;
+pub const AT_PAGESZ: c_ulong = 6;
+pub const AT_BASE: c_ulong = 7;
+pub const AT_FLAGS: c_ulong = 8;
+pub const AT_ENTRY: c_ulong = 9;
+pub const AT_NOTELF: c_ulong = 10;
+pub const AT_UID: c_ulong = 11;
+pub const AT_EUID: c_ulong = 12;
+pub const AT_GID: c_ulong = 13;
+pub const AT_EGID: c_ulong = 14;
+pub const AT_PLATFORM: c_ulong = 15;
+pub const AT_HWCAP: c_ulong = 16;
+pub const AT_CLKTCK: c_ulong = 17;
+pub const AT_SECURE: c_ulong = 23;
+pub const AT_BASE_PLATFORM: c_ulong = 24;
+pub const AT_RANDOM: c_ulong = 25;
+pub const AT_HWCAP2: c_ulong = 26;
+pub const AT_EXECFN: c_ulong = 31;
+pub const AT_SYSINFO_EHDR: c_ulong = 33;
+
+/// The pairs of type and value the kernel put after the environment, ended
+/// by AT_NULL, or null if it did not give us any
+static mut AUXV: *const c_ulong = ptr::null();
+
+pub unsafe fn init(auxv: *const c_ulong) {
+    AUXV = auxv;
+}
+
+/// The value of `type_` in the auxiliary vector, if it is there
+pub fn get(type_: c_ulong) -> Option<c_ulong> {
+    unsafe {
+        let mut entry = AUXV;
+        if entry.is_null() {
+            return None;
+        }
+        while *entry != AT_NULL {
+            if *entry == type_ {
+                return Some(*entry.offset(1));
+            }
+            entry = entry.offset(2);
+        }
+    }
+    None
+}
+
+#[no_mangle]
+pub extern "C" fn getauxval(type_: c_ulong) -> c_ulong {
+    match get(type_) {
+        Some(val) => val,
+        None => {
+            unsafe { platform::errno = ENOENT };
+            0
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! sys/random.h implementation for Redox, following the Linux getrandom(2) interface
+
+use core::slice;
+
+use header::errno::{EINTR, EIO};
+use platform;
+use platform::types::*;
+use platform::{Pal, Sys};
+
+pub const GRND_NONBLOCK: c_uint = 1;
+pub const GRND_RANDOM: c_uint = 2;
+
+/// Fill all of `buf` with random bytes from the kernel, retrying after
+/// signals and short reads. A kernel that gives nothing at all would keep
+/// this going forever, so that is an error.
+pub fn fill(mut buf: &mut [u8]) -> bool {
+    while !buf.is_empty() {
+        let count = Sys::getrandom(buf, 0);
+        if count < 0 {
+            if unsafe { platform::errno } == EINTR {
+                continue;
+            }
+            return false;
+        }
+        if count == 0 {
+            unsafe { platform::errno = EIO };
+            return false;
+        }
+        buf = &mut { buf }[count as usize..];
+    }
+    true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn getrandom(buf: *mut c_void, buflen: size_t, flags: c_uint) -> ssize_t {
+    Sys::getrandom(slice::from_raw_parts_mut(buf as *mut u8, buflen), flags)
+}
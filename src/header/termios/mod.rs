@@ -27,6 +27,11 @@ pub extern "C" fn tcgetattr(fd: c_int, out: *mut termios) -> c_int {
     Sys::tcgetattr(fd, out as *mut platform::types::termios)
 }
 
+#[no_mangle]
+pub extern "C" fn tcgetsid(fd: c_int) -> pid_t {
+    Sys::tcgetsid(fd)
+}
+
 #[no_mangle]
 pub extern "C" fn tcsetattr(fd: c_int, act: c_int, value: *mut termios) -> c_int {
     Sys::tcsetattr(fd, act, value as *mut platform::types::termios)
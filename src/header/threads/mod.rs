@@ -0,0 +1,214 @@
+//! threads.h implementation for Redox, following the C11 standard (section 7.26)
+
+use alloc::boxed::Box;
+use core::{mem, ptr};
+
+use header::errno::*;
+use header::pthread::*;
+use header::time::{nanosleep, timespec};
+use platform;
+use platform::types::*;
+use platform::{Pal, Sys};
+
+pub const thrd_success: c_int = 0;
+pub const thrd_busy: c_int = 1;
+pub const thrd_error: c_int = 2;
+pub const thrd_nomem: c_int = 3;
+pub const thrd_timedout: c_int = 4;
+
+pub const mtx_plain: c_int = 0;
+pub const mtx_recursive: c_int = 1;
+pub const mtx_timed: c_int = 2;
+
+pub const TSS_DTOR_ITERATIONS: c_int = PTHREAD_DESTRUCTOR_ITERATIONS;
+
+pub type thrd_t = pthread_t;
+pub type thrd_start_t = Option<unsafe extern "C" fn(arg: *mut c_void) -> c_int>;
+pub type mtx_t = pthread_mutex_t;
+pub type cnd_t = pthread_cond_t;
+pub type tss_t = pthread_key_t;
+pub type tss_dtor_t = Option<unsafe extern "C" fn(arg: *mut c_void)>;
+pub type once_flag = pthread_once_t;
+
+fn result(res: c_int) -> c_int {
+    match res {
+        0 => thrd_success,
+        EBUSY => thrd_busy,
+        ETIMEDOUT => thrd_timedout,
+        ENOMEM => thrd_nomem,
+        _ => thrd_error,
+    }
+}
+
+struct Start {
+    func: unsafe extern "C" fn(arg: *mut c_void) -> c_int,
+    arg: *mut c_void,
+}
+
+unsafe extern "C" fn thrd_trampoline(start: *mut c_void) -> *mut c_void {
+    // Freed before the thread runs, as it may never return here if it ends
+    // through thrd_exit
+    let Start { func, arg } = *Box::from_raw(start as *mut Start);
+    func(arg) as isize as *mut c_void
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn call_once(flag: *mut once_flag, func: Option<unsafe extern "C" fn()>) {
+    pthread_once(flag, func);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cnd_broadcast(cond: *mut cnd_t) -> c_int {
+    result(pthread_cond_broadcast(cond))
+}
+
+#[no_mangle]
+pub extern "C" fn cnd_destroy(cond: *mut cnd_t) {
+    pthread_cond_destroy(cond);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cnd_init(cond: *mut cnd_t) -> c_int {
+    result(pthread_cond_init(cond, ptr::null()))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cnd_signal(cond: *mut cnd_t) -> c_int {
+    result(pthread_cond_signal(cond))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cnd_timedwait(
+    cond: *mut cnd_t,
+    mtx: *mut mtx_t,
+    ts: *const timespec,
+) -> c_int {
+    result(pthread_cond_timedwait(cond, mtx, ts))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cnd_wait(cond: *mut cnd_t, mtx: *mut mtx_t) -> c_int {
+    result(pthread_cond_wait(cond, mtx))
+}
+
+#[no_mangle]
+pub extern "C" fn mtx_destroy(mtx: *mut mtx_t) {
+    pthread_mutex_destroy(mtx);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mtx_init(mtx: *mut mtx_t, type_: c_int) -> c_int {
+    let mut attr: pthread_mutexattr_t = mem::uninitialized();
+    pthread_mutexattr_init(&mut attr);
+    if type_ & mtx_recursive != 0 {
+        pthread_mutexattr_settype(&mut attr, PTHREAD_MUTEX_RECURSIVE);
+    }
+    result(pthread_mutex_init(mtx, &attr))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mtx_lock(mtx: *mut mtx_t) -> c_int {
+    result(pthread_mutex_lock(mtx))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mtx_timedlock(mtx: *mut mtx_t, ts: *const timespec) -> c_int {
+    result(pthread_mutex_timedlock(mtx, ts))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mtx_trylock(mtx: *mut mtx_t) -> c_int {
+    result(pthread_mutex_trylock(mtx))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mtx_unlock(mtx: *mut mtx_t) -> c_int {
+    result(pthread_mutex_unlock(mtx))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn thrd_create(
+    thr: *mut thrd_t,
+    func: thrd_start_t,
+    arg: *mut c_void,
+) -> c_int {
+    let func = match func {
+        Some(func) => func,
+        None => return thrd_error,
+    };
+    let start = Box::into_raw(Box::new(Start { func, arg }));
+    let res = pthread_create(thr, ptr::null(), Some(thrd_trampoline), start as *mut c_void);
+    if res != 0 {
+        drop(Box::from_raw(start));
+    }
+    result(res)
+}
+
+#[no_mangle]
+pub extern "C" fn thrd_current() -> thrd_t {
+    pthread_self()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn thrd_detach(thr: thrd_t) -> c_int {
+    result(pthread_detach(thr))
+}
+
+#[no_mangle]
+pub extern "C" fn thrd_equal(thr0: thrd_t, thr1: thrd_t) -> c_int {
+    pthread_equal(thr0, thr1)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn thrd_exit(res: c_int) -> ! {
+    pthread_exit(res as isize as *mut c_void)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn thrd_join(thr: thrd_t, res: *mut c_int) -> c_int {
+    let mut retval = ptr::null_mut();
+    let ret = pthread_join(thr, &mut retval);
+    if ret == 0 && !res.is_null() {
+        *res = retval as isize as c_int;
+    }
+    result(ret)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn thrd_sleep(
+    duration: *const timespec,
+    remaining: *mut timespec,
+) -> c_int {
+    if nanosleep(duration, remaining) == 0 {
+        0
+    } else if platform::errno == EINTR {
+        -1
+    } else {
+        -2
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn thrd_yield() {
+    Sys::sched_yield();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn tss_create(key: *mut tss_t, dtor: tss_dtor_t) -> c_int {
+    result(pthread_key_create(key, dtor))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn tss_delete(key: tss_t) {
+    pthread_key_delete(key);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn tss_get(key: tss_t) -> *mut c_void {
+    pthread_getspecific(key)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn tss_set(key: tss_t, val: *mut c_void) -> c_int {
+    result(pthread_setspecific(key, val))
+}
@@ -39,3 +39,31 @@ pub(crate) fn civil_from_days(days: c_long) -> (c_int, c_int, c_int, c_int) {
 fn is_leap(y: c_int) -> c_int {
     ((y % 4 == 0 && y % 100 != 0) || y % 400 == 0) as c_int
 }
+
+// compute the days since the epoch, the inverse of civil_from_days, with the
+// month counted from zero and allowed to lie outside the year like in mktime
+// for description of this algorithm see
+// http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+pub(crate) fn days_from_civil(year: c_long, month: c_long, day: c_long) -> c_long {
+    let mut year = year + month / 12;
+    let mut month = month % 12;
+    if month < 0 {
+        month += 12;
+        year -= 1;
+    }
+
+    // Years start in March, like in civil_from_days
+    if month <= 1 {
+        year -= 1;
+    }
+    let years_per_era = YEARS_PER_ERA as c_long;
+    let era = if year >= 0 {
+        year
+    } else {
+        year - (years_per_era - 1)
+    } / years_per_era;
+    let erayear = year - era * years_per_era;
+    let yearday = (153 * (month + if month > 1 { -2 } else { 10 }) + 2) / 5 + day - 1;
+    let eraday = erayear * DAYS_PER_YEAR as c_long + erayear / 4 - erayear / 100 + yearday;
+    era * DAYS_PER_ERA + eraday - EPOCH_ADJUSTMENT_DAYS
+}
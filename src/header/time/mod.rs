@@ -1,7 +1,9 @@
 //! time implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/time.h.html
 
 use core::mem::transmute;
+use core::ptr;
 
+use c_str::CStr;
 use header::errno::EIO;
 use platform;
 use platform::types::*;
@@ -13,6 +15,9 @@ use self::helpers::*;
 pub mod constants;
 mod helpers;
 mod strftime;
+mod strptime;
+mod timer;
+mod tz;
 
 #[repr(C)]
 pub struct timespec {
@@ -52,6 +57,34 @@ static mut TM: tm = tm {
     tm_zone: UTC,
 };
 
+#[allow(non_upper_case_globals)]
+#[no_mangle]
+pub static mut tzname: [*mut c_char; 2] = [UTC as *mut c_char, UTC as *mut c_char];
+#[allow(non_upper_case_globals)]
+#[no_mangle]
+pub static mut timezone: c_long = 0;
+#[allow(non_upper_case_globals)]
+#[no_mangle]
+pub static mut daylight: c_int = 0;
+
+#[allow(non_upper_case_globals)]
+#[no_mangle]
+pub static mut getdate_err: c_int = 0;
+
+static mut GETDATE: tm = tm {
+    tm_sec: 0,
+    tm_min: 0,
+    tm_hour: 0,
+    tm_mday: 0,
+    tm_mon: 0,
+    tm_year: 0,
+    tm_wday: 0,
+    tm_yday: 0,
+    tm_isdst: 0,
+    tm_gmtoff: 0,
+    tm_zone: UTC,
+};
+
 // The C Standard says that ctime and asctime return the same pointer.
 static mut ASCTIME: [c_char; 26] = [0; 26];
 
@@ -61,7 +94,23 @@ pub struct itimerspec {
     pub it_value: timespec,
 }
 
-pub struct sigevent;
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union sigval {
+    pub sival_int: c_int,
+    pub sival_ptr: *mut c_void,
+}
+
+#[repr(C)]
+pub struct sigevent {
+    pub sigev_value: sigval,
+    pub sigev_signo: c_int,
+    pub sigev_notify: c_int,
+    pub sigev_notify_function: Option<extern "C" fn(sigval)>,
+    // Really a pthread_attr_t, which pthread.h defines after including us
+    pub sigev_notify_attributes: *mut c_void,
+    pub __sigev_pad: [c_int; 8],
+}
 
 #[no_mangle]
 pub extern "C" fn asctime(timeptr: *const tm) -> *mut c_char {
@@ -111,9 +160,17 @@ pub extern "C" fn clock() -> clock_t {
     return ts.tv_sec * CLOCKS_PER_SEC + ts.tv_nsec / (1_000_000_000 / CLOCKS_PER_SEC);
 }
 
-// #[no_mangle]
+#[no_mangle]
+pub unsafe extern "C" fn clock_getcpuclockid(pid: pid_t, clock_id: *mut clockid_t) -> c_int {
+    if Sys::clock_getcpuclockid(pid, clock_id) < 0 {
+        return platform::errno;
+    }
+    0
+}
+
+#[no_mangle]
 pub extern "C" fn clock_getres(clock_id: clockid_t, res: *mut timespec) -> c_int {
-    unimplemented!();
+    Sys::clock_getres(clock_id, res as *mut platform::types::timespec)
 }
 
 #[no_mangle]
@@ -121,9 +178,29 @@ pub extern "C" fn clock_gettime(clock_id: clockid_t, tp: *mut timespec) -> c_int
     Sys::clock_gettime(clock_id, tp as *mut platform::types::timespec)
 }
 
-// #[no_mangle]
+#[no_mangle]
+pub unsafe extern "C" fn clock_nanosleep(
+    clock_id: clockid_t,
+    flags: c_int,
+    rqtp: *const timespec,
+    rmtp: *mut timespec,
+) -> c_int {
+    // Unlike nanosleep, this returns the error rather than -1
+    if Sys::clock_nanosleep(
+        clock_id,
+        flags,
+        rqtp as *const platform::types::timespec,
+        rmtp as *mut platform::types::timespec,
+    ) < 0
+    {
+        return platform::errno;
+    }
+    0
+}
+
+#[no_mangle]
 pub extern "C" fn clock_settime(clock_id: clockid_t, tp: *const timespec) -> c_int {
-    unimplemented!();
+    Sys::clock_settime(clock_id, tp as *const platform::types::timespec)
 }
 
 #[no_mangle]
@@ -141,9 +218,15 @@ pub extern "C" fn difftime(time1: time_t, time0: time_t) -> c_double {
     (time1 - time0) as c_double
 }
 
-// #[no_mangle]
-pub extern "C" fn getdate(string: *const c_char) -> tm {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn getdate(string: *const c_char) -> *mut tm {
+    match strptime::getdate(CStr::from_ptr(string).to_bytes(), &mut GETDATE) {
+        Ok(()) => &mut GETDATE,
+        Err(err) => {
+            getdate_err = err;
+            ptr::null_mut()
+        }
+    }
 }
 
 #[no_mangle]
@@ -195,132 +278,33 @@ pub unsafe extern "C" fn localtime(clock: *const time_t) -> *mut tm {
     localtime_r(clock, &mut TM)
 }
 
-const MONTH_DAYS: [[c_int; 12]; 2] = [
-    // Non-leap years:
-    [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31],
-    // Leap years:
-    [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31],
-];
-
-fn leap_year(year: c_int) -> bool {
-    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
-}
-
 #[no_mangle]
 pub unsafe extern "C" fn localtime_r(clock: *const time_t, t: *mut tm) -> *mut tm {
-    let clock = *clock;
-
-    let mut day = (clock / (60 * 60 * 24)) as c_int;
-    if clock < 0 && clock % (60 * 60 * 24) != 0 {
-        // -1 because for negative values round upwards
-        // -0.3 == 0, but we want -1
-        day -= 1;
-    }
-
-    (*t).tm_sec = (clock % 60) as c_int;
-    (*t).tm_min = ((clock / 60) % 60) as c_int;
-    (*t).tm_hour = ((clock / (60 * 60)) % 24) as c_int;
-
-    while (*t).tm_sec < 0 {
-        (*t).tm_sec += 60;
-        (*t).tm_min -= 1;
-    }
-    while (*t).tm_min < 0 {
-        (*t).tm_min += 60;
-        (*t).tm_hour -= 1;
-    }
-    while (*t).tm_hour < 0 {
-        (*t).tm_hour += 24;
-    }
-
-    // Jan 1th was a thursday, 4th of a zero-indexed week.
-    (*t).tm_wday = (day + 4) % 7;
-    if (*t).tm_wday < 0 {
-        (*t).tm_wday += 7;
-    }
-
-    let mut year = 1970;
-    if day < 0 {
-        while day < 0 {
-            let days_in_year = if leap_year(year) { 366 } else { 365 };
-
-            day += days_in_year;
-            year -= 1;
-        }
-        (*t).tm_year = year - 1900;
-        (*t).tm_yday = day + 1;
-    } else {
-        loop {
-            let days_in_year = if leap_year(year) { 366 } else { 365 };
-
-            if day < days_in_year {
-                break;
-            }
-
-            day -= days_in_year;
-            year += 1;
-        }
-        (*t).tm_year = year - 1900;
-        (*t).tm_yday = day;
-    }
-
-    let leap = if leap_year(year) { 1 } else { 0 };
-    (*t).tm_mon = 0;
-    loop {
-        let days_in_month = MONTH_DAYS[leap][(*t).tm_mon as usize];
-
-        if day < days_in_month {
-            break;
-        }
-
-        day -= days_in_month;
-        (*t).tm_mon += 1;
-    }
-    (*t).tm_mday = 1 + day as c_int;
-    (*t).tm_isdst = 0;
+    let local_type = tz::lock().zone.find(*clock);
+    let local = *clock + local_type.offset;
+    gmtime_r(&local, t);
 
+    (*t).tm_isdst = local_type.isdst as c_int;
+    (*t).tm_gmtoff = local_type.offset;
+    (*t).tm_zone = local_type.name.as_ptr() as *const c_char;
     t
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn mktime(t: *mut tm) -> time_t {
-    let mut year = (*t).tm_year + 1900;
-    let mut month = (*t).tm_mon;
-    let mut day = (*t).tm_mday as i64 - 1;
-
-    let leap = if leap_year(year) { 1 } else { 0 };
-
-    if year < 1970 {
-        day = MONTH_DAYS[if leap_year(year) { 1 } else { 0 }][(*t).tm_mon as usize] as i64 - day;
-
-        while year < 1969 {
-            year += 1;
-            day += if leap_year(year) { 366 } else { 365 };
-        }
-
-        while month < 11 {
-            month += 1;
-            day += MONTH_DAYS[leap][month as usize] as i64;
-        }
-
-        -(day * (60 * 60 * 24)
-            - (((*t).tm_hour as i64) * (60 * 60) + ((*t).tm_min as i64) * 60 + (*t).tm_sec as i64))
-    } else {
-        while year > 1970 {
-            year -= 1;
-            day += if leap_year(year) { 366 } else { 365 };
-        }
-
-        while month > 0 {
-            month -= 1;
-            day += MONTH_DAYS[leap][month as usize] as i64;
-        }
-
-        (day * (60 * 60 * 24)
-            + ((*t).tm_hour as i64) * (60 * 60)
-            + ((*t).tm_min as i64) * 60
-            + (*t).tm_sec as i64)
-    }
+    let days = days_from_civil(
+        ((*t).tm_year + YEAR_BASE) as c_long,
+        (*t).tm_mon as c_long,
+        (*t).tm_mday as c_long,
+    );
+    let local = days * SECSPERDAY
+        + (*t).tm_hour as c_long * SECSPERHOUR
+        + (*t).tm_min as c_long * SECSPERMIN
+        + (*t).tm_sec as c_long;
+
+    let clock = tz::lock().zone.utc_time(local, (*t).tm_isdst);
+    localtime_r(&clock, t);
+    clock
 }
 
 #[no_mangle]
@@ -350,60 +334,74 @@ pub unsafe extern "C" fn strftime(
     }
 }
 
-// #[no_mangle]
-pub extern "C" fn strptime(buf: *const c_char, format: *const c_char, tm: *mut tm) -> *mut c_char {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn strptime(
+    buf: *const c_char,
+    format: *const c_char,
+    tm: *mut tm,
+) -> *mut c_char {
+    let format = CStr::from_ptr(format).to_bytes();
+    match strptime::strptime(CStr::from_ptr(buf).to_bytes(), format, &mut *tm) {
+        Some(len) => buf.offset(len as isize) as *mut c_char,
+        None => ptr::null_mut(),
+    }
 }
 
 #[no_mangle]
 pub extern "C" fn time(tloc: *mut time_t) -> time_t {
-    let mut ts: platform::types::timespec = Default::default();
-    Sys::clock_gettime(CLOCK_REALTIME, &mut ts);
-    unsafe {
-        if !tloc.is_null() {
-            *tloc = ts.tv_sec
-        };
-    }
-    ts.tv_sec
+    Sys::time(tloc)
 }
 
-// #[no_mangle]
-pub extern "C" fn timer_create(
+#[no_mangle]
+pub unsafe extern "C" fn timer_create(
     clock_id: clockid_t,
     evp: *mut sigevent,
     timerid: *mut timer_t,
 ) -> c_int {
-    unimplemented!();
+    let timer = timer::create(clock_id, evp);
+    if timer.is_null() {
+        return -1;
+    }
+    *timerid = timer as timer_t;
+    0
 }
 
-// #[no_mangle]
-pub extern "C" fn timer_delete(timerid: timer_t) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn timer_delete(timerid: timer_t) -> c_int {
+    timer::delete(timerid as *mut timer::Timer)
 }
 
-// #[no_mangle]
-pub extern "C" fn tzset() {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn timer_getoverrun(timerid: timer_t) -> c_int {
+    Sys::timer_getoverrun((*(timerid as *mut timer::Timer)).id)
 }
 
-// #[no_mangle]
-pub extern "C" fn timer_settime(
+#[no_mangle]
+pub unsafe extern "C" fn timer_gettime(timerid: timer_t, value: *mut itimerspec) -> c_int {
+    Sys::timer_gettime(
+        (*(timerid as *mut timer::Timer)).id,
+        value as *mut platform::types::itimerspec,
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn timer_settime(
     timerid: timer_t,
     flags: c_int,
     value: *const itimerspec,
     ovalue: *mut itimerspec,
 ) -> c_int {
-    unimplemented!();
-}
-
-// #[no_mangle]
-pub extern "C" fn timer_gettime(timerid: timer_t, value: *mut itimerspec) -> c_int {
-    unimplemented!();
+    Sys::timer_settime(
+        (*(timerid as *mut timer::Timer)).id,
+        flags,
+        value as *const platform::types::itimerspec,
+        ovalue as *mut platform::types::itimerspec,
+    )
 }
 
-// #[no_mangle]
-pub extern "C" fn timer_getoverrun(timerid: timer_t) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub extern "C" fn tzset() {
+    tz::lock();
 }
 
 /*
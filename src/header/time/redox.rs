@@ -2,3 +2,6 @@ use platform::types::*;
 
 pub const CLOCK_REALTIME: c_int = 1;
 pub const CLOCK_MONOTONIC: c_int = 4;
+pub const CLOCK_PROCESS_CPUTIME_ID: c_int = 2;
+pub const CLOCK_THREAD_CPUTIME_ID: c_int = 3;
+pub const CLOCK_BOOTTIME: c_int = 7;
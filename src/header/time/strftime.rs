@@ -1,8 +1,12 @@
 use alloc::string::String;
+use core::ptr;
+
+use c_str::CStr;
 
 use platform::types::*;
 use platform::{self, Write};
 
+use super::constants::{DAY_NAMES_FULL, MON_NAMES_FULL};
 use super::tm;
 
 pub unsafe fn strftime<W: Write>(w: &mut W, format: *const c_char, t: *const tm) -> size_t {
@@ -43,30 +47,6 @@ pub unsafe fn strftime<W: Write>(w: &mut W, format: *const c_char, t: *const tm)
                 }
             }};
         }
-        const WDAYS: [&'static str; 7] = [
-            "Sunday",
-            "Monday",
-            "Tuesday",
-            "Wednesday",
-            "Thursday",
-            "Friday",
-            "Saturday",
-        ];
-        const MONTHS: [&'static str; 12] = [
-            "January",
-            "Febuary",
-            "March",
-            "April",
-            "May",
-            "June",
-            "July",
-            "August",
-            "September",
-            "October",
-            "November",
-            "December",
-        ];
-
         while *format != 0 {
             if *format as u8 != b'%' {
                 w!(byte * format as u8);
@@ -85,10 +65,10 @@ pub unsafe fn strftime<W: Write>(w: &mut W, format: *const c_char, t: *const tm)
                 b'%' => w!(byte b'%'),
                 b'n' => w!(byte b'\n'),
                 b't' => w!(byte b'\t'),
-                b'a' => w!(&WDAYS[(*t).tm_wday as usize][..3]),
-                b'A' => w!(WDAYS[(*t).tm_wday as usize]),
-                b'b' | b'h' => w!(&MONTHS[(*t).tm_mon as usize][..3]),
-                b'B' => w!(MONTHS[(*t).tm_mon as usize]),
+                b'a' => w!(&DAY_NAMES_FULL[(*t).tm_wday as usize][..3]),
+                b'A' => w!(DAY_NAMES_FULL[(*t).tm_wday as usize]),
+                b'b' | b'h' => w!(&MON_NAMES_FULL[(*t).tm_mon as usize][..3]),
+                b'B' => w!(MON_NAMES_FULL[(*t).tm_mon as usize]),
                 b'C' => {
                     let mut year = (*t).tm_year / 100;
                     // Round up
@@ -112,8 +92,11 @@ pub unsafe fn strftime<W: Write>(w: &mut W, format: *const c_char, t: *const tm)
                 b'P' => w!(if (*t).tm_hour < 12 { "am" } else { "pm" }),
                 b'r' => w!(recurse "%I:%M:%S %p"),
                 b'R' => w!(recurse "%H:%M"),
-                // Nothing is modified in mktime, but the C standard of course requires a mutable pointer ._.
-                b's' => w!("{}", super::mktime(t as *mut tm)),
+                b's' => {
+                    // mktime normalizes the struct it is given, so give it a copy
+                    let mut copy = ptr::read(t);
+                    w!("{}", super::mktime(&mut copy));
+                }
                 b'S' => w!("{:02}", (*t).tm_sec),
                 b'T' => w!(recurse "%H:%M:%S"),
                 b'u' => w!("{}", ((*t).tm_wday + 7 - 1) % 7 + 1),
@@ -122,8 +105,23 @@ pub unsafe fn strftime<W: Write>(w: &mut W, format: *const c_char, t: *const tm)
                 b'W' => w!("{}", ((*t).tm_yday + 7 - ((*t).tm_wday + 6) % 7) / 7),
                 b'y' => w!("{:02}", (*t).tm_year % 100),
                 b'Y' => w!("{}", (*t).tm_year + 1900),
-                b'z' => w!("+0000"), // TODO
-                b'Z' => w!("UTC"),   // TODO
+                b'z' => {
+                    let offset = (*t).tm_gmtoff;
+                    let minutes = offset.abs() / 60;
+                    w!(
+                        "{}{:02}{:02}",
+                        if offset < 0 { '-' } else { '+' },
+                        minutes / 60,
+                        minutes % 60
+                    );
+                }
+                b'Z' => {
+                    if !(*t).tm_zone.is_null() {
+                        for &b in CStr::from_ptr((*t).tm_zone).to_bytes() {
+                            w!(byte b);
+                        }
+                    }
+                }
                 b'+' => w!(recurse "%a %b %d %T %Z %Y"),
                 _ => return false,
             }
@@ -0,0 +1,489 @@
+use alloc::Vec;
+use core::{mem, ptr};
+
+use c_str::CStr;
+use header::ctype;
+use header::fcntl::O_RDONLY;
+use header::stdlib::getenv;
+use header::sys_stat;
+use platform::types::*;
+use platform::{Pal, RawFile, Sys};
+
+use super::constants::*;
+use super::helpers::{civil_from_days, days_from_civil};
+use super::{localtime_r, mktime, time, tm, tzname, tzset};
+
+/// What getdate leaves in the fields of tm that the input does not set
+const UNSET: c_int = c_int::min_value();
+
+/// What the input said besides the fields of tm, or which of those it set
+#[derive(Default)]
+struct Parsed {
+    century: Option<c_int>,
+    year_in_century: Option<c_int>,
+    /// The hour was given on a 12-hour clock, and is kept in tm_hour as 0-11
+    hour12: bool,
+    pm: bool,
+    /// The week number, and whether weeks start on Monday rather than Sunday
+    week: Option<(c_int, bool)>,
+    year: bool,
+    mon: bool,
+    mday: bool,
+    wday: bool,
+    yday: bool,
+}
+
+fn is_space(c: u8) -> bool {
+    ctype::isspace(c as c_int) != 0
+}
+
+fn skip_spaces(s: &[u8]) -> usize {
+    s.iter().take_while(|&&c| is_space(c)).count()
+}
+
+fn starts_with_ignore_case(s: &[u8], prefix: &[u8]) -> bool {
+    s.len() >= prefix.len()
+        && s
+            .iter()
+            .zip(prefix)
+            .all(|(a, b)| a.to_ascii_lowercase() == b.to_ascii_lowercase())
+}
+
+/// Match the full or abbreviated name of one of `names`, returning its index
+fn name(s: &[u8], names: &[&str]) -> Option<(c_int, usize)> {
+    for (i, name) in names.iter().enumerate() {
+        let name = name.as_bytes();
+        if starts_with_ignore_case(s, name) {
+            return Some((i as c_int, name.len()));
+        }
+        if starts_with_ignore_case(s, &name[..3]) {
+            return Some((i as c_int, 3));
+        }
+    }
+    None
+}
+
+/// Match a number from `min` to `max` of at most `digits` digits, which may
+/// have spaces before it
+fn number(s: &[u8], min: c_int, max: c_int, digits: usize) -> Option<(c_int, usize)> {
+    let spaces = skip_spaces(s);
+    let len = s[spaces..]
+        .iter()
+        .take(digits)
+        .take_while(|c| c.is_ascii_digit())
+        .count();
+    if len == 0 {
+        return None;
+    }
+    let n = s[spaces..spaces + len]
+        .iter()
+        .fold(0, |n, &c| n * 10 + (c - b'0') as c_int);
+    if n < min || n > max {
+        return None;
+    }
+    Some((n, spaces + len))
+}
+
+/// Match a UTC offset like Z, +01, -0330 or +05:30, returning it in seconds
+fn offset(s: &[u8]) -> Option<(c_long, usize)> {
+    let sign = match s.first() {
+        Some(&b'Z') => return Some((0, 1)),
+        Some(&b'+') => 1,
+        Some(&b'-') => -1,
+        _ => return None,
+    };
+    let digits = |s: &[u8]| s.len() >= 2 && s[..2].iter().all(|c| c.is_ascii_digit());
+    let two = |s: &[u8]| ((s[0] - b'0') * 10 + s[1] - b'0') as c_long;
+
+    if !digits(&s[1..]) {
+        return None;
+    }
+    let hours = two(&s[1..]);
+    let mut len = 3;
+    let mut minutes = 0;
+    let colon = (s.get(len) == Some(&b':')) as usize;
+    if digits(&s[len + colon..]) {
+        minutes = two(&s[len + colon..]);
+        len += colon + 2;
+    }
+    if hours > 24 || minutes >= 60 {
+        return None;
+    }
+    Some((sign * (hours * SECSPERHOUR + minutes * SECSPERMIN), len))
+}
+
+impl Parsed {
+    /// Match `s` against `format`, returning how much of `s` matched
+    unsafe fn parse(&mut self, s: &[u8], format: &[u8], tm: &mut tm) -> Option<usize> {
+        let mut i = 0;
+        let mut f = 0;
+        while f < format.len() {
+            let c = format[f];
+            f += 1;
+            if is_space(c) {
+                i += skip_spaces(&s[i..]);
+                continue;
+            }
+            if c != b'%' {
+                if s.get(i) != Some(&c) {
+                    return None;
+                }
+                i += 1;
+                continue;
+            }
+
+            let mut spec = *format.get(f)?;
+            f += 1;
+            if spec == b'E' || spec == b'O' {
+                // Ignore because these do nothing without locale
+                spec = *format.get(f)?;
+                f += 1;
+            }
+
+            let s = &s[i..];
+            i += match spec {
+                b'%' => {
+                    if s.first() != Some(&b'%') {
+                        return None;
+                    }
+                    1
+                }
+                b'n' | b't' => skip_spaces(s),
+                b'a' | b'A' => {
+                    let (n, len) = name(s, &DAY_NAMES_FULL)?;
+                    tm.tm_wday = n;
+                    self.wday = true;
+                    len
+                }
+                b'b' | b'B' | b'h' => {
+                    let (n, len) = name(s, &MON_NAMES_FULL)?;
+                    tm.tm_mon = n;
+                    self.mon = true;
+                    len
+                }
+                b'c' => self.parse(s, b"%a %b %e %H:%M:%S %Y", tm)?,
+                b'C' => {
+                    let (n, len) = number(s, 0, 99, 2)?;
+                    self.century = Some(n);
+                    len
+                }
+                b'd' | b'e' => {
+                    let (n, len) = number(s, 1, 31, 2)?;
+                    tm.tm_mday = n;
+                    self.mday = true;
+                    len
+                }
+                b'D' | b'x' => self.parse(s, b"%m/%d/%y", tm)?,
+                b'F' => self.parse(s, b"%Y-%m-%d", tm)?,
+                b'H' | b'k' => {
+                    let (n, len) = number(s, 0, 23, 2)?;
+                    tm.tm_hour = n;
+                    self.hour12 = false;
+                    len
+                }
+                b'I' | b'l' => {
+                    let (n, len) = number(s, 1, 12, 2)?;
+                    tm.tm_hour = n % 12;
+                    self.hour12 = true;
+                    len
+                }
+                b'j' => {
+                    let (n, len) = number(s, 1, 366, 3)?;
+                    tm.tm_yday = n - 1;
+                    self.yday = true;
+                    len
+                }
+                b'm' => {
+                    let (n, len) = number(s, 1, 12, 2)?;
+                    tm.tm_mon = n - 1;
+                    self.mon = true;
+                    len
+                }
+                b'M' => {
+                    let (n, len) = number(s, 0, 59, 2)?;
+                    tm.tm_min = n;
+                    len
+                }
+                b'p' | b'P' => {
+                    if starts_with_ignore_case(s, b"AM") {
+                        self.pm = false;
+                    } else if starts_with_ignore_case(s, b"PM") {
+                        self.pm = true;
+                    } else {
+                        return None;
+                    }
+                    2
+                }
+                b'r' => self.parse(s, b"%I:%M:%S %p", tm)?,
+                b'R' => self.parse(s, b"%H:%M", tm)?,
+                b's' => {
+                    let negative = s.first() == Some(&b'-');
+                    let digits = s[negative as usize..]
+                        .iter()
+                        .take_while(|c| c.is_ascii_digit())
+                        .count();
+                    if digits == 0 {
+                        return None;
+                    }
+                    let mut clock: time_t = 0;
+                    for &c in &s[negative as usize..negative as usize + digits] {
+                        clock = clock.checked_mul(10)?.checked_add((c - b'0') as time_t)?;
+                    }
+                    if negative {
+                        clock = -clock;
+                    }
+                    localtime_r(&clock, tm);
+                    negative as usize + digits
+                }
+                b'S' => {
+                    let (n, len) = number(s, 0, 60, 2)?;
+                    tm.tm_sec = n;
+                    len
+                }
+                b'T' | b'X' => self.parse(s, b"%H:%M:%S", tm)?,
+                b'u' => {
+                    let (n, len) = number(s, 1, 7, 1)?;
+                    tm.tm_wday = n % 7;
+                    self.wday = true;
+                    len
+                }
+                b'U' | b'W' => {
+                    let (n, len) = number(s, 0, 53, 2)?;
+                    self.week = Some((n, spec == b'W'));
+                    len
+                }
+                b'w' => {
+                    let (n, len) = number(s, 0, 6, 1)?;
+                    tm.tm_wday = n;
+                    self.wday = true;
+                    len
+                }
+                b'y' => {
+                    let (n, len) = number(s, 0, 99, 2)?;
+                    self.year_in_century = Some(n);
+                    len
+                }
+                b'Y' => {
+                    let (n, len) = number(s, 0, 9999, 4)?;
+                    tm.tm_year = n - YEAR_BASE;
+                    self.year = true;
+                    self.century = None;
+                    self.year_in_century = None;
+                    len
+                }
+                b'z' => {
+                    let (n, len) = offset(s)?;
+                    tm.tm_gmtoff = n;
+                    len
+                }
+                b'Z' => {
+                    // Only the names of the local time zone mean anything
+                    let len = s.iter().take_while(|&&c| !is_space(c)).count();
+                    let zone = &s[..len];
+                    tzset();
+                    for (isdst, &name) in tzname.iter().enumerate() {
+                        if zone == CStr::from_ptr(name).to_bytes() {
+                            tm.tm_isdst = isdst as c_int;
+                            break;
+                        }
+                    }
+                    len
+                }
+                b'+' => self.parse(s, b"%a %b %d %T %Z %Y", tm)?,
+                _ => return None,
+            };
+        }
+        Some(i)
+    }
+
+    /// Fill in what follows from the fields given, like the day of the week
+    /// from the date
+    fn finish(&self, tm: &mut tm) {
+        match (self.century, self.year_in_century) {
+            (century, Some(year)) => {
+                let century = century.unwrap_or(if year < 69 { 20 } else { 19 });
+                tm.tm_year = century * 100 + year - YEAR_BASE;
+            }
+            (Some(century), None) => tm.tm_year = century * 100 - YEAR_BASE,
+            (None, None) => (),
+        }
+        let year_given = self.year || self.century.is_some() || self.year_in_century.is_some();
+        if self.hour12 && self.pm {
+            tm.tm_hour += 12;
+        }
+
+        let year = (tm.tm_year + YEAR_BASE) as c_long;
+        let jan1 = days_from_civil(year, 0, 1);
+        let mut yday = self.yday;
+        if let Some((week, monday_first)) = self.week {
+            if year_given && self.wday && !yday {
+                // Week 1 starts on the first Sunday or Monday of the year
+                let first = monday_first as c_long;
+                let jan1_wday = ((jan1 + 4) % 7 + 7) % 7;
+                tm.tm_yday = ((7 - (jan1_wday - first)) % 7
+                    + (week as c_long - 1) * 7
+                    + (tm.tm_wday as c_long - first + 7) % 7) as c_int;
+                yday = true;
+            }
+        }
+
+        let days = if self.mon && self.mday {
+            days_from_civil(year, tm.tm_mon as c_long, tm.tm_mday as c_long)
+        } else if year_given && yday {
+            let days = jan1 + tm.tm_yday as c_long;
+            let (_, mon, mday, _) = civil_from_days(days + EPOCH_ADJUSTMENT_DAYS);
+            tm.tm_mon = mon;
+            tm.tm_mday = mday;
+            days
+        } else {
+            return;
+        };
+        if !self.wday {
+            tm.tm_wday = (((days + 4) % 7 + 7) % 7) as c_int;
+        }
+        if !yday {
+            tm.tm_yday = (days - jan1) as c_int;
+        }
+    }
+}
+
+/// Parse `s` as described by `format` into `tm`, returning how much of `s`
+/// was used
+pub unsafe fn strptime(s: &[u8], format: &[u8], tm: &mut tm) -> Option<usize> {
+    let mut parsed = Parsed::default();
+    let len = parsed.parse(s, format, tm)?;
+    parsed.finish(tm);
+    Some(len)
+}
+
+fn days_in_month(year: c_long, mon: c_int) -> c_long {
+    days_from_civil(year, mon as c_long + 1, 1) - days_from_civil(year, mon as c_long, 1)
+}
+
+/// Parse `s` with the first template in the DATEMSK file that matches all of
+/// it, filling in what it leaves out from the current time. Errors are the
+/// codes for getdate_err.
+pub unsafe fn getdate(s: &[u8], result: &mut tm) -> Result<(), c_int> {
+    let path = getenv(b"DATEMSK\0".as_ptr() as *const c_char);
+    if path.is_null() {
+        return Err(1);
+    }
+    let mut st: stat = mem::zeroed();
+    if sys_stat::stat(path, &mut st) < 0 {
+        return Err(3);
+    }
+    if st.st_mode as c_int & sys_stat::S_IFMT != sys_stat::S_IFREG {
+        return Err(4);
+    }
+
+    let file = RawFile::open(CStr::from_ptr(path), O_RDONLY, 0).map_err(|_| 2)?;
+    let mut templates = Vec::new();
+    let mut buf = [0; 512];
+    loop {
+        match Sys::read(*file, &mut buf) {
+            0 => break,
+            n if n < 0 => return Err(5),
+            n => templates.extend_from_slice(&buf[..n as usize]),
+        }
+    }
+
+    // Trailing spaces never keep a template from matching
+    let len = s.len() - s.iter().rev().take_while(|&&c| is_space(c)).count();
+    let s = &s[..len];
+
+    let mut tm: tm = mem::zeroed();
+    let mut matched = false;
+    for template in templates.split(|&c| c == b'\n') {
+        if template.is_empty() {
+            continue;
+        }
+        tm.tm_sec = UNSET;
+        tm.tm_min = UNSET;
+        tm.tm_hour = UNSET;
+        tm.tm_mday = UNSET;
+        tm.tm_mon = UNSET;
+        tm.tm_year = UNSET;
+        tm.tm_wday = UNSET;
+        if strptime(s, template, &mut tm) == Some(s.len()) {
+            matched = true;
+            break;
+        }
+    }
+    if !matched {
+        return Err(7);
+    }
+
+    let now = time(ptr::null_mut());
+    let mut current: tm = mem::zeroed();
+    localtime_r(&now, &mut current);
+    let weekday_given = tm.tm_wday >= 0 && tm.tm_wday <= 6;
+    let mut mday_derived = false;
+
+    // Only a weekday means today if it is today, or else the next one
+    if weekday_given && tm.tm_year == UNSET && tm.tm_mon == UNSET && tm.tm_mday == UNSET {
+        tm.tm_year = current.tm_year;
+        tm.tm_mon = current.tm_mon;
+        tm.tm_mday = current.tm_mday + (tm.tm_wday - current.tm_wday + 7) % 7;
+        mday_derived = true;
+    }
+
+    // A month without a day means its first day, or the first of the
+    // weekday given. Without a year, it means the next time it comes around.
+    if tm.tm_mon != UNSET && tm.tm_mday == UNSET {
+        if tm.tm_year == UNSET {
+            tm.tm_year = current.tm_year + (tm.tm_mon < current.tm_mon) as c_int;
+        }
+        tm.tm_mday = 1;
+        if weekday_given {
+            let first = days_from_civil((tm.tm_year + YEAR_BASE) as c_long, tm.tm_mon as c_long, 1);
+            tm.tm_mday += ((tm.tm_wday as c_long - (first + 4) % 7 + 7) % 7) as c_int;
+        }
+        mday_derived = true;
+    }
+
+    if tm.tm_hour == UNSET && tm.tm_min == UNSET && tm.tm_sec == UNSET {
+        tm.tm_hour = current.tm_hour;
+        tm.tm_min = current.tm_min;
+        tm.tm_sec = current.tm_sec;
+    }
+    if tm.tm_hour == UNSET {
+        tm.tm_hour = 0;
+    }
+    if tm.tm_min == UNSET {
+        tm.tm_min = 0;
+    }
+    if tm.tm_sec == UNSET {
+        tm.tm_sec = 0;
+    }
+
+    // Only a time means today if it is still to come, or else tomorrow
+    if tm.tm_year == UNSET && tm.tm_mon == UNSET && tm.tm_mday == UNSET && tm.tm_wday == UNSET {
+        tm.tm_year = current.tm_year;
+        tm.tm_mon = current.tm_mon;
+        tm.tm_mday = current.tm_mday + (tm.tm_hour < current.tm_hour) as c_int;
+        mday_derived = true;
+    }
+
+    if tm.tm_year == UNSET {
+        tm.tm_year = current.tm_year;
+    }
+    if tm.tm_mon == UNSET {
+        tm.tm_mon = current.tm_mon;
+    }
+    if tm.tm_mday == UNSET {
+        tm.tm_mday = current.tm_mday;
+    }
+    if !mday_derived
+        && (tm.tm_mday < 1
+            || tm.tm_mday as c_long > days_in_month((tm.tm_year + YEAR_BASE) as c_long, tm.tm_mon))
+    {
+        return Err(8);
+    }
+
+    tm.tm_isdst = -1;
+    if mktime(&mut tm) == -1 {
+        return Err(8);
+    }
+    *result = tm;
+    Ok(())
+}
@@ -0,0 +1,182 @@
+//! Per-process timers on top of the kernel's. A SIGEV_THREAD timer gets a
+//! helper thread that the kernel signals directly and that calls the notify
+//! function each time the timer fires.
+
+use alloc::boxed::Box;
+use core::{intrinsics, mem, ptr};
+
+use header::errno::EINVAL;
+use header::pthread::{
+    pthread_attr_init, pthread_attr_t, pthread_create, pthread_t, PTHREAD_CREATE_DETACHED,
+};
+use header::signal::{SIGALRM, SIG_BLOCK};
+use platform;
+use platform::types::*;
+use platform::{Pal, PalSignal, Sys};
+use sync;
+
+use super::{sigevent, sigval, SIGEV_NONE, SIGEV_SIGNAL, SIGEV_THREAD};
+
+/// The signal helper threads wait for, kept from applications like glibc and
+/// musl do
+const SIGTIMER: c_int = 32;
+/// Signal one particular thread instead of the process
+const SIGEV_THREAD_ID: c_int = 4;
+
+const STARTING: c_int = 0;
+const READY: c_int = 1;
+const DELETED: c_int = 2;
+
+pub struct Timer {
+    /// The kernel's id for the timer
+    pub id: c_int,
+    /// Set for SIGEV_THREAD timers, which are freed by their helper thread
+    thread: bool,
+    notify: Option<extern "C" fn(sigval)>,
+    value: sigval,
+    state: c_int,
+    tid: pid_t,
+}
+
+unsafe extern "C" fn helper(arg: *mut c_void) -> *mut c_void {
+    let timer = arg as *mut Timer;
+
+    // Leave every other signal to the threads of the application
+    let all: sigset_t = !0;
+    Sys::sigprocmask(SIG_BLOCK, &all, ptr::null_mut());
+
+    (*timer).tid = Sys::gettid();
+    intrinsics::atomic_store(&mut (*timer).state, READY);
+    sync::futex_wake(&mut (*timer).state, 1);
+
+    let set: sigset_t = 1 << (SIGTIMER - 1);
+    loop {
+        let sig = Sys::sigtimedwait(&set, ptr::null());
+        if intrinsics::atomic_load(&(*timer).state) == DELETED {
+            break;
+        }
+        if sig == SIGTIMER {
+            if let Some(notify) = (*timer).notify {
+                notify((*timer).value);
+            }
+        }
+    }
+
+    drop(Box::from_raw(timer));
+    ptr::null_mut()
+}
+
+/// Start the helper thread of `timer` and wait until it is ready to be
+/// signalled
+unsafe fn start_helper(timer: *mut Timer, attr: *const pthread_attr_t) -> bool {
+    let mut helper_attr = if attr.is_null() {
+        let mut attr: pthread_attr_t = mem::uninitialized();
+        pthread_attr_init(&mut attr);
+        attr
+    } else {
+        ptr::read(attr)
+    };
+    // Nobody is going to join it
+    helper_attr.detachstate = PTHREAD_CREATE_DETACHED;
+
+    let mut thread: pthread_t = ptr::null_mut();
+    let err = pthread_create(
+        &mut thread,
+        &helper_attr,
+        Some(helper),
+        timer as *mut c_void,
+    );
+    if err != 0 {
+        platform::errno = err;
+        return false;
+    }
+
+    loop {
+        let state = intrinsics::atomic_load(&(*timer).state);
+        if state != STARTING {
+            break;
+        }
+        sync::futex_wait(&mut (*timer).state, state, None);
+    }
+    true
+}
+
+/// Have the helper thread of `timer` free it and exit
+unsafe fn stop_helper(timer: *mut Timer) {
+    // The timer may be gone as soon as the helper sees it is deleted
+    let tid = (*timer).tid;
+    intrinsics::atomic_store(&mut (*timer).state, DELETED);
+    Sys::tkill(tid, SIGTIMER);
+}
+
+/// Create a timer that notifies as `evp` says, or with SIGALRM if it is null
+pub unsafe fn create(clock_id: clockid_t, evp: *const sigevent) -> *mut Timer {
+    let timer = Box::into_raw(Box::new(Timer {
+        id: 0,
+        thread: false,
+        notify: None,
+        value: sigval { sival_int: 0 },
+        state: STARTING,
+        tid: 0,
+    }));
+
+    let mut kernel_evp: platform::types::sigevent = mem::zeroed();
+    if evp.is_null() {
+        // The kernel would put its own id in the value, not ours
+        kernel_evp.sigev_value = timer as *mut c_void;
+        kernel_evp.sigev_signo = SIGALRM as c_int;
+        kernel_evp.sigev_notify = SIGEV_SIGNAL;
+    } else {
+        let evp = &*evp;
+        match evp.sigev_notify {
+            SIGEV_SIGNAL | SIGEV_NONE => {
+                kernel_evp.sigev_value = evp.sigev_value.sival_ptr;
+                kernel_evp.sigev_signo = evp.sigev_signo;
+                kernel_evp.sigev_notify = evp.sigev_notify;
+            }
+            SIGEV_THREAD if evp.sigev_notify_function.is_some() => {
+                (*timer).thread = true;
+                (*timer).notify = evp.sigev_notify_function;
+                (*timer).value = evp.sigev_value;
+                let attr = evp.sigev_notify_attributes as *const pthread_attr_t;
+                if !start_helper(timer, attr) {
+                    drop(Box::from_raw(timer));
+                    return ptr::null_mut();
+                }
+
+                kernel_evp.sigev_signo = SIGTIMER;
+                kernel_evp.sigev_notify = SIGEV_THREAD_ID;
+                kernel_evp.sigev_notify_thread_id = (*timer).tid;
+            }
+            _ => {
+                drop(Box::from_raw(timer));
+                platform::errno = EINVAL;
+                return ptr::null_mut();
+            }
+        }
+    }
+
+    if Sys::timer_create(clock_id, &mut kernel_evp, &mut (*timer).id) < 0 {
+        if (*timer).thread {
+            let errno = platform::errno;
+            stop_helper(timer);
+            platform::errno = errno;
+        } else {
+            drop(Box::from_raw(timer));
+        }
+        return ptr::null_mut();
+    }
+    timer
+}
+
+pub unsafe fn delete(timer: *mut Timer) -> c_int {
+    if Sys::timer_delete((*timer).id) < 0 {
+        return -1;
+    }
+    if (*timer).thread {
+        stop_helper(timer);
+    } else {
+        drop(Box::from_raw(timer));
+    }
+    0
+}
@@ -0,0 +1,574 @@
+//! Time zones, described either by a POSIX TZ rule like
+//! "EST5EDT,M3.2.0,M11.1.0" or by a TZif file from the zoneinfo database,
+//! see https://tools.ietf.org/html/rfc8536
+
+use alloc::boxed::Box;
+use alloc::Vec;
+
+use c_str::CStr;
+use header::stdlib::getenv;
+use platform::rawfile::file_read_all;
+use platform::types::*;
+use sync::{Mutex, MutexGuard};
+
+use super::constants::*;
+use super::helpers::{civil_from_days, days_from_civil};
+use super::{daylight, timezone, tzname};
+
+const ZONEINFO: &[u8] = b"/usr/share/zoneinfo/";
+const LOCALTIME: &[u8] = b"/etc/localtime";
+/// When daylight saving time starts and ends if a TZ rule does not say
+const DEFAULT_CHANGES: &[u8] = b",M3.2.0,M11.1.0";
+const HEADER_LEN: usize = 44;
+
+/// A kind of local time, such as EST or EDT
+#[derive(Clone, Copy)]
+pub struct LocalType {
+    /// Seconds east of UTC
+    pub offset: c_long,
+    pub isdst: bool,
+    /// The abbreviation with a nul after it. It is never freed, as tm_zone
+    /// and tzname point to it.
+    pub name: &'static [u8],
+}
+
+static NAMES: Mutex<Vec<&'static [u8]>> = Mutex::new(Vec::new());
+
+fn intern(name: &[u8]) -> &'static [u8] {
+    let mut names = NAMES.lock();
+    if let Some(&interned) = names
+        .iter()
+        .find(|interned| &interned[..interned.len() - 1] == name)
+    {
+        return interned;
+    }
+
+    let mut interned = name.to_vec();
+    interned.push(0);
+    let interned: &'static [u8] = Box::leak(interned.into_boxed_slice());
+    names.push(interned);
+    interned
+}
+
+/// The day of the year that daylight saving time starts or ends on
+#[derive(Clone, Copy)]
+enum Day {
+    /// Jn: day n from 1 to 365, never counting February 29
+    Julian(c_long),
+    /// n: day n from 0 to 365
+    Zero(c_long),
+    /// Mm.w.d: weekday d of week w of month m, where week 5 is the last
+    Weekday(c_long, c_long, c_long),
+}
+
+#[derive(Clone, Copy)]
+struct Change {
+    day: Day,
+    /// Seconds into the day, in the local time in use before the change
+    time: c_long,
+}
+
+impl Change {
+    /// When the change happens in `year`, as seconds since the epoch in the
+    /// local time in use before the change
+    fn local_time(&self, year: c_long) -> time_t {
+        let days = match self.day {
+            Day::Julian(n) => {
+                let leap = days_from_civil(year, 2, 1) - days_from_civil(year, 1, 1) == 29;
+                days_from_civil(year, 0, n) + (leap && n >= 60) as c_long
+            }
+            Day::Zero(n) => days_from_civil(year, 0, n + 1),
+            Day::Weekday(month, week, weekday) => {
+                let first = days_from_civil(year, month - 1, 1);
+                let next = days_from_civil(year, month, 1);
+                // January 1 1970 was a Thursday
+                let first_weekday = ((first + 4) % 7 + 7) % 7;
+                let mut day = first + (weekday - first_weekday + 7) % 7 + (week - 1) * 7;
+                while day >= next {
+                    day -= 7;
+                }
+                day
+            }
+        };
+        days * SECSPERDAY + self.time
+    }
+}
+
+/// The rule of a TZ string, which TZif files also use for the times after
+/// their last transition
+struct Rule {
+    std: LocalType,
+    dst: Option<(LocalType, Change, Change)>,
+}
+
+impl Rule {
+    fn find(&self, clock: time_t) -> LocalType {
+        let (dst, start, end) = match self.dst {
+            Some(dst) => dst,
+            None => return self.std,
+        };
+
+        let year = year(clock + self.std.offset);
+        let start = start.local_time(year) - self.std.offset;
+        let end = end.local_time(year) - dst.offset;
+        let in_dst = if start < end {
+            clock >= start && clock < end
+        } else {
+            // Daylight saving time goes on over the new year
+            clock < end || clock >= start
+        };
+        if in_dst {
+            dst
+        } else {
+            self.std
+        }
+    }
+
+    fn opposite(&self, isdst: bool) -> Option<LocalType> {
+        if isdst {
+            Some(self.std)
+        } else {
+            self.dst.map(|(dst, _, _)| dst)
+        }
+    }
+
+    /// Parse a TZ rule like "EST5EDT,M3.2.0,M11.1.0"
+    fn parse(s: &[u8]) -> Option<Rule> {
+        let (name, s) = parse_name(s)?;
+        let (offset, s) = parse_time(s)?;
+        let std = LocalType {
+            offset: -offset,
+            isdst: false,
+            name,
+        };
+        if s.is_empty() {
+            return Some(Rule { std, dst: None });
+        }
+
+        let (name, s) = parse_name(s)?;
+        let (offset, s) = match s.first() {
+            None | Some(&b',') => (std.offset + SECSPERHOUR, s),
+            _ => {
+                let (offset, s) = parse_time(s)?;
+                (-offset, s)
+            }
+        };
+        let dst = LocalType {
+            offset,
+            isdst: true,
+            name,
+        };
+
+        let s = if s.is_empty() { DEFAULT_CHANGES } else { s };
+        let (start, s) = parse_change(expect(s, b',')?)?;
+        let (end, s) = parse_change(expect(s, b',')?)?;
+        if !s.is_empty() {
+            return None;
+        }
+        Some(Rule {
+            std,
+            dst: Some((dst, start, end)),
+        })
+    }
+}
+
+/// The year `clock` seconds after the epoch falls in
+fn year(clock: time_t) -> c_long {
+    let mut days = clock / SECSPERDAY;
+    if clock % SECSPERDAY < 0 {
+        days -= 1;
+    }
+    civil_from_days(days + EPOCH_ADJUSTMENT_DAYS).0 as c_long
+}
+
+fn expect(s: &[u8], c: u8) -> Option<&[u8]> {
+    if s.first() == Some(&c) {
+        Some(&s[1..])
+    } else {
+        None
+    }
+}
+
+/// Parse a name like EST, or one in angle brackets like <+0330>
+fn parse_name(s: &[u8]) -> Option<(&'static [u8], &[u8])> {
+    let (name, rest) = match expect(s, b'<') {
+        Some(s) => {
+            let len = s.iter().position(|&c| c == b'>')?;
+            let name = &s[..len];
+            if !name
+                .iter()
+                .all(|&c| c.is_ascii_alphanumeric() || c == b'+' || c == b'-')
+            {
+                return None;
+            }
+            (name, &s[len + 1..])
+        }
+        None => {
+            let len = s.iter().take_while(|c| c.is_ascii_alphabetic()).count();
+            (&s[..len], &s[len..])
+        }
+    };
+    if name.len() < 3 {
+        return None;
+    }
+    Some((intern(name), rest))
+}
+
+fn parse_number(s: &[u8]) -> Option<(c_long, &[u8])> {
+    let len = s.iter().take_while(|c| c.is_ascii_digit()).count();
+    if len == 0 || len > 3 {
+        return None;
+    }
+    let n = s[..len]
+        .iter()
+        .fold(0, |n, &c| n * 10 + (c - b'0') as c_long);
+    Some((n, &s[len..]))
+}
+
+/// Parse [+|-]hh[:mm[:ss]] into seconds
+fn parse_time(s: &[u8]) -> Option<(c_long, &[u8])> {
+    let (sign, mut s) = match s.first() {
+        Some(&b'-') => (-1, &s[1..]),
+        Some(&b'+') => (1, &s[1..]),
+        _ => (1, s),
+    };
+
+    let (hours, rest) = parse_number(s)?;
+    let mut seconds = hours * SECSPERHOUR;
+    s = rest;
+    for &unit in &[SECSPERMIN, 1] {
+        match expect(s, b':') {
+            Some(rest) => {
+                let (n, rest) = parse_number(rest)?;
+                if n >= 60 {
+                    return None;
+                }
+                seconds += n * unit;
+                s = rest;
+            }
+            None => break,
+        }
+    }
+    Some((sign * seconds, s))
+}
+
+/// Parse a day like J60, 59 or M3.2.0, followed by an optional /time
+fn parse_change(s: &[u8]) -> Option<(Change, &[u8])> {
+    let (day, s) = match s.first() {
+        Some(&b'J') => {
+            let (n, s) = parse_number(&s[1..])?;
+            if n < 1 || n > 365 {
+                return None;
+            }
+            (Day::Julian(n), s)
+        }
+        Some(&b'M') => {
+            let (month, s) = parse_number(&s[1..])?;
+            let (week, s) = parse_number(expect(s, b'.')?)?;
+            let (weekday, s) = parse_number(expect(s, b'.')?)?;
+            if month < 1 || month > 12 || week < 1 || week > 5 || weekday > 6 {
+                return None;
+            }
+            (Day::Weekday(month, week, weekday), s)
+        }
+        _ => {
+            let (n, s) = parse_number(s)?;
+            if n > 365 {
+                return None;
+            }
+            (Day::Zero(n), s)
+        }
+    };
+
+    let (time, s) = match expect(s, b'/') {
+        Some(s) => parse_time(s)?,
+        None => (2 * SECSPERHOUR, s),
+    };
+    Some((Change { day, time }, s))
+}
+
+/// The big-endian, two's complement number in the first `len` bytes of `data`
+fn be(data: &[u8], len: usize) -> i64 {
+    let n = data[..len].iter().fold(0, |n, &b| n << 8 | b as u64);
+    let shift = 64 - 8 * len as u32;
+    ((n << shift) as i64) >> shift
+}
+
+struct Header {
+    version: u8,
+    isutcnt: usize,
+    isstdcnt: usize,
+    leapcnt: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+}
+
+impl Header {
+    fn parse(data: &[u8]) -> Option<Header> {
+        if data.len() < HEADER_LEN || &data[..4] != b"TZif" {
+            return None;
+        }
+        let count = |i: usize| be(&data[20 + 4 * i..], 4) as u32 as usize;
+        Some(Header {
+            version: data[4],
+            isutcnt: count(0),
+            isstdcnt: count(1),
+            leapcnt: count(2),
+            timecnt: count(3),
+            typecnt: count(4),
+            charcnt: count(5),
+        })
+    }
+
+    /// The length of the data after the header, with times of `time_len`
+    /// bytes
+    fn data_len(&self, time_len: usize) -> usize {
+        self.timecnt * (time_len + 1)
+            + self.typecnt * 6
+            + self.charcnt
+            + self.leapcnt * (time_len + 4)
+            + self.isstdcnt
+            + self.isutcnt
+    }
+}
+
+pub struct Zone {
+    /// When the local time changes, and the index in `types` of the kind of
+    /// local time it changes to
+    transitions: Vec<(time_t, usize)>,
+    types: Vec<LocalType>,
+    /// The local time after the last transition
+    rule: Option<Rule>,
+}
+
+impl Zone {
+    fn from_rule(rule: Rule) -> Zone {
+        Zone {
+            transitions: Vec::new(),
+            types: Vec::new(),
+            rule: Some(rule),
+        }
+    }
+
+    fn utc() -> Zone {
+        Zone::from_rule(Rule {
+            std: LocalType {
+                offset: 0,
+                isdst: false,
+                name: intern(b"UTC"),
+            },
+            dst: None,
+        })
+    }
+
+    /// The zone that the value of TZ describes, or the system's zone if TZ
+    /// is not set
+    fn load(tz: Option<&[u8]>) -> Zone {
+        let zone = match tz {
+            None => Zone::from_file(LOCALTIME),
+            Some(tz) => match expect(tz, b':') {
+                Some(name) => Zone::from_file(name),
+                None if tz.is_empty() => None,
+                None => Zone::from_file(tz).or_else(|| Rule::parse(tz).map(Zone::from_rule)),
+            },
+        };
+        zone.unwrap_or_else(Zone::utc)
+    }
+
+    fn from_file(name: &[u8]) -> Option<Zone> {
+        let mut path = Vec::new();
+        if name.first() != Some(&b'/') {
+            path.extend_from_slice(ZONEINFO);
+        }
+        path.extend_from_slice(name);
+        path.push(0);
+
+        let data = file_read_all(unsafe { CStr::from_bytes_with_nul_unchecked(&path) }).ok()?;
+        Zone::parse(&data)
+    }
+
+    fn parse(data: &[u8]) -> Option<Zone> {
+        let header = Header::parse(data)?;
+        if header.version == 0 {
+            return Zone::parse_data(&header, &data[HEADER_LEN..], 4).map(|(zone, _)| zone);
+        }
+
+        // Skip the data for version 1 readers, whose times end in 2038
+        let data = data.get(HEADER_LEN + header.data_len(4)..)?;
+        let header = Header::parse(data)?;
+        let (mut zone, footer) = Zone::parse_data(&header, &data[HEADER_LEN..], 8)?;
+        if let Some(footer) = expect(footer, b'\n') {
+            if let Some(len) = footer.iter().position(|&c| c == b'\n') {
+                zone.rule = Rule::parse(&footer[..len]);
+            }
+        }
+        Some(zone)
+    }
+
+    /// Parse the data following `header`, returning the zone and what comes
+    /// after the data
+    fn parse_data<'a>(
+        header: &Header,
+        data: &'a [u8],
+        time_len: usize,
+    ) -> Option<(Zone, &'a [u8])> {
+        let len = header.data_len(time_len);
+        if data.len() < len || header.typecnt == 0 {
+            return None;
+        }
+        let (times, rest) = data.split_at(header.timecnt * time_len);
+        let (indices, rest) = rest.split_at(header.timecnt);
+        let (infos, rest) = rest.split_at(header.typecnt * 6);
+        let names = &rest[..header.charcnt];
+
+        let mut types = Vec::with_capacity(header.typecnt);
+        for info in infos.chunks(6) {
+            let name = names.get(info[5] as usize..)?;
+            let name_len = name.iter().position(|&c| c == 0)?;
+            types.push(LocalType {
+                offset: be(info, 4) as c_long,
+                isdst: info[4] != 0,
+                name: intern(&name[..name_len]),
+            });
+        }
+
+        // Leap seconds are ignored, like POSIX wants
+        let mut transitions = Vec::with_capacity(header.timecnt);
+        for (time, &index) in times.chunks(time_len).zip(indices) {
+            if index as usize >= types.len() {
+                return None;
+            }
+            transitions.push((be(time, time_len) as time_t, index as usize));
+        }
+
+        let zone = Zone {
+            transitions,
+            types,
+            rule: None,
+        };
+        Some((zone, &data[len..]))
+    }
+
+    /// How many transitions happened by `clock`
+    fn passed(&self, clock: time_t) -> usize {
+        match self
+            .transitions
+            .binary_search_by(|&(time, _)| time.cmp(&clock))
+        {
+            Ok(i) => i + 1,
+            Err(i) => i,
+        }
+    }
+
+    /// The kind of local time in use at `clock`
+    pub fn find(&self, clock: time_t) -> LocalType {
+        let passed = self.passed(clock);
+        match self.rule {
+            Some(ref rule) if passed == self.transitions.len() => rule.find(clock),
+            // Before the first transition, the first kind of local time is
+            // in use
+            _ if passed == 0 => self.types[0],
+            _ => self.types[self.transitions[passed - 1].1],
+        }
+    }
+
+    /// The nearest kind of local time to `clock` that is daylight saving
+    /// time if `isdst` is not, or standard time if it is
+    fn opposite(&self, clock: time_t, isdst: bool) -> Option<LocalType> {
+        let passed = self.passed(clock);
+        if let Some(ref rule) = self.rule {
+            if passed == self.transitions.len() {
+                return rule.opposite(isdst);
+            }
+        }
+
+        let before = self.transitions[..passed].iter().rev();
+        let after = self.transitions[passed..].iter();
+        before
+            .chain(after)
+            .map(|&(_, i)| self.types[i])
+            .find(|local_type| local_type.isdst != isdst)
+    }
+
+    /// The seconds since the epoch at the local time `local`, seconds since
+    /// the epoch as if it were UTC. Like in mktime, `isdst` says whether the
+    /// time is daylight saving time, if it is not negative.
+    pub fn utc_time(&self, local: time_t, isdst: c_int) -> time_t {
+        let mut local_type = self.find(local - self.find(local).offset);
+        let actual = self.find(local - local_type.offset);
+        if actual.offset != local_type.offset {
+            // The clocks skipped over the time, so go by the kind of local
+            // time used before the skip
+            local_type = actual;
+        }
+
+        if isdst >= 0 && local_type.isdst != (isdst > 0) {
+            if let Some(opposite) = self.opposite(local - local_type.offset, local_type.isdst) {
+                local_type = opposite;
+            }
+        }
+        local - local_type.offset
+    }
+
+    /// Standard time and daylight saving time as last used
+    fn current(&self) -> (LocalType, Option<LocalType>) {
+        if let Some(ref rule) = self.rule {
+            return (rule.std, rule.opposite(false));
+        }
+
+        let last = |isdst| {
+            self.transitions
+                .iter()
+                .rev()
+                .map(|&(_, i)| self.types[i])
+                .find(|local_type| local_type.isdst == isdst)
+        };
+        (last(false).unwrap_or(self.types[0]), last(true))
+    }
+
+    unsafe fn export(&self) {
+        let (std, dst) = self.current();
+        tzname[0] = std.name.as_ptr() as *mut c_char;
+        tzname[1] = dst.unwrap_or(std).name.as_ptr() as *mut c_char;
+        timezone = -std.offset;
+        daylight = dst.is_some() as c_int;
+    }
+}
+
+pub struct State {
+    /// The value of TZ the zone was loaded for
+    tz: Option<Vec<u8>>,
+    loaded: bool,
+    pub zone: Zone,
+}
+
+static STATE: Mutex<State> = Mutex::new(State {
+    tz: None,
+    loaded: false,
+    zone: Zone {
+        transitions: Vec::new(),
+        types: Vec::new(),
+        rule: None,
+    },
+});
+
+/// Lock the time zone, loading it first if TZ changed since the last time,
+/// and updating tzname, timezone and daylight to match
+pub fn lock() -> MutexGuard<'static, State> {
+    let mut state = STATE.lock();
+    let tz = unsafe { getenv(b"TZ\0".as_ptr() as *const c_char) };
+    let tz = if tz.is_null() {
+        None
+    } else {
+        Some(unsafe { CStr::from_ptr(tz) }.to_bytes())
+    };
+
+    if !state.loaded || state.tz.as_ref().map(|tz| &tz[..]) != tz {
+        state.zone = Zone::load(tz);
+        state.tz = tz.map(|tz| tz.to_vec());
+        state.loaded = true;
+        unsafe { state.zone.export() };
+    }
+    state
+}
@@ -3,7 +3,8 @@
 use core::{ptr, slice};
 
 use c_str::CStr;
-use header::sys_time;
+use header::stdlib::getenv;
+use header::{errno, pthread, sys_random, sys_time};
 use platform;
 use platform::types::*;
 use platform::{Pal, Sys};
@@ -11,10 +12,12 @@ use platform::{Pal, Sys};
 pub use self::brk::*;
 pub use self::getopt::*;
 pub use self::pathconf::*;
+pub use self::sysconf::*;
 
 mod brk;
 mod getopt;
 mod pathconf;
+mod sysconf;
 
 pub const F_OK: c_int = 0;
 pub const R_OK: c_int = 4;
@@ -34,7 +37,14 @@ pub const STDIN_FILENO: c_int = 0;
 pub const STDOUT_FILENO: c_int = 1;
 pub const STDERR_FILENO: c_int = 2;
 
+pub const _CS_PATH: c_int = 0;
+
 const PATH_MAX: usize = 4096;
+/// Where to look for programs when PATH is not set
+const DEFAULT_PATH: &[u8] = b"/bin:/usr/bin";
+/// The most arguments for the shell when running a script, which are made on
+/// the stack as exec must not allocate
+const SHELL_ARGS_MAX: usize = 1024;
 
 #[no_mangle]
 pub extern "C" fn _exit(status: c_int) {
@@ -91,9 +101,23 @@ pub extern "C" fn close(fildes: c_int) -> c_int {
     Sys::close(fildes)
 }
 
-// #[no_mangle]
-pub extern "C" fn confstr(name: c_int, buf: *mut c_char, len: size_t) -> size_t {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn confstr(name: c_int, buf: *mut c_char, len: size_t) -> size_t {
+    let value: &[u8] = match name {
+        // Where the standard utilities are found
+        _CS_PATH => DEFAULT_PATH,
+        _ => {
+            platform::errno = errno::EINVAL;
+            return 0;
+        }
+    };
+
+    if !buf.is_null() && len > 0 {
+        let copied = value.len().min(len - 1);
+        ptr::copy_nonoverlapping(value.as_ptr() as *const c_char, buf, copied);
+        *buf.offset(copied as isize) = 0;
+    }
+    value.len() + 1
 }
 
 // #[no_mangle]
@@ -116,24 +140,7 @@ pub extern "C" fn encrypt(block: [c_char; 64], edflag: c_int) {
     unimplemented!();
 }
 
-// #[no_mangle]
-// pub extern "C" fn execl(path: *const c_char, args: *const *mut c_char) -> c_int {
-//     unimplemented!();
-// }
-
-// #[no_mangle]
-// pub extern "C" fn execle(
-//   path: *const c_char,
-//   args: *const *mut c_char,
-//   envp: *const *mut c_char,
-// ) -> c_int {
-//     unimplemented!();
-// }
-
-// #[no_mangle]
-// pub extern "C" fn execlp(file: *const c_char, args: *const *mut c_char) -> c_int {
-//     unimplemented!();
-// }
+// execl, execle and execlp are variadic, so they are in src/c/unistd.c
 
 #[no_mangle]
 pub unsafe extern "C" fn execv(path: *const c_char, argv: *const *mut c_char) -> c_int {
@@ -150,9 +157,116 @@ pub unsafe extern "C" fn execve(
     Sys::execve(path, argv, envp)
 }
 
-// #[no_mangle]
-pub extern "C" fn execvp(file: *const c_char, argv: *const *mut c_char) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn execvp(file: *const c_char, argv: *const *mut c_char) -> c_int {
+    execvpe(file, argv, platform::environ)
+}
+
+/// Like execve, but falling back to the shell on ENOEXEC, as the kernel
+/// refuses files that are neither executables nor scripts with a #! line
+unsafe fn exec_file(path: &CStr, argv: *const *mut c_char, envp: *const *mut c_char) -> c_int {
+    Sys::execve(path, argv, envp);
+    if platform::errno != errno::ENOEXEC {
+        return -1;
+    }
+
+    // sh, the path of the script and the rest of argv, with room for the
+    // null at the end
+    let mut args = [ptr::null_mut(); SHELL_ARGS_MAX];
+    args[0] = b"sh\0".as_ptr() as *mut c_char;
+    args[1] = path.as_ptr() as *mut c_char;
+    let mut len = 2;
+    if !(*argv).is_null() {
+        let mut arg = argv.offset(1);
+        while !(*arg).is_null() {
+            if len == SHELL_ARGS_MAX - 1 {
+                platform::errno = errno::E2BIG;
+                return -1;
+            }
+            args[len] = *arg;
+            len += 1;
+            arg = arg.offset(1);
+        }
+    }
+
+    let shell = CStr::from_bytes_with_nul_unchecked(b"/bin/sh\0");
+    Sys::execve(shell, args.as_ptr(), envp);
+    platform::errno = errno::ENOEXEC;
+    -1
+}
+
+/// execvpe, without allocating. The child of posix_spawn shares the memory
+/// of its parent, and must not touch the allocator.
+pub unsafe fn exec_search(
+    file: *const c_char,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+) -> c_int {
+    let file = CStr::from_ptr(file);
+    let name = file.to_bytes();
+    if name.is_empty() {
+        platform::errno = errno::ENOENT;
+        return -1;
+    }
+    if name.contains(&b'/') {
+        return exec_file(file, argv, envp);
+    }
+
+    let path_var = getenv(b"PATH\0".as_ptr() as *const c_char);
+    let dirs = if path_var.is_null() {
+        DEFAULT_PATH
+    } else {
+        CStr::from_ptr(path_var).to_bytes()
+    };
+
+    let mut seen_eacces = false;
+    let mut buf = [0; PATH_MAX];
+    for dir in dirs.split(|&b| b == b':') {
+        // An empty entry is the current directory
+        let dir: &[u8] = if dir.is_empty() { b"." } else { dir };
+        let len = dir.len() + 1 + name.len();
+        if len >= PATH_MAX {
+            platform::errno = errno::ENAMETOOLONG;
+            continue;
+        }
+        buf[..dir.len()].copy_from_slice(dir);
+        buf[dir.len()] = b'/';
+        buf[dir.len() + 1..len].copy_from_slice(name);
+        buf[len] = 0;
+
+        let path = CStr::from_bytes_with_nul_unchecked(&buf[..=len]);
+        exec_file(path, argv, envp);
+        match platform::errno {
+            // Keep looking, but say why if nothing else is found
+            errno::EACCES => seen_eacces = true,
+            errno::ENOENT | errno::ENOTDIR | errno::ENAMETOOLONG => (),
+            errno::ELOOP | errno::ESTALE => (),
+            _ => return -1,
+        }
+    }
+
+    if seen_eacces {
+        platform::errno = errno::EACCES;
+    }
+    -1
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn execvpe(
+    file: *const c_char,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+) -> c_int {
+    exec_search(file, argv, envp)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn fexecve(
+    fildes: c_int,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+) -> c_int {
+    Sys::fexecve(fildes, argv, envp)
 }
 
 #[no_mangle]
@@ -172,7 +286,19 @@ pub extern "C" fn fdatasync(fildes: c_int) -> c_int {
 
 #[no_mangle]
 pub extern "C" fn fork() -> pid_t {
-    Sys::fork()
+    unsafe {
+        platform::fork_prepare();
+    }
+    let pid = Sys::fork();
+    unsafe {
+        if pid == 0 {
+            platform::fork_child();
+            pthread::fork_child();
+        } else {
+            platform::fork_parent();
+        }
+    }
+    pid
 }
 
 #[no_mangle]
@@ -226,6 +352,19 @@ pub extern "C" fn getegid() -> gid_t {
     Sys::getegid()
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn getentropy(buffer: *mut c_void, length: size_t) -> c_int {
+    if length > 256 {
+        platform::errno = errno::EIO;
+        return -1;
+    }
+    if sys_random::fill(slice::from_raw_parts_mut(buffer as *mut u8, length)) {
+        0
+    } else {
+        -1
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn geteuid() -> uid_t {
     Sys::geteuid()
@@ -261,9 +400,9 @@ pub extern "C" fn getlogin_r(name: *mut c_char, namesize: size_t) -> c_int {
     unimplemented!();
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn getpagesize() -> c_int {
-    unimplemented!();
+    sysconf(_SC_PAGESIZE) as c_int
 }
 
 // #[no_mangle]
@@ -291,9 +430,9 @@ pub extern "C" fn getppid() -> pid_t {
     Sys::getppid()
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn getsid(pid: pid_t) -> pid_t {
-    unimplemented!();
+    Sys::getsid(pid)
 }
 
 #[no_mangle]
@@ -379,9 +518,15 @@ pub extern "C" fn read(fildes: c_int, buf: *const c_void, nbyte: size_t) -> ssiz
     Sys::read(fildes, buf)
 }
 
-// #[no_mangle]
-pub extern "C" fn readlink(path: *const c_char, buf: *mut c_char, bufsize: size_t) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn readlink(
+    path: *const c_char,
+    buf: *mut c_char,
+    bufsize: size_t,
+) -> ssize_t {
+    let path = CStr::from_ptr(path);
+    let buf = slice::from_raw_parts_mut(buf as *mut u8, bufsize);
+    Sys::readlink(path, buf)
 }
 
 #[no_mangle]
@@ -400,9 +545,9 @@ pub extern "C" fn setpgid(pid: pid_t, pgid: pid_t) -> c_int {
     Sys::setpgid(pid, pgid)
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn setpgrp() -> pid_t {
-    unimplemented!();
+    Sys::setpgid(0, 0)
 }
 
 #[no_mangle]
@@ -415,9 +560,9 @@ pub extern "C" fn setreuid(ruid: uid_t, euid: uid_t) -> c_int {
     Sys::setreuid(ruid, euid)
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn setsid() -> pid_t {
-    unimplemented!();
+    Sys::setsid()
 }
 
 #[no_mangle]
@@ -451,19 +596,14 @@ pub extern "C" fn sync() {
     unimplemented!();
 }
 
-// #[no_mangle]
-pub extern "C" fn sysconf(name: c_int) -> c_long {
-    unimplemented!();
-}
-
-// #[no_mangle]
-pub extern "C" fn tcgetpgrp() -> pid_t {
-    unimplemented!();
+#[no_mangle]
+pub extern "C" fn tcgetpgrp(fildes: c_int) -> pid_t {
+    Sys::tcgetpgrp(fildes)
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn tcsetpgrp(fildes: c_int, pgid_id: pid_t) -> c_int {
-    unimplemented!();
+    Sys::tcsetpgrp(fildes, pgid_id)
 }
 
 // #[no_mangle]
@@ -522,9 +662,12 @@ pub extern "C" fn usleep(useconds: useconds_t) -> c_int {
     Sys::nanosleep(&rqtp, rmtp)
 }
 
-// #[no_mangle]
+/// A child that shared our stack would overwrite the frame it returns into,
+/// so this is fork, which POSIX allows. posix_spawn is the fast way to start
+/// a program.
+#[no_mangle]
 pub extern "C" fn vfork() -> pid_t {
-    unimplemented!();
+    fork()
 }
 
 #[no_mangle]
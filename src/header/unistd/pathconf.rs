@@ -1,4 +1,10 @@
+use c_str::CStr;
+use header::errno::EINVAL;
+use platform;
 use platform::types::*;
+use platform::{Pal, Sys};
+
+use super::PATH_MAX;
 
 pub const _PC_LINK_MAX: c_int = 0;
 pub const _PC_MAX_CANON: c_int = 1;
@@ -22,12 +28,77 @@ pub const _PC_ALLOC_SIZE_MIN: c_int = 18;
 pub const _PC_SYMLINK_MAX: c_int = 19;
 pub const _PC_2_SYMLINKS: c_int = 20;
 
-// #[no_mangle]
+const MAX_CANON: c_long = 255;
+const MAX_INPUT: c_long = 255;
+const PIPE_BUF: c_long = 4096;
+/// For file systems that do not report their name length
+const NAME_MAX: c_long = 255;
+
+const EXT_SUPER_MAGIC: c_long = 0xEF53;
+const BTRFS_SUPER_MAGIC: c_long = 0x9123_683E;
+const XFS_SUPER_MAGIC: c_long = 0x5846_5342;
+
+/// How many links a file on a file system of type `f_type` can have
+fn link_max(f_type: c_long) -> c_long {
+    match f_type {
+        // ext4 allows more, but ext2 and ext3 share its magic
+        EXT_SUPER_MAGIC => 32000,
+        BTRFS_SUPER_MAGIC => 65535,
+        XFS_SUPER_MAGIC => 0x7FFF_FFFF,
+        _ => 127,
+    }
+}
+
+/// Look up `name` for a file, calling `get_statfs` about its file system only for
+/// the values that depend on it
+fn pathconf_fs<F: FnOnce(*mut statfs) -> c_int>(name: c_int, get_statfs: F) -> c_long {
+    match name {
+        _PC_MAX_CANON => MAX_CANON,
+        _PC_MAX_INPUT => MAX_INPUT,
+        _PC_PATH_MAX => PATH_MAX as c_long,
+        _PC_PIPE_BUF => PIPE_BUF,
+        _PC_CHOWN_RESTRICTED | _PC_NO_TRUNC | _PC_2_SYMLINKS => 1,
+        _PC_VDISABLE => 0,
+        _PC_FILESIZEBITS => 64,
+        // Unsupported, or without a limit
+        _PC_SYNC_IO
+        | _PC_ASYNC_IO
+        | _PC_PRIO_IO
+        | _PC_SOCK_MAXBUF
+        | _PC_REC_INCR_XFER_SIZE
+        | _PC_REC_MAX_XFER_SIZE
+        | _PC_SYMLINK_MAX => -1,
+        _PC_LINK_MAX
+        | _PC_NAME_MAX
+        | _PC_REC_MIN_XFER_SIZE
+        | _PC_REC_XFER_ALIGN
+        | _PC_ALLOC_SIZE_MIN => {
+            let mut buf = statfs::default();
+            if get_statfs(&mut buf) < 0 {
+                return -1;
+            }
+            match name {
+                _PC_LINK_MAX => link_max(buf.f_type),
+                _PC_NAME_MAX if buf.f_namelen > 0 => buf.f_namelen,
+                _PC_NAME_MAX => NAME_MAX,
+                _PC_ALLOC_SIZE_MIN => buf.f_frsize,
+                _ => buf.f_bsize,
+            }
+        }
+        _ => {
+            unsafe { platform::errno = EINVAL };
+            -1
+        }
+    }
+}
+
+#[no_mangle]
 pub extern "C" fn fpathconf(fildes: c_int, name: c_int) -> c_long {
-    unimplemented!();
+    pathconf_fs(name, |buf| Sys::fstatfs(fildes, buf))
 }
 
-// #[no_mangle]
-pub extern "C" fn pathconf(path: *const c_char, name: c_int) -> c_long {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn pathconf(path: *const c_char, name: c_int) -> c_long {
+    let path = CStr::from_ptr(path);
+    pathconf_fs(name, |buf| Sys::statfs(path, buf))
 }
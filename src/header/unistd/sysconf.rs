@@ -0,0 +1,105 @@
+use core::str;
+
+use c_str::CStr;
+use header::errno::EINVAL;
+use header::sys_auxv::{self, AT_CLKTCK, AT_PAGESZ};
+use header::sys_resource::{RLIMIT_NOFILE, RLIMIT_STACK, RLIM_INFINITY};
+use platform;
+use platform::rawfile::file_read_all;
+use platform::types::*;
+use platform::{Pal, Sys};
+
+pub const _SC_ARG_MAX: c_int = 0;
+pub const _SC_CLK_TCK: c_int = 2;
+pub const _SC_OPEN_MAX: c_int = 4;
+pub const _SC_PAGESIZE: c_int = 30;
+pub const _SC_PAGE_SIZE: c_int = 30;
+pub const _SC_NPROCESSORS_CONF: c_int = 83;
+pub const _SC_NPROCESSORS_ONLN: c_int = 84;
+pub const _SC_HOST_NAME_MAX: c_int = 180;
+
+/// The page size when the kernel does not say, as on Redox
+const PAGE_SIZE: c_long = 4096;
+/// The clock ticks per second when the kernel does not say
+const CLK_TCK: c_long = 100;
+/// The least space for arguments and environment POSIX allows
+const ARG_MAX: c_long = 131072;
+/// The most space Linux gives arguments and environment, however large the
+/// stack is: three quarters of its default 8 MiB stack
+const ARG_MAX_LIMIT: c_long = 6 * 1024 * 1024;
+/// The open files when the kernel does not say, the usual soft limit on Linux
+const OPEN_MAX: c_long = 1024;
+const HOST_NAME_MAX: c_long = 64;
+
+/// Count the processors in a list like /sys/devices/system/cpu/online, which
+/// has ranges like "0-3,6"
+#[cfg(target_os = "linux")]
+fn cpu_count(online: bool) -> Option<c_long> {
+    let path: &[u8] = if online {
+        b"/sys/devices/system/cpu/online\0"
+    } else {
+        b"/sys/devices/system/cpu/possible\0"
+    };
+    let data = file_read_all(unsafe { CStr::from_bytes_with_nul_unchecked(path) }).ok()?;
+    let list = str::from_utf8(&data).ok()?.trim();
+
+    let mut count = 0;
+    for range in list.split(',') {
+        let mut bounds = range.splitn(2, '-');
+        let first: c_long = bounds.next()?.parse().ok()?;
+        let last: c_long = match bounds.next() {
+            Some(last) => last.parse().ok()?,
+            None => first,
+        };
+        count += last - first + 1;
+    }
+    Some(count)
+}
+
+/// Find the processor count in sys:cpu, whose first line is like "CPUs: 4".
+/// Every processor is always online.
+#[cfg(target_os = "redox")]
+fn cpu_count(_online: bool) -> Option<c_long> {
+    let data = file_read_all(unsafe { CStr::from_bytes_with_nul_unchecked(b"sys:cpu\0") }).ok()?;
+    for line in str::from_utf8(&data).ok()?.lines() {
+        if line.starts_with("CPUs:") {
+            return line[5..].trim().parse().ok();
+        }
+    }
+    None
+}
+
+fn soft_limit(resource: c_int) -> Option<rlim_t> {
+    let mut rlim = platform::types::rlimit::default();
+    if Sys::getrlimit(resource, &mut rlim) < 0 {
+        return None;
+    }
+    Some(rlim.rlim_cur)
+}
+
+#[no_mangle]
+pub extern "C" fn sysconf(name: c_int) -> c_long {
+    match name {
+        // The kernel lets arguments and environment take a quarter of the
+        // stack, up to a limit that also holds when the stack is unlimited
+        _SC_ARG_MAX => match soft_limit(RLIMIT_STACK) {
+            Some(stack) => ARG_MAX.max((stack / 4).min(ARG_MAX_LIMIT as rlim_t) as c_long),
+            None => ARG_MAX,
+        },
+        _SC_CLK_TCK => sys_auxv::get(AT_CLKTCK).map_or(CLK_TCK, |ticks| ticks as c_long),
+        _SC_HOST_NAME_MAX => HOST_NAME_MAX,
+        _SC_NPROCESSORS_CONF => cpu_count(false).unwrap_or(1),
+        _SC_NPROCESSORS_ONLN => cpu_count(true).unwrap_or(1),
+        // No limit is -1 without an error
+        _SC_OPEN_MAX => match soft_limit(RLIMIT_NOFILE) {
+            Some(RLIM_INFINITY) => -1,
+            Some(files) => files as c_long,
+            None => OPEN_MAX,
+        },
+        _SC_PAGESIZE => sys_auxv::get(AT_PAGESZ).map_or(PAGE_SIZE, |size| size as c_long),
+        _ => {
+            unsafe { platform::errno = EINVAL };
+            -1
+        }
+    }
+}
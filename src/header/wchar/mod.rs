@@ -1,10 +1,13 @@
 //! wchar implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/wchar.h.html
 
+use alloc::Vec;
 use core::ptr;
 use va_list::VaList as va_list;
 
+use header::ctype;
+use header::errno::ERANGE;
 use header::stdio::*;
-use header::stdlib::MB_CUR_MAX;
+use header::stdlib::{self, strtod, MB_CUR_MAX};
 use header::time::*;
 use platform;
 use platform::types::*;
@@ -350,9 +353,62 @@ pub extern "C" fn wcsstr(ws1: *const wchar_t, ws2: *const wchar_t) -> *mut wchar
     unimplemented!();
 }
 
-// #[no_mangle]
-pub extern "C" fn wcstod(nptr: *const wchar_t, endptr: *mut *mut wchar_t) -> f64 {
-    unimplemented!();
+/// Run the narrow parser `strto` on the ASCII start of `nptr`, which is
+/// all a number can be made of, and point `endptr` at the same place
+pub unsafe fn narrow_strto<T, F>(nptr: *const wchar_t, endptr: *mut *mut wchar_t, strto: F) -> T
+where
+    F: FnOnce(*const c_char, *mut *mut c_char) -> T,
+{
+    let mut bytes = Vec::new();
+    let mut wc = nptr;
+    while *wc > 0 && *wc < 0x80 {
+        bytes.push(*wc as c_char);
+        wc = wc.offset(1);
+    }
+    bytes.push(0);
+
+    let mut end = ptr::null_mut();
+    let value = strto(bytes.as_ptr(), &mut end);
+    if !endptr.is_null() {
+        *endptr = nptr.offset((end as usize - bytes.as_ptr() as usize) as isize) as *mut _;
+    }
+    value
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wcstod(nptr: *const wchar_t, endptr: *mut *mut wchar_t) -> c_double {
+    let is_ascii = |wc: wchar_t| wc > 0 && wc < 0x80;
+
+    let mut start = nptr;
+    while is_ascii(*start) && ctype::isspace(*start as c_int) != 0 {
+        start = start.offset(1);
+    }
+
+    // Numbers are all ASCII, so narrow as much as could be part of one
+    let mut bytes = Vec::new();
+    let mut wc = start;
+    while is_ascii(*wc) {
+        bytes.push(*wc as u8);
+        if !strtod::is_prefix(&bytes) {
+            bytes.pop();
+            break;
+        }
+        wc = wc.offset(1);
+    }
+
+    let parsed = strtod::parse::<c_double>(&bytes);
+    if parsed.out_of_range {
+        platform::errno = ERANGE;
+    }
+
+    if !endptr.is_null() {
+        *endptr = if parsed.len == 0 {
+            nptr as *mut _
+        } else {
+            start.offset(parsed.len as isize) as *mut _
+        };
+    }
+    parsed.value
 }
 
 // #[no_mangle]
@@ -364,14 +420,22 @@ pub extern "C" fn wcstok(
     unimplemented!();
 }
 
-// #[no_mangle]
-pub extern "C" fn wcstol(nptr: *const wchar_t, endptr: *mut *mut wchar_t, base: c_int) -> c_long {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn wcstol(
+    nptr: *const wchar_t,
+    endptr: *mut *mut wchar_t,
+    base: c_int,
+) -> c_long {
+    narrow_strto(nptr, endptr, |s, end| stdlib::strtol(s, end, base))
 }
 
-// #[no_mangle]
-pub extern "C" fn wcstoul(nptr: *const wchar_t, endptr: *mut *mut wchar_t, base: c_int) -> c_ulong {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn wcstoul(
+    nptr: *const wchar_t,
+    endptr: *mut *mut wchar_t,
+    base: c_int,
+) -> c_ulong {
+    narrow_strto(nptr, endptr, |s, end| stdlib::strtoul(s, end, base))
 }
 
 // #[no_mangle]
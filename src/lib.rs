@@ -38,6 +38,7 @@ pub mod c_str;
 pub mod header;
 pub mod platform;
 pub mod start;
+pub mod sync;
 
 use platform::{Allocator, Pal, Sys};
 
@@ -18,14 +18,10 @@ macro_rules! strto_impl {
             }
         };
 
-        let invalid_input = || {
-            platform::errno = EINVAL;
-            set_endptr(0);
-        };
-
         // only valid bases are 2 through 36
         if $base != 0 && ($base < 2 || $base > 36) {
-            invalid_input();
+            platform::errno = EINVAL;
+            set_endptr(0);
             return 0;
         }
 
@@ -43,57 +39,55 @@ macro_rules! strto_impl {
                 pos
             }
             None => {
-                invalid_input();
+                // no number, which is not an error
+                set_endptr(0);
                 return 0;
             }
         };
 
         // convert the string to a number
         let num_str = $s.offset(idx);
-        let res = match $base {
-            0 => detect_base(num_str)
-                .and_then(|($base, i)| convert_integer(num_str.offset(i), $base)),
-            8 => convert_octal(num_str),
-            16 => convert_hex(num_str),
-            _ => convert_integer(num_str, $base),
+        let base = if $base == 0 {
+            detect_base(num_str)
+        } else {
+            $base
+        };
+        let res = if base == 16 {
+            convert_hex(num_str)
+        } else {
+            convert_integer(num_str, base)
         };
 
-        // check for error parsing octal/hex prefix
-        // also check to ensure a number was indeed parsed
-        let (num, i, overflow) = match res {
+        // check to ensure a number was indeed parsed
+        let (magnitude, i, overflow) = match res {
             Some(res) => res,
             None => {
-                invalid_input();
+                // no number, which is not an error
+                set_endptr(0);
                 return 0;
             }
         };
         idx += i;
 
-        let overflow = if CHECK_SIGN {
-            overflow || (num as c_long).is_negative()
+        // The largest magnitude that fits with this sign. Unsigned results
+        // take any magnitude and wrap when negated, like strtoul("-1").
+        let limit = if CHECK_SIGN && !positive {
+            (MIN_VAL as uintmax_t).wrapping_neg()
         } else {
-            overflow
+            MAX_VAL as uintmax_t
         };
-        // account for the sign
-        let num = num as $rettype;
-        let num = if overflow {
+        let num = if overflow || magnitude > limit {
             platform::errno = ERANGE;
-            if CHECK_SIGN {
-                if positive {
-                    MAX_VAL
-                } else {
-                    MIN_VAL
-                }
+            if CHECK_SIGN && !positive {
+                MIN_VAL
             } else {
                 MAX_VAL
             }
+        } else if positive {
+            magnitude as $rettype
         } else {
-            if positive {
-                num
-            } else {
-                // not using -num to keep the compiler happy
-                num.overflowing_neg().0
-            }
+            // not using -num to keep the compiler happy
+            (magnitude as $rettype).overflowing_neg().0
         };
 
         set_endptr(idx);
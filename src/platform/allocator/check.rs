@@ -0,0 +1,196 @@
+//! Hardened allocation mode, turned on by the malloc_check feature or by
+//! setting RELIBC_MALLOC_CHECK in the environment
+//!
+//! Allocations are surrounded by redzones that are verified whenever they are
+//! freed. Freed memory is poisoned and kept in quarantine for a while before
+//! it is really released, so that double frees and writes after free are
+//! caught as well. Any corruption that is found aborts with a diagnostic.
+
+use core::fmt::Write;
+use core::{intrinsics, mem, ptr, slice};
+
+use platform::types::*;
+use platform::FileWriter;
+use sync;
+
+use super::{inner_alloc_align, inner_free};
+
+const REDZONE: usize = 16;
+const REDZONE_BYTE: u8 = 0xfd;
+const FREED_BYTE: u8 = 0xdd;
+
+const ALLOCATED: usize = 0xa110_ca7e;
+const FREED: usize = 0xf4ee_d0ff;
+
+const QUARANTINE_LEN: usize = 256;
+
+/// Placed right in front of the leading redzone
+#[repr(C)]
+struct Header {
+    /// Distance from the start of the underlying allocation to the user data
+    offset: usize,
+    size: usize,
+    state: usize,
+}
+
+static mut ENABLED: bool = cfg!(feature = "malloc_check");
+
+/// Where the memory handed out so far starts and ends. A pointer has to be
+/// in there before anything in front of it is read.
+static mut HEAP_START: usize = !0;
+static mut HEAP_END: usize = 0;
+
+static mut QUARANTINE_LOCK: c_int = 0;
+static mut QUARANTINE: [usize; QUARANTINE_LEN] = [0; QUARANTINE_LEN];
+static mut QUARANTINE_NEXT: usize = 0;
+
+pub fn enabled() -> bool {
+    unsafe { ENABLED }
+}
+
+pub unsafe fn enable() {
+    ENABLED = true;
+}
+
+unsafe fn header(ptr: *mut c_void) -> *mut Header {
+    (ptr as *mut u8).offset(-((REDZONE + mem::size_of::<Header>()) as isize)) as *mut Header
+}
+
+unsafe fn report(problem: &str, ptr: *mut c_void) -> ! {
+    let _ = writeln!(FileWriter(2), "relibc: malloc check: {} at {:p}", problem, ptr);
+    intrinsics::abort();
+}
+
+/// The header of `ptr`, or a report if it cannot be one of ours. Pointers
+/// from anywhere else, like the stack, are caught here instead of faulting.
+unsafe fn checked_header(ptr: *mut c_void) -> *mut Header {
+    let addr = ptr as usize;
+    let start = intrinsics::atomic_load(&HEAP_START);
+    let end = intrinsics::atomic_load(&HEAP_END);
+    // Every allocation is 16 byte aligned, between its header and redzones
+    if addr % 16 != 0
+        || addr < start.saturating_add(mem::size_of::<Header>() + REDZONE)
+        || addr > end.saturating_sub(REDZONE)
+    {
+        report("free of a pointer that malloc did not return", ptr);
+    }
+    header(ptr)
+}
+
+unsafe fn is_filled(ptr: *const u8, len: usize, byte: u8) -> bool {
+    slice::from_raw_parts(ptr, len).iter().all(|&b| b == byte)
+}
+
+unsafe fn check_state(ptr: *mut c_void, state: usize) {
+    match state {
+        ALLOCATED => (),
+        FREED => report("double free", ptr),
+        _ => report("free of a pointer that malloc did not return", ptr),
+    }
+}
+
+unsafe fn check_redzones(ptr: *mut c_void, header: *mut Header) {
+    let data = ptr as *const u8;
+    if (*header).size > intrinsics::atomic_load(&HEAP_END) - REDZONE - ptr as usize {
+        report("free of a pointer that malloc did not return", ptr);
+    }
+    if !is_filled(data.offset(-(REDZONE as isize)), REDZONE, REDZONE_BYTE) {
+        report("write before the start of the allocation", ptr);
+    }
+    if !is_filled(data.offset((*header).size as isize), REDZONE, REDZONE_BYTE) {
+        report("write past the end of the allocation", ptr);
+    }
+}
+
+/// Verify that `ptr` is a live allocation with intact redzones
+unsafe fn check(ptr: *mut c_void) -> *mut Header {
+    let header = checked_header(ptr);
+    check_state(ptr, intrinsics::atomic_load(&(*header).state));
+    check_redzones(ptr, header);
+    header
+}
+
+unsafe fn release(ptr: *mut c_void) {
+    let header = header(ptr);
+    if (*header).state != FREED || !is_filled(ptr as *const u8, (*header).size, FREED_BYTE) {
+        report("write to freed memory", ptr);
+    }
+    inner_free((ptr as *mut u8).offset(-((*header).offset as isize)) as *mut c_void);
+}
+
+pub unsafe fn alloc(size: usize, alignment: usize) -> *mut c_void {
+    let align = alignment.max(16).next_power_of_two();
+    let offset = (mem::size_of::<Header>() + REDZONE + align - 1) & !(align - 1);
+    let total = match offset.checked_add(size).and_then(|x| x.checked_add(REDZONE)) {
+        Some(total) => total,
+        None => return ptr::null_mut(),
+    };
+
+    let base = inner_alloc_align(total, align) as *mut u8;
+    if base.is_null() {
+        return ptr::null_mut();
+    }
+
+    intrinsics::atomic_umin(&mut HEAP_START, base as usize);
+    intrinsics::atomic_umax(&mut HEAP_END, base as usize + total);
+
+    let ptr = base.offset(offset as isize) as *mut c_void;
+    *header(ptr) = Header {
+        offset,
+        size,
+        state: ALLOCATED,
+    };
+    intrinsics::write_bytes((ptr as *mut u8).offset(-(REDZONE as isize)), REDZONE_BYTE, REDZONE);
+    intrinsics::write_bytes((ptr as *mut u8).offset(size as isize), REDZONE_BYTE, REDZONE);
+    ptr
+}
+
+pub unsafe fn realloc(ptr: *mut c_void, size: usize) -> *mut c_void {
+    if ptr.is_null() {
+        return alloc(size, 16);
+    }
+
+    let old_size = (*check(ptr)).size;
+    let new = alloc(size, 16);
+    if !new.is_null() {
+        ptr::copy_nonoverlapping(ptr as *const u8, new as *mut u8, old_size.min(size));
+        free(ptr);
+    }
+    new
+}
+
+pub unsafe fn free(ptr: *mut c_void) {
+    // Mark it freed in the same step as checking it, so that when two threads
+    // free it at once, one of them sees the other
+    let header = checked_header(ptr);
+    let (state, _) = intrinsics::atomic_cxchg(&mut (*header).state, ALLOCATED, FREED);
+    check_state(ptr, state);
+    check_redzones(ptr, header);
+    intrinsics::write_bytes(ptr as *mut u8, FREED_BYTE, (*header).size);
+
+    sync::futex_lock(&mut QUARANTINE_LOCK, None);
+    let old = QUARANTINE[QUARANTINE_NEXT];
+    QUARANTINE[QUARANTINE_NEXT] = ptr as usize;
+    QUARANTINE_NEXT = (QUARANTINE_NEXT + 1) % QUARANTINE_LEN;
+    sync::futex_unlock(&mut QUARANTINE_LOCK);
+
+    if old != 0 {
+        release(old as *mut c_void);
+    }
+}
+
+pub unsafe fn usable_size(ptr: *mut c_void) -> usize {
+    (*check(ptr)).size
+}
+
+pub unsafe fn fork_prepare() {
+    sync::futex_lock(&mut QUARANTINE_LOCK, None);
+}
+
+pub unsafe fn fork_parent() {
+    sync::futex_unlock(&mut QUARANTINE_LOCK);
+}
+
+pub unsafe fn fork_child() {
+    QUARANTINE_LOCK = 0;
+}
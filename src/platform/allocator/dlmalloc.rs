@@ -1,24 +1,19 @@
-use core::alloc::{GlobalAlloc, Layout};
+use platform::types::*;
 
-use super::types::*;
+use super::AllocInfo;
+
+/// Alignment of every chunk dlmalloc hands out
+pub const MIN_ALIGN: usize = 16;
 
 extern "C" {
     fn dlmalloc(bytes: size_t) -> *mut c_void;
     fn dlmemalign(alignment: size_t, bytes: size_t) -> *mut c_void;
     fn dlrealloc(oldmem: *mut c_void, bytes: size_t) -> *mut c_void;
     fn dlfree(mem: *mut c_void);
-}
-
-pub struct Allocator;
-
-unsafe impl<'a> GlobalAlloc for Allocator {
-    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
-        dlmemalign(layout.align(), layout.size()) as *mut u8
-    }
-
-    unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
-        dlfree(ptr as *mut c_void)
-    }
+    fn dlmalloc_usable_size(mem: *mut c_void) -> size_t;
+    fn dlmallinfo() -> AllocInfo;
+    fn dlmallopt(param_number: c_int, value: c_int) -> c_int;
+    fn dlmalloc_trim(pad: size_t) -> c_int;
 }
 
 pub unsafe fn alloc(size: usize) -> *mut c_void {
@@ -36,3 +31,19 @@ pub unsafe fn realloc(ptr: *mut c_void, size: size_t) -> *mut c_void {
 pub unsafe fn free(ptr: *mut c_void) {
     dlfree(ptr)
 }
+
+pub unsafe fn usable_size(ptr: *mut c_void) -> usize {
+    dlmalloc_usable_size(ptr)
+}
+
+pub unsafe fn info() -> AllocInfo {
+    dlmallinfo()
+}
+
+pub unsafe fn set_option(param: c_int, value: c_int) -> c_int {
+    dlmallopt(param, value)
+}
+
+pub unsafe fn trim(pad: usize) -> c_int {
+    dlmalloc_trim(pad)
+}
@@ -0,0 +1,402 @@
+//! Thread safe front end to the allocator backends
+//!
+//! The backends assume a single caller, so every call into them is made with
+//! one global lock held. To keep most allocations away from that lock, small
+//! chunks that get freed are kept for reuse in a cache that is thread local.
+//! Caches are also linked together, so that mallinfo, malloc_trim and fork
+//! can get at the caches of other threads. Only those take a cache's lock
+//! from another thread, so the owner normally finds it free.
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::{cmp, ptr};
+
+use platform::types::*;
+use sync;
+
+pub use self::trace::AllocHooks;
+
+mod check;
+mod trace;
+
+#[cfg(not(feature = "ralloc"))]
+#[path = "dlmalloc.rs"]
+mod backend;
+
+#[cfg(feature = "ralloc")]
+#[path = "ralloc.rs"]
+mod backend;
+
+/// Chunks are cached by usable size, in classes of CLASS_SIZE bytes each
+const CLASS_SIZE: usize = 16;
+const CLASS_COUNT: usize = 32;
+/// Maximum number of chunks cached per class
+const CLASS_DEPTH: usize = 8;
+
+/// Whether a cache is in the list of caches
+const UNLINKED: c_int = 0;
+const LINKED: c_int = 1;
+/// The thread has flushed its cache on the way out and must not use it again
+const RETIRED: c_int = 2;
+
+struct Cache {
+    lock: c_int,
+    state: c_int,
+    next: *mut Cache,
+    len: [usize; CLASS_COUNT],
+    chunks: [[*mut c_void; CLASS_DEPTH]; CLASS_COUNT],
+}
+
+/// Heap statistics, laid out like dlmalloc's struct mallinfo
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct AllocInfo {
+    pub arena: size_t,
+    pub ordblks: size_t,
+    pub smblks: size_t,
+    pub hblks: size_t,
+    pub hblkhd: size_t,
+    pub usmblks: size_t,
+    pub fsmblks: size_t,
+    pub uordblks: size_t,
+    pub fordblks: size_t,
+    pub keepcost: size_t,
+}
+
+static mut LOCK: c_int = 0;
+
+/// Protects the list of caches, see `each_cache`
+static mut CACHES_LOCK: c_int = 0;
+static mut CACHES: *mut Cache = 0 as *mut Cache;
+
+#[thread_local]
+static mut CACHE: Cache = Cache {
+    lock: 0,
+    state: UNLINKED,
+    next: 0 as *mut Cache,
+    len: [0; CLASS_COUNT],
+    chunks: [[0 as *mut c_void; CLASS_DEPTH]; CLASS_COUNT],
+};
+
+unsafe fn locked<T, F: FnOnce() -> T>(f: F) -> T {
+    sync::futex_lock(&mut LOCK, None);
+    let res = f();
+    sync::futex_unlock(&mut LOCK);
+    res
+}
+
+/// Run `f` on every cache with its lock held, and the list locked. Locks are
+/// always taken in this order: list, cache, global.
+unsafe fn each_cache<F: FnMut(&mut Cache)>(mut f: F) {
+    sync::futex_lock(&mut CACHES_LOCK, None);
+    let mut cache = CACHES;
+    while !cache.is_null() {
+        sync::futex_lock(&mut (*cache).lock, None);
+        f(&mut *cache);
+        sync::futex_unlock(&mut (*cache).lock);
+        cache = (*cache).next;
+    }
+    sync::futex_unlock(&mut CACHES_LOCK);
+}
+
+/// Run `f` on the cache of the calling thread, unless another thread is
+/// looking at it right now
+unsafe fn with_cache<T, F: FnOnce(&mut Cache) -> T>(f: F) -> Option<T> {
+    let cache = &mut CACHE as *mut Cache;
+    match (*cache).state {
+        UNLINKED => {
+            sync::futex_lock(&mut CACHES_LOCK, None);
+            (*cache).next = CACHES;
+            CACHES = cache;
+            (*cache).state = LINKED;
+            sync::futex_unlock(&mut CACHES_LOCK);
+        }
+        RETIRED => return None,
+        _ => (),
+    }
+
+    if !sync::futex_trylock(&mut (*cache).lock) {
+        return None;
+    }
+    let res = f(&mut *cache);
+    sync::futex_unlock(&mut (*cache).lock);
+    Some(res)
+}
+
+/// Give the chunks in `cache` back to the backend, with the global lock held
+unsafe fn cache_flush(cache: &mut Cache) {
+    for class in 0..CLASS_COUNT {
+        for &ptr in &cache.chunks[class][..cache.len[class]] {
+            backend::free(ptr);
+        }
+        cache.len[class] = 0;
+    }
+}
+
+unsafe fn cache_pop(size: usize) -> *mut c_void {
+    let class = match (size + CLASS_SIZE - 1) / CLASS_SIZE {
+        0 => 1,
+        class => class,
+    };
+    if class > CLASS_COUNT {
+        return 0 as *mut c_void;
+    }
+
+    with_cache(|cache| {
+        let len = &mut cache.len[class - 1];
+        if *len == 0 {
+            0 as *mut c_void
+        } else {
+            *len -= 1;
+            cache.chunks[class - 1][*len]
+        }
+    }).unwrap_or(0 as *mut c_void)
+}
+
+unsafe fn cache_push(ptr: *mut c_void) -> bool {
+    // Only the size of a chunk we own is read here, which no other thread
+    // changes, so this does not need the lock
+    let class = backend::usable_size(ptr) / CLASS_SIZE;
+    if class == 0 || class > CLASS_COUNT {
+        return false;
+    }
+
+    with_cache(|cache| {
+        let len = &mut cache.len[class - 1];
+        if *len == CLASS_DEPTH {
+            false
+        } else {
+            cache.chunks[class - 1][*len] = ptr;
+            *len += 1;
+            true
+        }
+    }).unwrap_or(false)
+}
+
+pub struct Allocator;
+
+// relibc's own allocations are checked, but not traced
+unsafe impl<'a> GlobalAlloc for Allocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        checked_alloc_align(layout.size(), layout.align()) as *mut u8
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
+        checked_free(ptr as *mut c_void)
+    }
+}
+
+unsafe fn inner_alloc(size: usize) -> *mut c_void {
+    let ptr = cache_pop(size);
+    if !ptr.is_null() {
+        return ptr;
+    }
+    locked(|| backend::alloc(size))
+}
+
+unsafe fn inner_alloc_align(size: usize, alignment: usize) -> *mut c_void {
+    if alignment <= backend::MIN_ALIGN {
+        return inner_alloc(size);
+    }
+    locked(|| backend::alloc_align(size, alignment))
+}
+
+unsafe fn inner_realloc(ptr: *mut c_void, size: usize) -> *mut c_void {
+    if ptr.is_null() || size == 0 {
+        return locked(|| backend::realloc(ptr, size));
+    }
+
+    // Staying in the same chunk needs nobody else, but a chunk that shrinks a
+    // lot should give the rest back
+    let usable = backend::usable_size(ptr);
+    if size <= usable && size > usable / 2 {
+        return ptr;
+    }
+
+    // Small chunks move through the cache instead of taking the lock
+    if cmp::max(size, usable) <= CLASS_SIZE * CLASS_COUNT {
+        let new = inner_alloc(size);
+        if !new.is_null() {
+            ptr::copy_nonoverlapping(ptr as *const u8, new as *mut u8, cmp::min(size, usable));
+            inner_free(ptr);
+        }
+        return new;
+    }
+    locked(|| backend::realloc(ptr, size))
+}
+
+unsafe fn inner_free(ptr: *mut c_void) {
+    if ptr.is_null() || cache_push(ptr) {
+        return;
+    }
+    locked(|| backend::free(ptr))
+}
+
+unsafe fn checked_alloc_align(size: usize, alignment: usize) -> *mut c_void {
+    if check::enabled() {
+        check::alloc(size, alignment)
+    } else {
+        inner_alloc_align(size, alignment)
+    }
+}
+
+unsafe fn checked_free(ptr: *mut c_void) {
+    if !check::enabled() {
+        inner_free(ptr);
+    } else if !ptr.is_null() {
+        check::free(ptr);
+    }
+}
+
+pub unsafe fn alloc(size: usize) -> *mut c_void {
+    alloc_align(size, backend::MIN_ALIGN)
+}
+
+pub unsafe fn alloc_align(size: usize, alignment: usize) -> *mut c_void {
+    let ptr = checked_alloc_align(size, alignment);
+    trace::on_alloc(ptr, size);
+    ptr
+}
+
+pub unsafe fn realloc(ptr: *mut c_void, size: size_t) -> *mut c_void {
+    let new = if check::enabled() {
+        check::realloc(ptr, size)
+    } else {
+        inner_realloc(ptr, size)
+    };
+    trace::on_realloc(ptr, new, size);
+    new
+}
+
+pub unsafe fn free(ptr: *mut c_void) {
+    trace::on_free(ptr);
+    checked_free(ptr);
+}
+
+/// Check every allocation from now on, see check.rs. As checked and
+/// unchecked allocations cannot be mixed, this has to be called before
+/// anything is allocated.
+pub unsafe fn alloc_check_enable() {
+    check::enable();
+}
+
+/// Replace the hooks called on allocations, or remove them if `hooks` is null
+pub unsafe fn alloc_set_hooks(hooks: *const AllocHooks) {
+    trace::set_hooks(hooks);
+}
+
+/// Keep track of live allocations from now on, to list them at exit
+pub unsafe fn alloc_leak_report_enable() {
+    trace::enable_leak_report();
+}
+
+/// Print every allocation that is still live, if enabled
+pub unsafe fn alloc_leak_report() {
+    trace::leak_report();
+}
+
+pub unsafe fn alloc_usable_size(ptr: *mut c_void) -> usize {
+    if ptr.is_null() {
+        0
+    } else if check::enabled() {
+        check::usable_size(ptr)
+    } else {
+        backend::usable_size(ptr)
+    }
+}
+
+pub unsafe fn alloc_info() -> AllocInfo {
+    let mut info = locked(|| backend::info());
+
+    // The backend counts cached chunks as allocated, but they are free as far
+    // as the program is concerned
+    each_cache(|cache| {
+        for class in 0..CLASS_COUNT {
+            for &ptr in &cache.chunks[class][..cache.len[class]] {
+                let size = backend::usable_size(ptr);
+                info.ordblks += 1;
+                info.uordblks = info.uordblks.saturating_sub(size);
+                info.fordblks += size;
+            }
+        }
+    });
+
+    info
+}
+
+pub unsafe fn alloc_set_option(param: c_int, value: c_int) -> c_int {
+    locked(|| backend::set_option(param, value))
+}
+
+/// Return cached chunks to the backend and give as much memory back to the
+/// system as possible, keeping `pad` bytes spare. Returns 1 if any memory
+/// was released.
+pub unsafe fn alloc_trim(pad: usize) -> c_int {
+    each_cache(|cache| locked(|| cache_flush(cache)));
+
+    locked(|| backend::trim(pad))
+}
+
+/// Return the cache of the calling thread to the backend and take it out of
+/// the list, as the thread is about to exit and its thread locals go away.
+/// Its allocations go straight to the backend from now on.
+pub unsafe fn alloc_thread_exit() {
+    let cache = &mut CACHE as *mut Cache;
+    if (*cache).state == LINKED {
+        sync::futex_lock(&mut CACHES_LOCK, None);
+        let mut link = &mut CACHES as *mut *mut Cache;
+        while *link != cache {
+            link = &mut (**link).next;
+        }
+        *link = (*cache).next;
+        sync::futex_unlock(&mut CACHES_LOCK);
+
+        locked(|| cache_flush(&mut *cache));
+    }
+    (*cache).state = RETIRED;
+}
+
+/// Take every allocator lock before forking, so that no other thread is in
+/// the middle of an allocation when the address space is copied
+pub unsafe fn fork_prepare() {
+    // Locks are always taken in this order: trace, quarantine, cache list,
+    // caches, global
+    trace::fork_prepare();
+    check::fork_prepare();
+    sync::futex_lock(&mut CACHES_LOCK, None);
+    let mut cache = CACHES;
+    while !cache.is_null() {
+        sync::futex_lock(&mut (*cache).lock, None);
+        cache = (*cache).next;
+    }
+    sync::futex_lock(&mut LOCK, None);
+}
+
+pub unsafe fn fork_parent() {
+    sync::futex_unlock(&mut LOCK);
+    let mut cache = CACHES;
+    while !cache.is_null() {
+        sync::futex_unlock(&mut (*cache).lock);
+        cache = (*cache).next;
+    }
+    sync::futex_unlock(&mut CACHES_LOCK);
+    check::fork_parent();
+    trace::fork_parent();
+}
+
+/// Only the forking thread lives on in the child, so there is nobody to
+/// wake up and the locks can just be reset. The thread locals of the other
+/// threads may not have been copied, so their caches are dropped from the
+/// list, along with the few chunks in them.
+pub unsafe fn fork_child() {
+    CACHE.lock = 0;
+    CACHE.next = 0 as *mut Cache;
+    CACHES = if CACHE.state == LINKED {
+        &mut CACHE as *mut Cache
+    } else {
+        0 as *mut Cache
+    };
+    CACHES_LOCK = 0;
+    LOCK = 0;
+    check::fork_child();
+    trace::fork_child();
+}
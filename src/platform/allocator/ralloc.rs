@@ -1,20 +1,42 @@
 extern crate ralloc;
 
-pub use ralloc::Allocator;
+use platform::types::*;
 
-unsafe fn alloc_inner(size: usize, offset: usize, align: usize) -> *mut c_void {
+use super::AllocInfo;
+
+/// Alignment of the pointers returned by alloc
+pub const MIN_ALIGN: usize = 8;
+
+// Every allocation is directly preceded by its total size and alignment, so
+// that it can be freed without the caller knowing either
+
+fn offset(align: usize) -> usize {
+    if align <= 16 {
+        16
+    } else {
+        align / 2
+    }
+}
+
+unsafe fn header(ptr: *mut c_void) -> *mut u64 {
+    (ptr as *mut u64).offset(-2)
+}
+
+unsafe fn alloc_inner(size: usize, align: usize) -> *mut c_void {
+    let offset = offset(align);
     let ptr = ralloc::alloc(size + offset, align);
     if !ptr.is_null() {
-        *(ptr as *mut u64) = (size + offset) as u64;
-        *(ptr as *mut u64).offset(1) = align as u64;
-        ptr.offset(offset as isize) as *mut c_void
+        let ptr = ptr.offset(offset as isize) as *mut c_void;
+        *header(ptr) = (size + offset) as u64;
+        *header(ptr).offset(1) = align as u64;
+        ptr
     } else {
         ptr as *mut c_void
     }
 }
 
 pub unsafe fn alloc(size: usize) -> *mut c_void {
-    alloc_inner(size, 16, 8)
+    alloc_inner(size, 8)
 }
 
 pub unsafe fn alloc_align(size: usize, alignment: usize) -> *mut c_void {
@@ -23,26 +45,46 @@ pub unsafe fn alloc_align(size: usize, alignment: usize) -> *mut c_void {
         align *= 2;
     }
 
-    alloc_inner(size, align / 2, align)
+    alloc_inner(size, align)
 }
 
 pub unsafe fn realloc(ptr: *mut c_void, size: size_t) -> *mut c_void {
-    let old_ptr = (ptr as *mut u8).offset(-16);
-    let old_size = *(old_ptr as *mut u64);
-    let align = *(old_ptr as *mut u64).offset(1);
-    let ptr = ralloc::realloc(old_ptr, old_size as usize, size + 16, align as usize);
+    let old_size = *header(ptr);
+    let align = *header(ptr).offset(1);
+    let offset = offset(align as usize);
+    let old_ptr = (ptr as *mut u8).offset(-(offset as isize));
+    let ptr = ralloc::realloc(old_ptr, old_size as usize, size + offset, align as usize);
     if !ptr.is_null() {
-        *(ptr as *mut u64) = (size + 16) as u64;
-        *(ptr as *mut u64).offset(1) = align;
-        ptr.offset(16) as *mut c_void
+        let ptr = ptr.offset(offset as isize) as *mut c_void;
+        *header(ptr) = (size + offset) as u64;
+        *header(ptr).offset(1) = align;
+        ptr
     } else {
         ptr as *mut c_void
     }
 }
 
 pub unsafe fn free(ptr: *mut c_void) {
-    let ptr = (ptr as *mut u8).offset(-16);
-    let size = *(ptr as *mut u64);
-    let _align = *(ptr as *mut u64).offset(1);
-    ralloc::free(ptr, size as usize);
+    let size = *header(ptr);
+    let align = *header(ptr).offset(1);
+    let offset = offset(align as usize);
+    ralloc::free((ptr as *mut u8).offset(-(offset as isize)), size as usize);
+}
+
+pub unsafe fn usable_size(ptr: *mut c_void) -> usize {
+    *header(ptr) as usize - offset(*header(ptr).offset(1) as usize)
+}
+
+// ralloc keeps no statistics and takes no options
+
+pub unsafe fn info() -> AllocInfo {
+    AllocInfo::default()
+}
+
+pub unsafe fn set_option(param: c_int, value: c_int) -> c_int {
+    0
+}
+
+pub unsafe fn trim(pad: usize) -> c_int {
+    0
 }
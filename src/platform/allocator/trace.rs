@@ -0,0 +1,138 @@
+//! Allocation tracing: user supplied hooks, and a leak report at exit that is
+//! turned on by setting RELIBC_LEAK_REPORT in the environment
+//!
+//! Only allocations made through the C interface are traced. Whatever relibc
+//! allocates for itself through GlobalAlloc is not, which is also what keeps
+//! the bookkeeping here from tracing itself.
+
+use alloc::boxed::Box;
+use alloc::btree_map::BTreeMap;
+use core::fmt::Write;
+use core::mem;
+use core::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+
+use platform::types::*;
+use platform::FileWriter;
+use sync::Mutex;
+
+/// Called after every allocation, reallocation and free made through the C
+/// interface. Hooks must not allocate themselves.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct AllocHooks {
+    pub malloc: Option<unsafe extern "C" fn(ptr: *mut c_void, size: size_t, data: *mut c_void)>,
+    pub realloc: Option<
+        unsafe extern "C" fn(old: *mut c_void, ptr: *mut c_void, size: size_t, data: *mut c_void),
+    >,
+    pub free: Option<unsafe extern "C" fn(ptr: *mut c_void, data: *mut c_void)>,
+    pub data: *mut c_void,
+}
+
+/// Pointer to the current AllocHooks, or 0
+static HOOKS: AtomicUsize = ATOMIC_USIZE_INIT;
+
+static mut LEAK_REPORT: bool = false;
+
+lazy_static! {
+    /// Size of every live allocation, by address
+    static ref LIVE: Mutex<BTreeMap<usize, usize>> = Mutex::new(BTreeMap::new());
+}
+
+unsafe fn hooks() -> Option<&'static AllocHooks> {
+    (HOOKS.load(Ordering::Acquire) as *const AllocHooks).as_ref()
+}
+
+pub unsafe fn set_hooks(hooks: *const AllocHooks) {
+    let new = if hooks.is_null() {
+        0
+    } else {
+        Box::into_raw(Box::new(*hooks)) as usize
+    };
+    // Another thread may still be running the old hooks, so they are leaked
+    // rather than freed
+    HOOKS.store(new, Ordering::Release);
+}
+
+pub unsafe fn enable_leak_report() {
+    LEAK_REPORT = true;
+}
+
+pub unsafe fn on_alloc(ptr: *mut c_void, size: usize) {
+    if ptr.is_null() {
+        return;
+    }
+    if LEAK_REPORT {
+        LIVE.lock().insert(ptr as usize, size);
+    }
+    if let Some(hooks) = hooks() {
+        if let Some(malloc) = hooks.malloc {
+            malloc(ptr, size, hooks.data);
+        }
+    }
+}
+
+pub unsafe fn on_realloc(old: *mut c_void, ptr: *mut c_void, size: usize) {
+    // On failure the old allocation is left alone
+    if ptr.is_null() && size != 0 {
+        return;
+    }
+    if LEAK_REPORT {
+        let mut live = LIVE.lock();
+        live.remove(&(old as usize));
+        if !ptr.is_null() {
+            live.insert(ptr as usize, size);
+        }
+    }
+    if let Some(hooks) = hooks() {
+        if let Some(realloc) = hooks.realloc {
+            realloc(old, ptr, size, hooks.data);
+        }
+    }
+}
+
+pub unsafe fn on_free(ptr: *mut c_void) {
+    if ptr.is_null() {
+        return;
+    }
+    if LEAK_REPORT {
+        LIVE.lock().remove(&(ptr as usize));
+    }
+    if let Some(hooks) = hooks() {
+        if let Some(free) = hooks.free {
+            free(ptr, hooks.data);
+        }
+    }
+}
+
+pub unsafe fn leak_report() {
+    if !LEAK_REPORT {
+        return;
+    }
+
+    let live = LIVE.lock();
+    if live.is_empty() {
+        return;
+    }
+    let mut w = FileWriter(2);
+    let _ = writeln!(
+        w,
+        "relibc: leak report: {} allocations with {} bytes still live",
+        live.len(),
+        live.values().sum::<usize>()
+    );
+    for (ptr, size) in live.iter() {
+        let _ = writeln!(w, "relibc:   {:#x}: {} bytes", ptr, size);
+    }
+}
+
+pub unsafe fn fork_prepare() {
+    mem::forget(LIVE.lock());
+}
+
+pub unsafe fn fork_parent() {
+    LIVE.force_unlock();
+}
+
+pub unsafe fn fork_child() {
+    LIVE.force_unlock();
+}
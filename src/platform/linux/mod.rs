@@ -7,20 +7,97 @@ use c_str::CStr;
 
 mod signal;
 mod socket;
+pub mod tls;
+pub mod vdso;
 
+const ESRCH: c_int = 3;
 const EINVAL: c_int = 22;
 const ENOSYS: c_int = 38;
 
+const CLOCK_REALTIME: clockid_t = 0;
+
+// The kernel names the CPU-time clock of a process or thread by the
+// complement of its id, shifted left to make room for these flags
+const CPUCLOCK_SCHED: clockid_t = 2;
+const CPUCLOCK_PERTHREAD: clockid_t = 4;
+
 const SIGCHLD: usize = 17;
 
 const TCGETS: c_ulong = 0x5401;
 const TCSETS: c_ulong = 0x5402;
+const TIOCGPGRP: c_ulong = 0x540F;
+const TIOCSPGRP: c_ulong = 0x5410;
 const TIOCGWINSZ: c_ulong = 0x5413;
+const TIOCGSID: c_ulong = 0x5429;
+
+const CLONE_VM: usize = 0x100;
+const CLONE_FS: usize = 0x200;
+const CLONE_FILES: usize = 0x400;
+const CLONE_SIGHAND: usize = 0x800;
+const CLONE_VFORK: usize = 0x4000;
+const CLONE_THREAD: usize = 0x10000;
+const CLONE_SYSVSEM: usize = 0x40000;
+const CLONE_SETTLS: usize = 0x80000;
+const CLONE_CHILD_CLEARTID: usize = 0x200000;
 
 const AT_FDCWD: c_int = -100;
 const AT_EMPTY_PATH: c_int = 0x1000;
 const AT_REMOVEDIR: c_int = 0x200;
 
+// The new thread starts on its own stack with nothing but the entry point and
+// its argument, so this needs to be assembly
+#[cfg(target_arch = "x86_64")]
+global_asm!(
+    "
+    .global __relibc_clone
+    __relibc_clone:
+        mov %rcx, %r10 # <- child tid pointer goes in r10 for syscalls
+        mov $56, %eax # <- 56 is CLONE
+        syscall
+        test %rax, %rax
+        jnz 1f
+        xor %ebp, %ebp
+        pop %rax
+        pop %rdi
+        call *%rax
+        xor %edi, %edi
+        mov $60, %eax # <- 60 is EXIT
+        syscall
+    1:
+        ret
+"
+);
+#[cfg(target_arch = "aarch64")]
+global_asm!(
+    "
+    .global __relibc_clone
+    __relibc_clone:
+        mov x5, x4 # <- the kernel takes the thread pointer before the child tid
+        mov x4, x3
+        mov x3, x5
+        mov x8, #220
+        svc 0
+        cbnz x0, 1f
+        ldp x1, x0, [sp], #16
+        blr x1
+        mov x0, xzr
+        mov x8, #93
+        svc 0
+    1:
+        ret
+"
+);
+
+extern "C" {
+    fn __relibc_clone(
+        flags: usize,
+        stack: *mut usize,
+        ptid: *mut c_int,
+        ctid: *mut c_int,
+        tls: usize,
+    ) -> usize;
+}
+
 fn e(sys: usize) -> usize {
     if (sys as isize) < 0 && (sys as isize) >= -256 {
         unsafe {
@@ -71,8 +148,95 @@ impl Pal for Sys {
         }) as c_int
     }
 
+    fn clock_getcpuclockid(pid: pid_t, clock_id: *mut clockid_t) -> c_int {
+        let clock = (!pid << 3) | CPUCLOCK_SCHED;
+        // Only the kernel knows whether the process is there
+        if Self::clock_getres(clock, ptr::null_mut()) < 0 {
+            unsafe {
+                if errno == EINVAL {
+                    errno = ESRCH;
+                }
+            }
+            return -1;
+        }
+        unsafe {
+            *clock_id = clock;
+        }
+        0
+    }
+
+    fn clock_getres(clk_id: clockid_t, res: *mut timespec) -> c_int {
+        e(unsafe { syscall!(CLOCK_GETRES, clk_id, res) }) as c_int
+    }
+
     fn clock_gettime(clk_id: clockid_t, tp: *mut timespec) -> c_int {
-        e(unsafe { syscall!(CLOCK_GETTIME, clk_id, tp) }) as c_int
+        match vdso::clock_gettime(clk_id, tp) {
+            Some(ret) => e(ret) as c_int,
+            None => e(unsafe { syscall!(CLOCK_GETTIME, clk_id, tp) }) as c_int,
+        }
+    }
+
+    fn clock_nanosleep(
+        clk_id: clockid_t,
+        flags: c_int,
+        rqtp: *const timespec,
+        rmtp: *mut timespec,
+    ) -> c_int {
+        e(unsafe { syscall!(CLOCK_NANOSLEEP, clk_id, flags, rqtp, rmtp) }) as c_int
+    }
+
+    fn clock_settime(clk_id: clockid_t, tp: *const timespec) -> c_int {
+        e(unsafe { syscall!(CLOCK_SETTIME, clk_id, tp) }) as c_int
+    }
+
+    unsafe fn clone_thread(
+        stack: *mut c_void,
+        func: extern "C" fn(*mut c_void),
+        arg: *mut c_void,
+        ctid: *mut c_int,
+    ) -> pid_t {
+        let flags = CLONE_VM
+            | CLONE_FS
+            | CLONE_FILES
+            | CLONE_SIGHAND
+            | CLONE_THREAD
+            | CLONE_SYSVSEM
+            | CLONE_SETTLS
+            | CLONE_CHILD_CLEARTID;
+
+        // The thread local storage of the new thread goes at the top of its
+        // stack
+        let tls_base = stack as usize - tls::size();
+        let tp = tls::create(tls_base as *mut u8);
+
+        // __relibc_clone pops these off the new stack in the child
+        let stack = ((tls_base & !0xF) as *mut usize).offset(-2);
+        *stack = func as usize;
+        *stack.offset(1) = arg as usize;
+
+        e(__relibc_clone(flags, stack, ptr::null_mut(), ctid, tp)) as pid_t
+    }
+
+    unsafe fn clone_vfork(
+        stack: *mut c_void,
+        func: extern "C" fn(*mut c_void),
+        arg: *mut c_void,
+    ) -> pid_t {
+        // The caller is suspended until the child execs or exits, so the child
+        // can use its memory in the meantime
+        let flags = CLONE_VM | CLONE_VFORK | SIGCHLD;
+
+        let stack = (stack as *mut usize).offset(-2);
+        *stack = func as usize;
+        *stack.offset(1) = arg as usize;
+
+        e(__relibc_clone(
+            flags,
+            stack,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            0,
+        )) as pid_t
     }
 
     fn close(fildes: c_int) -> c_int {
@@ -93,7 +257,14 @@ impl Pal for Sys {
 
     fn exit(status: c_int) -> ! {
         unsafe {
-            syscall!(EXIT, status);
+            syscall!(EXIT_GROUP, status);
+        }
+        loop {}
+    }
+
+    fn exit_thread() -> ! {
+        unsafe {
+            syscall!(EXIT, 0);
         }
         loop {}
     }
@@ -110,6 +281,18 @@ impl Pal for Sys {
         e(unsafe { syscall!(FCHOWN, fildes, owner, group) }) as c_int
     }
 
+    unsafe fn fexecve(fildes: c_int, argv: *const *mut c_char, envp: *const *mut c_char) -> c_int {
+        let empty_cstr: *const c_char = super::cstr_from_bytes_with_nul_unchecked(b"\0");
+        e(syscall!(
+            EXECVEAT,
+            fildes,
+            empty_cstr,
+            argv,
+            envp,
+            AT_EMPTY_PATH
+        )) as c_int
+    }
+
     fn flock(fd: c_int, operation: c_int) -> c_int {
         e(unsafe { syscall!(FLOCK, fd, operation) }) as c_int
     }
@@ -119,6 +302,10 @@ impl Pal for Sys {
         e(unsafe { syscall!(NEWFSTATAT, fildes, empty_cstr, buf, AT_EMPTY_PATH) }) as c_int
     }
 
+    fn fstatfs(fildes: c_int, buf: *mut statfs) -> c_int {
+        e(unsafe { syscall!(FSTATFS, fildes, buf) }) as c_int
+    }
+
     fn fcntl(fildes: c_int, cmd: c_int, arg: c_int) -> c_int {
         e(unsafe { syscall!(FCNTL, fildes, cmd, arg) }) as c_int
     }
@@ -135,6 +322,10 @@ impl Pal for Sys {
         e(unsafe { syscall!(FTRUNCATE, fildes, length) }) as c_int
     }
 
+    unsafe fn futex(addr: *mut c_int, op: c_int, val: c_int, timeout: *const timespec) -> c_int {
+        e(syscall!(FUTEX, addr, op, val, timeout)) as c_int
+    }
+
     fn futimens(fd: c_int, times: *const timespec) -> c_int {
         e(unsafe { syscall!(UTIMENSAT, fd, ptr::null::<c_char>(), times, 0) }) as c_int
     }
@@ -143,6 +334,13 @@ impl Pal for Sys {
         e(unsafe { syscall!(UTIMENSAT, AT_FDCWD, path.as_ptr(), times, 0) }) as c_int
     }
 
+    fn getcpu(cpu: *mut c_uint, node: *mut c_uint) -> c_int {
+        match vdso::getcpu(cpu, node) {
+            Some(ret) => e(ret) as c_int,
+            None => e(unsafe { syscall!(GETCPU, cpu, node, 0) }) as c_int,
+        }
+    }
+
     fn getcwd(buf: *mut c_char, size: size_t) -> *mut c_char {
         if e(unsafe { syscall!(GETCWD, buf, size) }) == !0 {
             ptr::null_mut()
@@ -167,10 +365,23 @@ impl Pal for Sys {
         e(unsafe { syscall!(GETGID) }) as gid_t
     }
 
+    fn getrandom(buf: &mut [u8], flags: c_uint) -> ssize_t {
+        e(unsafe { syscall!(GETRANDOM, buf.as_mut_ptr(), buf.len(), flags) }) as ssize_t
+    }
+
+    fn getrlimit(resource: c_int, rlim: *mut rlimit) -> c_int {
+        // Not every architecture has getrlimit, but they all have prlimit64
+        e(unsafe { syscall!(PRLIMIT64, 0, resource, 0, rlim) }) as c_int
+    }
+
     fn getrusage(who: c_int, r_usage: *mut rusage) -> c_int {
         e(unsafe { syscall!(GETRUSAGE, who, r_usage) }) as c_int
     }
 
+    fn getsid(pid: pid_t) -> pid_t {
+        e(unsafe { syscall!(GETSID, pid) }) as pid_t
+    }
+
     unsafe fn gethostname(mut name: *mut c_char, len: size_t) -> c_int {
         // len only needs to be mutable on linux
         let mut len = len;
@@ -215,8 +426,15 @@ impl Pal for Sys {
         e(unsafe { syscall!(GETPPID) }) as pid_t
     }
 
+    fn gettid() -> pid_t {
+        e(unsafe { syscall!(GETTID) }) as pid_t
+    }
+
     fn gettimeofday(tp: *mut timeval, tzp: *mut timezone) -> c_int {
-        e(unsafe { syscall!(GETTIMEOFDAY, tp, tzp) }) as c_int
+        match vdso::gettimeofday(tp, tzp) {
+            Some(ret) => e(ret) as c_int,
+            None => e(unsafe { syscall!(GETTIMEOFDAY, tp, tzp) }) as c_int,
+        }
     }
 
     fn getuid() -> uid_t {
@@ -269,6 +487,10 @@ impl Pal for Sys {
         e(syscall!(MMAP, addr, len, prot, flags, fildes, off)) as *mut c_void
     }
 
+    unsafe fn mprotect(addr: *mut c_void, len: usize, prot: c_int) -> c_int {
+        e(syscall!(MPROTECT, addr, len, prot)) as c_int
+    }
+
     unsafe fn munmap(addr: *mut c_void, len: usize) -> c_int {
         e(syscall!(MUNMAP, addr, len)) as c_int
     }
@@ -289,6 +511,18 @@ impl Pal for Sys {
         e(unsafe { syscall!(READ, fildes, buf.as_mut_ptr(), buf.len()) }) as ssize_t
     }
 
+    fn readlink(pathname: &CStr, out: &mut [u8]) -> ssize_t {
+        e(unsafe {
+            syscall!(
+                READLINKAT,
+                AT_FDCWD,
+                pathname.as_ptr(),
+                out.as_mut_ptr(),
+                out.len()
+            )
+        }) as ssize_t
+    }
+
     fn rename(old: &CStr, new: &CStr) -> c_int {
         e(unsafe { syscall!(RENAMEAT, AT_FDCWD, old.as_ptr(), AT_FDCWD, new.as_ptr()) }) as c_int
     }
@@ -297,6 +531,54 @@ impl Pal for Sys {
         e(unsafe { syscall!(UNLINKAT, AT_FDCWD, path.as_ptr(), AT_REMOVEDIR) }) as c_int
     }
 
+    fn sched_get_priority_max(policy: c_int) -> c_int {
+        e(unsafe { syscall!(SCHED_GET_PRIORITY_MAX, policy) }) as c_int
+    }
+
+    fn sched_get_priority_min(policy: c_int) -> c_int {
+        e(unsafe { syscall!(SCHED_GET_PRIORITY_MIN, policy) }) as c_int
+    }
+
+    fn sched_getaffinity(pid: pid_t, cpusetsize: size_t, mask: *mut cpu_set_t) -> c_int {
+        let size = e(unsafe { syscall!(SCHED_GETAFFINITY, pid, cpusetsize, mask) });
+        if size == !0 {
+            return -1;
+        }
+        // The kernel only writes as many bytes as it has CPUs, clear the rest
+        unsafe {
+            ptr::write_bytes((mask as *mut u8).offset(size as isize), 0, cpusetsize - size);
+        }
+        0
+    }
+
+    fn sched_getparam(pid: pid_t, param: *mut sched_param) -> c_int {
+        e(unsafe { syscall!(SCHED_GETPARAM, pid, param) }) as c_int
+    }
+
+    fn sched_getscheduler(pid: pid_t) -> c_int {
+        e(unsafe { syscall!(SCHED_GETSCHEDULER, pid) }) as c_int
+    }
+
+    fn sched_rr_get_interval(pid: pid_t, interval: *mut timespec) -> c_int {
+        e(unsafe { syscall!(SCHED_RR_GET_INTERVAL, pid, interval) }) as c_int
+    }
+
+    fn sched_setaffinity(pid: pid_t, cpusetsize: size_t, mask: *const cpu_set_t) -> c_int {
+        e(unsafe { syscall!(SCHED_SETAFFINITY, pid, cpusetsize, mask) }) as c_int
+    }
+
+    fn sched_setparam(pid: pid_t, param: *const sched_param) -> c_int {
+        e(unsafe { syscall!(SCHED_SETPARAM, pid, param) }) as c_int
+    }
+
+    fn sched_setscheduler(pid: pid_t, policy: c_int, param: *const sched_param) -> c_int {
+        e(unsafe { syscall!(SCHED_SETSCHEDULER, pid, policy, param) }) as c_int
+    }
+
+    fn sched_yield() -> c_int {
+        e(unsafe { syscall!(SCHED_YIELD) }) as c_int
+    }
+
     fn select(
         nfds: c_int,
         readfds: *mut fd_set,
@@ -323,10 +605,38 @@ impl Pal for Sys {
         e(unsafe { syscall!(SETREUID, ruid, euid) }) as c_int
     }
 
+    fn setrlimit(resource: c_int, rlim: *const rlimit) -> c_int {
+        e(unsafe { syscall!(PRLIMIT64, 0, resource, rlim, 0) }) as c_int
+    }
+
+    fn setsid() -> pid_t {
+        e(unsafe { syscall!(SETSID) }) as pid_t
+    }
+
+    fn statfs(path: &CStr, buf: *mut statfs) -> c_int {
+        e(unsafe { syscall!(STATFS, path.as_ptr(), buf) }) as c_int
+    }
+
     fn tcgetattr(fd: c_int, out: *mut termios) -> c_int {
         Self::ioctl(fd, TCGETS, out as *mut c_void)
     }
 
+    fn tcgetpgrp(fd: c_int) -> pid_t {
+        let mut pgrp: pid_t = 0;
+        if Self::ioctl(fd, TIOCGPGRP, &mut pgrp as *mut pid_t as *mut c_void) < 0 {
+            return -1;
+        }
+        pgrp
+    }
+
+    fn tcgetsid(fd: c_int) -> pid_t {
+        let mut sid: pid_t = 0;
+        if Self::ioctl(fd, TIOCGSID, &mut sid as *mut pid_t as *mut c_void) < 0 {
+            return -1;
+        }
+        sid
+    }
+
     fn tcsetattr(fd: c_int, act: c_int, value: *const termios) -> c_int {
         if act < 0 || act > 2 {
             unsafe {
@@ -338,6 +648,61 @@ impl Pal for Sys {
         Self::ioctl(fd, TCSETS + act as c_ulong, value as *mut c_void)
     }
 
+    fn tcsetpgrp(fd: c_int, pgrp: pid_t) -> c_int {
+        // The kernel only reads the group
+        Self::ioctl(fd, TIOCSPGRP, &pgrp as *const pid_t as *mut c_void)
+    }
+
+    fn thread_getcpuclockid(tid: pid_t, clock_id: *mut clockid_t) -> c_int {
+        unsafe {
+            *clock_id = (!tid << 3) | CPUCLOCK_SCHED | CPUCLOCK_PERTHREAD;
+        }
+        0
+    }
+
+    fn time(tloc: *mut time_t) -> time_t {
+        if let Some(time) = vdso::time(tloc) {
+            return time;
+        }
+
+        // Not every architecture has a time system call
+        let mut tp = timespec::default();
+        if Self::clock_gettime(CLOCK_REALTIME, &mut tp) < 0 {
+            return -1;
+        }
+        if !tloc.is_null() {
+            unsafe {
+                *tloc = tp.tv_sec;
+            }
+        }
+        tp.tv_sec
+    }
+
+    fn timer_create(clock_id: clockid_t, evp: *mut sigevent, timerid: *mut c_int) -> c_int {
+        e(unsafe { syscall!(TIMER_CREATE, clock_id, evp, timerid) }) as c_int
+    }
+
+    fn timer_delete(timerid: c_int) -> c_int {
+        e(unsafe { syscall!(TIMER_DELETE, timerid) }) as c_int
+    }
+
+    fn timer_getoverrun(timerid: c_int) -> c_int {
+        e(unsafe { syscall!(TIMER_GETOVERRUN, timerid) }) as c_int
+    }
+
+    fn timer_gettime(timerid: c_int, value: *mut itimerspec) -> c_int {
+        e(unsafe { syscall!(TIMER_GETTIME, timerid, value) }) as c_int
+    }
+
+    fn timer_settime(
+        timerid: c_int,
+        flags: c_int,
+        value: *const itimerspec,
+        ovalue: *mut itimerspec,
+    ) -> c_int {
+        e(unsafe { syscall!(TIMER_SETTIME, timerid, flags, value, ovalue) }) as c_int
+    }
+
     fn times(out: *mut tms) -> clock_t {
         unsafe { syscall!(TIMES, out) as clock_t }
     }
@@ -37,4 +37,13 @@ impl PalSignal for Sys {
     fn sigprocmask(how: c_int, set: *const sigset_t, oset: *mut sigset_t) -> c_int {
         e(unsafe { syscall!(RT_SIGPROCMASK, how, set, oset, mem::size_of::<sigset_t>()) }) as c_int
     }
+
+    fn sigtimedwait(set: *const sigset_t, timeout: *const timespec) -> c_int {
+        let size = mem::size_of::<sigset_t>();
+        e(unsafe { syscall!(RT_SIGTIMEDWAIT, set, 0, timeout, size) }) as c_int
+    }
+
+    fn tkill(tid: pid_t, sig: c_int) -> c_int {
+        e(unsafe { syscall!(TKILL, tid, sig) }) as c_int
+    }
 }
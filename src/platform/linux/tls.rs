@@ -0,0 +1,152 @@
+//! Thread local storage of the executable, which the compiler reaches through
+//! the thread pointer. Nobody sets it up but us: every thread gets a copy of
+//! the PT_TLS segment laid out around its thread pointer the way the linker
+//! expects, with the block before the thread pointer on x86_64 and after a
+//! two word control block on aarch64.
+
+use core::{cmp, mem, ptr, slice};
+
+use super::super::types::*;
+use super::super::Pal;
+use super::vdso::Phdr;
+use super::Sys;
+
+const PT_PHDR: u32 = 6;
+const PT_TLS: u32 = 7;
+
+const PROT_READ: c_int = 1;
+const PROT_WRITE: c_int = 2;
+const MAP_PRIVATE: c_int = 0x02;
+const MAP_ANONYMOUS: c_int = 0x20;
+
+#[cfg(target_arch = "x86_64")]
+const ARCH_SET_FS: usize = 0x1002;
+
+/// What the thread pointer points to. On x86_64 the first word has to point
+/// to itself, which is how code finds out the thread pointer.
+#[repr(C)]
+struct Tcb {
+    this: usize,
+    _reserved: usize,
+}
+
+/// The initial contents of every thread's block
+struct Image {
+    addr: usize,
+    file_size: usize,
+    mem_size: usize,
+    align: usize,
+}
+
+static mut IMAGE: Image = Image {
+    addr: 0,
+    file_size: 0,
+    mem_size: 0,
+    align: mem::align_of::<Tcb>(),
+};
+
+#[cfg(target_arch = "aarch64")]
+global_asm!(
+    "
+    .global __relibc_set_thread_pointer
+    __relibc_set_thread_pointer:
+        msr tpidr_el0, x0
+        ret
+"
+);
+
+#[cfg(target_arch = "aarch64")]
+extern "C" {
+    fn __relibc_set_thread_pointer(tp: usize);
+}
+
+fn align_up(value: usize, align: usize) -> usize {
+    (value + align - 1) & !(align - 1)
+}
+
+/// How much memory `create` needs for a thread
+pub fn size() -> usize {
+    unsafe {
+        align_up(IMAGE.mem_size, IMAGE.align)
+            + align_up(mem::size_of::<Tcb>(), IMAGE.align)
+            + IMAGE.align
+    }
+}
+
+/// Lay out a copy of the initial block in the `size()` bytes at `base`, and
+/// return the thread pointer of the thread that will use it
+pub unsafe fn create(base: *mut u8) -> usize {
+    let end = base as usize + size();
+    let block_size = align_up(IMAGE.mem_size, IMAGE.align);
+
+    #[cfg(target_arch = "x86_64")]
+    let (tp, block) = {
+        let tp = (end - mem::size_of::<Tcb>()) & !(IMAGE.align - 1);
+        (tp, tp - block_size)
+    };
+    #[cfg(target_arch = "aarch64")]
+    let (tp, block) = {
+        let tp = align_up(base as usize, IMAGE.align);
+        (tp, tp + align_up(mem::size_of::<Tcb>(), IMAGE.align))
+    };
+    debug_assert!(block >= base as usize && block + block_size <= end);
+
+    ptr::copy_nonoverlapping(IMAGE.addr as *const u8, block as *mut u8, IMAGE.file_size);
+    ptr::write_bytes(
+        (block + IMAGE.file_size) as *mut u8,
+        0,
+        IMAGE.mem_size - IMAGE.file_size,
+    );
+    ptr::write(
+        tp as *mut Tcb,
+        Tcb {
+            this: tp,
+            _reserved: 0,
+        },
+    );
+    tp
+}
+
+/// Find the PT_TLS segment in the program headers the kernel passed in the
+/// auxiliary vector, and give the calling thread its block. This has to run
+/// before anything touches a thread local.
+pub unsafe fn init(phdr_addr: usize, phnum: usize) {
+    let phdrs = slice::from_raw_parts(phdr_addr as *const Phdr, phnum);
+    // A position independent executable is not loaded at the addresses in it
+    let load_offset = phdrs
+        .iter()
+        .find(|phdr| phdr.p_type == PT_PHDR)
+        .map_or(0, |phdr| phdr_addr.wrapping_sub(phdr.p_vaddr as usize));
+    if let Some(tls) = phdrs.iter().find(|phdr| phdr.p_type == PT_TLS) {
+        IMAGE = Image {
+            addr: load_offset.wrapping_add(tls.p_vaddr as usize),
+            file_size: tls.p_filesz as usize,
+            mem_size: tls.p_memsz as usize,
+            align: cmp::max(tls.p_align as usize, mem::align_of::<Tcb>()),
+        };
+    }
+
+    let base = Sys::mmap(
+        ptr::null_mut(),
+        size(),
+        PROT_READ | PROT_WRITE,
+        MAP_PRIVATE | MAP_ANONYMOUS,
+        -1,
+        0,
+    );
+    if base as usize == !0 {
+        Sys::exit(127);
+    }
+    set_thread_pointer(create(base as *mut u8));
+}
+
+fn set_thread_pointer(tp: usize) {
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        syscall!(ARCH_PRCTL, ARCH_SET_FS, tp);
+    }
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        __relibc_set_thread_pointer(tp);
+    }
+}
@@ -0,0 +1,296 @@
+//! The vDSO, a shared object the kernel maps into every process to answer
+//! a few system calls without entering the kernel. Its functions are looked up
+//! once at startup; each one that is missing falls back to the system call.
+
+use core::{mem, ptr, slice};
+
+use super::super::types::*;
+
+const PT_LOAD: u32 = 1;
+const PT_DYNAMIC: u32 = 2;
+
+const DT_NULL: i64 = 0;
+const DT_HASH: i64 = 4;
+const DT_STRTAB: i64 = 5;
+const DT_SYMTAB: i64 = 6;
+const DT_GNU_HASH: i64 = 0x6fff_fef5;
+const DT_VERSYM: i64 = 0x6fff_fff0;
+const DT_VERDEF: i64 = 0x6fff_fffc;
+
+const STT_FUNC: u8 = 2;
+const STB_GLOBAL: u8 = 1;
+const STB_WEAK: u8 = 2;
+const SHN_UNDEF: u16 = 0;
+const VER_FLG_BASE: u16 = 1;
+const VERSYM_HIDDEN: u16 = 0x8000;
+
+#[repr(C)]
+struct Ehdr {
+    e_ident: [u8; 16],
+    e_type: u16,
+    e_machine: u16,
+    e_version: u32,
+    e_entry: u64,
+    e_phoff: u64,
+    e_shoff: u64,
+    e_flags: u32,
+    e_ehsize: u16,
+    e_phentsize: u16,
+    e_phnum: u16,
+    e_shentsize: u16,
+    e_shnum: u16,
+    e_shstrndx: u16,
+}
+
+#[repr(C)]
+pub(super) struct Phdr {
+    pub(super) p_type: u32,
+    pub(super) p_flags: u32,
+    pub(super) p_offset: u64,
+    pub(super) p_vaddr: u64,
+    pub(super) p_paddr: u64,
+    pub(super) p_filesz: u64,
+    pub(super) p_memsz: u64,
+    pub(super) p_align: u64,
+}
+
+#[repr(C)]
+struct Dyn {
+    d_tag: i64,
+    d_val: u64,
+}
+
+#[repr(C)]
+struct Sym {
+    st_name: u32,
+    st_info: u8,
+    st_other: u8,
+    st_shndx: u16,
+    st_value: u64,
+    st_size: u64,
+}
+
+#[repr(C)]
+struct Verdef {
+    vd_version: u16,
+    vd_flags: u16,
+    vd_ndx: u16,
+    vd_cnt: u16,
+    vd_hash: u32,
+    vd_aux: u32,
+    vd_next: u32,
+}
+
+#[repr(C)]
+struct Verdaux {
+    vda_name: u32,
+    vda_next: u32,
+}
+
+static mut CLOCK_GETTIME: Option<extern "C" fn(clockid_t, *mut timespec) -> c_int> = None;
+static mut GETTIMEOFDAY: Option<extern "C" fn(*mut timeval, *mut timezone) -> c_int> = None;
+static mut TIME: Option<extern "C" fn(*mut time_t) -> time_t> = None;
+static mut GETCPU: Option<extern "C" fn(*mut c_uint, *mut c_uint, *mut c_void) -> c_int> = None;
+
+struct Vdso {
+    /// What to add to an address in the object to get where it is mapped
+    load_offset: usize,
+    symtab: *const Sym,
+    strtab: *const c_char,
+    symbols: usize,
+    versym: *const u16,
+    verdef: *const Verdef,
+}
+
+impl Vdso {
+    unsafe fn parse(base: usize) -> Option<Vdso> {
+        let ehdr = &*(base as *const Ehdr);
+        if &ehdr.e_ident[..4] != b"\x7fELF" {
+            return None;
+        }
+
+        let phdrs = slice::from_raw_parts(
+            (base + ehdr.e_phoff as usize) as *const Phdr,
+            ehdr.e_phnum as usize,
+        );
+        let mut load_offset = None;
+        let mut dynamic = None;
+        for phdr in phdrs {
+            match phdr.p_type {
+                PT_LOAD if load_offset.is_none() => {
+                    load_offset = Some(
+                        base.wrapping_add(phdr.p_offset as usize)
+                            .wrapping_sub(phdr.p_vaddr as usize),
+                    )
+                }
+                PT_DYNAMIC => dynamic = Some((base + phdr.p_offset as usize) as *const Dyn),
+                _ => (),
+            }
+        }
+        let load_offset = load_offset?;
+        let mut dynamic = dynamic?;
+
+        let mut hash = 0;
+        let mut gnu_hash = 0;
+        let mut symtab = 0;
+        let mut strtab = 0;
+        let mut versym = 0;
+        let mut verdef = 0;
+        loop {
+            let addr = load_offset.wrapping_add((*dynamic).d_val as usize);
+            match (*dynamic).d_tag {
+                DT_NULL => break,
+                DT_HASH => hash = addr,
+                DT_GNU_HASH => gnu_hash = addr,
+                DT_SYMTAB => symtab = addr,
+                DT_STRTAB => strtab = addr,
+                DT_VERSYM => versym = addr,
+                DT_VERDEF => verdef = addr,
+                _ => (),
+            }
+            dynamic = dynamic.offset(1);
+        }
+        if symtab == 0 || strtab == 0 {
+            return None;
+        }
+
+        let symbols = if hash != 0 {
+            // The number of chains, one for each symbol
+            *(hash as *const u32).offset(1) as usize
+        } else if gnu_hash != 0 {
+            gnu_hash_symbols(gnu_hash as *const u32)
+        } else {
+            return None;
+        };
+
+        // Without version definitions, any version will do
+        if verdef == 0 {
+            versym = 0;
+        }
+
+        Some(Vdso {
+            load_offset,
+            symtab: symtab as *const Sym,
+            strtab: strtab as *const c_char,
+            symbols,
+            versym: versym as *const u16,
+            verdef: verdef as *const Verdef,
+        })
+    }
+
+    unsafe fn name(&self, offset: u32) -> &[u8] {
+        super::super::c_str(self.strtab.offset(offset as isize))
+    }
+
+    unsafe fn has_version(&self, index: usize, version: &[u8]) -> bool {
+        if self.versym.is_null() {
+            return true;
+        }
+        let ndx = *self.versym.offset(index as isize) & !VERSYM_HIDDEN;
+
+        let mut def = self.verdef;
+        loop {
+            if (*def).vd_flags & VER_FLG_BASE == 0 && (*def).vd_ndx & !VERSYM_HIDDEN == ndx {
+                let aux = (def as usize + (*def).vd_aux as usize) as *const Verdaux;
+                return self.name((*aux).vda_name) == version;
+            }
+            if (*def).vd_next == 0 {
+                return false;
+            }
+            def = (def as usize + (*def).vd_next as usize) as *const Verdef;
+        }
+    }
+
+    /// Where the function `name` of the given version is mapped
+    unsafe fn function(&self, name: &[u8], version: &[u8]) -> Option<usize> {
+        for i in 0..self.symbols {
+            let sym = &*self.symtab.offset(i as isize);
+            let kind = sym.st_info & 0xf;
+            let bind = sym.st_info >> 4;
+            if kind == STT_FUNC
+                && (bind == STB_GLOBAL || bind == STB_WEAK)
+                && sym.st_shndx != SHN_UNDEF
+                && self.name(sym.st_name) == name
+                && self.has_version(i, version)
+            {
+                return Some(self.load_offset.wrapping_add(sym.st_value as usize));
+            }
+        }
+        None
+    }
+}
+
+/// Count the symbols of a GNU hash table, which only says how far the chain
+/// of its last bucket goes
+unsafe fn gnu_hash_symbols(table: *const u32) -> usize {
+    let buckets = *table as usize;
+    let first = *table.offset(1) as usize;
+    let bloom_words = *table.offset(2) as usize;
+    let bucket = table.offset(4 + (bloom_words * mem::size_of::<usize>() / 4) as isize);
+    let chain = bucket.offset(buckets as isize);
+
+    let mut last = 0;
+    for i in 0..buckets {
+        last = last.max(*bucket.offset(i as isize) as usize);
+    }
+    if last < first {
+        return first;
+    }
+    // The lowest bit marks the end of a chain
+    while *chain.offset((last - first) as isize) & 1 == 0 {
+        last += 1;
+    }
+    last + 1
+}
+
+/// Look up the functions of the vDSO mapped at `base`
+pub unsafe fn init(base: usize) {
+    let vdso = match Vdso::parse(base) {
+        Some(vdso) => vdso,
+        None => return,
+    };
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        CLOCK_GETTIME = vdso
+            .function(b"__vdso_clock_gettime", b"LINUX_2.6")
+            .map(|addr| mem::transmute(addr));
+        GETTIMEOFDAY = vdso
+            .function(b"__vdso_gettimeofday", b"LINUX_2.6")
+            .map(|addr| mem::transmute(addr));
+        TIME = vdso
+            .function(b"__vdso_time", b"LINUX_2.6")
+            .map(|addr| mem::transmute(addr));
+        GETCPU = vdso
+            .function(b"__vdso_getcpu", b"LINUX_2.6")
+            .map(|addr| mem::transmute(addr));
+    }
+    // There is no time or getcpu here
+    #[cfg(target_arch = "aarch64")]
+    {
+        CLOCK_GETTIME = vdso
+            .function(b"__kernel_clock_gettime", b"LINUX_2.6.39")
+            .map(|addr| mem::transmute(addr));
+        GETTIMEOFDAY = vdso
+            .function(b"__kernel_gettimeofday", b"LINUX_2.6.39")
+            .map(|addr| mem::transmute(addr));
+    }
+}
+
+// Like the system calls, these return a negative error number on failure
+
+pub fn clock_gettime(clk_id: clockid_t, tp: *mut timespec) -> Option<usize> {
+    unsafe { CLOCK_GETTIME }.map(|f| f(clk_id, tp) as usize)
+}
+
+pub fn gettimeofday(tp: *mut timeval, tzp: *mut timezone) -> Option<usize> {
+    unsafe { GETTIMEOFDAY }.map(|f| f(tp, tzp) as usize)
+}
+
+pub fn time(tloc: *mut time_t) -> Option<time_t> {
+    unsafe { TIME }.map(|f| f(tloc))
+}
+
+pub fn getcpu(cpu: *mut c_uint, node: *mut c_uint) -> Option<usize> {
+    unsafe { GETCPU }.map(|f| f(cpu, node, ptr::null_mut()) as usize)
+}
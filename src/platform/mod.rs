@@ -3,12 +3,6 @@ use core::{fmt, ptr};
 
 pub use self::allocator::*;
 
-#[cfg(not(feature = "ralloc"))]
-#[path = "allocator/dlmalloc.rs"]
-mod allocator;
-
-#[cfg(feature = "ralloc")]
-#[path = "allocator/ralloc.rs"]
 mod allocator;
 
 pub use self::pal::{Pal, PalSignal, PalSocket};
@@ -25,6 +19,9 @@ mod sys;
 #[path = "redox/mod.rs"]
 mod sys;
 
+#[cfg(all(not(feature = "no_std"), target_os = "linux"))]
+pub use self::sys::{tls, vdso};
+
 pub use self::rawfile::RawFile;
 
 pub mod rawfile;
@@ -30,10 +30,48 @@ pub trait Pal {
         Self::no_pal("chown")
     }
 
+    fn clock_getcpuclockid(pid: pid_t, clock_id: *mut clockid_t) -> c_int {
+        Self::no_pal("clock_getcpuclockid")
+    }
+
+    fn clock_getres(clk_id: clockid_t, res: *mut timespec) -> c_int {
+        Self::no_pal("clock_getres")
+    }
+
     fn clock_gettime(clk_id: clockid_t, tp: *mut timespec) -> c_int {
         Self::no_pal("clock_gettime")
     }
 
+    fn clock_nanosleep(
+        clk_id: clockid_t,
+        flags: c_int,
+        rqtp: *const timespec,
+        rmtp: *mut timespec,
+    ) -> c_int {
+        Self::no_pal("clock_nanosleep")
+    }
+
+    fn clock_settime(clk_id: clockid_t, tp: *const timespec) -> c_int {
+        Self::no_pal("clock_settime")
+    }
+
+    unsafe fn clone_thread(
+        stack: *mut c_void,
+        func: extern "C" fn(*mut c_void),
+        arg: *mut c_void,
+        ctid: *mut c_int,
+    ) -> pid_t {
+        Self::no_pal("clone_thread")
+    }
+
+    unsafe fn clone_vfork(
+        stack: *mut c_void,
+        func: extern "C" fn(*mut c_void),
+        arg: *mut c_void,
+    ) -> pid_t {
+        Self::no_pal("clone_vfork")
+    }
+
     fn close(fildes: c_int) -> c_int {
         Self::no_pal("close")
     }
@@ -52,6 +90,8 @@ pub trait Pal {
 
     fn exit(status: c_int) -> !;
 
+    fn exit_thread() -> !;
+
     fn fchdir(fildes: c_int) -> c_int {
         Self::no_pal("fchdir")
     }
@@ -64,6 +104,10 @@ pub trait Pal {
         Self::no_pal("fchown")
     }
 
+    unsafe fn fexecve(fildes: c_int, argv: *const *mut c_char, envp: *const *mut c_char) -> c_int {
+        Self::no_pal("fexecve")
+    }
+
     fn flock(fd: c_int, operation: c_int) -> c_int {
         Self::no_pal("flock")
     }
@@ -72,6 +116,10 @@ pub trait Pal {
         Self::no_pal("fstat")
     }
 
+    fn fstatfs(fildes: c_int, buf: *mut statfs) -> c_int {
+        Self::no_pal("fstatfs")
+    }
+
     fn fcntl(fildes: c_int, cmd: c_int, arg: c_int) -> c_int {
         Self::no_pal("fcntl")
     }
@@ -80,6 +128,10 @@ pub trait Pal {
         Self::no_pal("fork")
     }
 
+    fn fpath(fildes: c_int, out: &mut [u8]) -> ssize_t {
+        Self::no_pal("fpath") as ssize_t
+    }
+
     fn fsync(fildes: c_int) -> c_int {
         Self::no_pal("fsync")
     }
@@ -88,6 +140,10 @@ pub trait Pal {
         Self::no_pal("ftruncate")
     }
 
+    unsafe fn futex(addr: *mut c_int, op: c_int, val: c_int, timeout: *const timespec) -> c_int {
+        Self::no_pal("futex")
+    }
+
     fn futimens(fd: c_int, times: *const timespec) -> c_int {
         Self::no_pal("futimens")
     }
@@ -96,6 +152,10 @@ pub trait Pal {
         Self::no_pal("utimens")
     }
 
+    fn getcpu(cpu: *mut c_uint, node: *mut c_uint) -> c_int {
+        Self::no_pal("getcpu")
+    }
+
     fn getcwd(buf: *mut c_char, size: size_t) -> *mut c_char {
         Self::no_pal("getcwd");
         ptr::null_mut()
@@ -117,10 +177,22 @@ pub trait Pal {
         Self::no_pal("getgid")
     }
 
+    fn getrandom(buf: &mut [u8], flags: c_uint) -> ssize_t {
+        Self::no_pal("getrandom") as ssize_t
+    }
+
+    fn getrlimit(resource: c_int, rlim: *mut rlimit) -> c_int {
+        Self::no_pal("getrlimit")
+    }
+
     fn getrusage(who: c_int, r_usage: *mut rusage) -> c_int {
         Self::no_pal("getrusage")
     }
 
+    fn getsid(pid: pid_t) -> pid_t {
+        Self::no_pal("getsid")
+    }
+
     unsafe fn gethostname(name: *mut c_char, len: size_t) -> c_int {
         Self::no_pal("gethostname")
     }
@@ -141,6 +213,10 @@ pub trait Pal {
         Self::no_pal("getppid")
     }
 
+    fn gettid() -> pid_t {
+        Self::no_pal("gettid")
+    }
+
     fn gettimeofday(tp: *mut timeval, tzp: *mut timezone) -> c_int {
         Self::no_pal("gettimeofday")
     }
@@ -184,6 +260,10 @@ pub trait Pal {
         Self::no_pal("mmap") as *mut c_void
     }
 
+    unsafe fn mprotect(addr: *mut c_void, len: usize, prot: c_int) -> c_int {
+        Self::no_pal("mprotect")
+    }
+
     unsafe fn munmap(addr: *mut c_void, len: usize) -> c_int {
         Self::no_pal("munmap")
     }
@@ -204,6 +284,10 @@ pub trait Pal {
         Self::no_pal("read") as ssize_t
     }
 
+    fn readlink(pathname: &CStr, out: &mut [u8]) -> ssize_t {
+        Self::no_pal("readlink") as ssize_t
+    }
+
     fn rename(old: &CStr, new: &CStr) -> c_int {
         Self::no_pal("rename")
     }
@@ -212,6 +296,46 @@ pub trait Pal {
         Self::no_pal("rmdir")
     }
 
+    fn sched_get_priority_max(policy: c_int) -> c_int {
+        Self::no_pal("sched_get_priority_max")
+    }
+
+    fn sched_get_priority_min(policy: c_int) -> c_int {
+        Self::no_pal("sched_get_priority_min")
+    }
+
+    fn sched_getaffinity(pid: pid_t, cpusetsize: size_t, mask: *mut cpu_set_t) -> c_int {
+        Self::no_pal("sched_getaffinity")
+    }
+
+    fn sched_getparam(pid: pid_t, param: *mut sched_param) -> c_int {
+        Self::no_pal("sched_getparam")
+    }
+
+    fn sched_getscheduler(pid: pid_t) -> c_int {
+        Self::no_pal("sched_getscheduler")
+    }
+
+    fn sched_rr_get_interval(pid: pid_t, interval: *mut timespec) -> c_int {
+        Self::no_pal("sched_rr_get_interval")
+    }
+
+    fn sched_setaffinity(pid: pid_t, cpusetsize: size_t, mask: *const cpu_set_t) -> c_int {
+        Self::no_pal("sched_setaffinity")
+    }
+
+    fn sched_setparam(pid: pid_t, param: *const sched_param) -> c_int {
+        Self::no_pal("sched_setparam")
+    }
+
+    fn sched_setscheduler(pid: pid_t, policy: c_int, param: *const sched_param) -> c_int {
+        Self::no_pal("sched_setscheduler")
+    }
+
+    fn sched_yield() -> c_int {
+        Self::no_pal("sched_yield")
+    }
+
     fn select(
         nfds: c_int,
         readfds: *mut fd_set,
@@ -238,14 +362,72 @@ pub trait Pal {
         Self::no_pal("setreuid")
     }
 
+    fn setrlimit(resource: c_int, rlim: *const rlimit) -> c_int {
+        Self::no_pal("setrlimit")
+    }
+
+    fn setsid() -> pid_t {
+        Self::no_pal("setsid")
+    }
+
+    fn statfs(path: &CStr, buf: *mut statfs) -> c_int {
+        Self::no_pal("statfs")
+    }
+
     fn tcgetattr(fd: c_int, out: *mut termios) -> c_int {
         Self::no_pal("tcgetattr")
     }
 
+    fn tcgetpgrp(fd: c_int) -> pid_t {
+        Self::no_pal("tcgetpgrp")
+    }
+
+    fn tcgetsid(fd: c_int) -> pid_t {
+        Self::no_pal("tcgetsid")
+    }
+
     fn tcsetattr(fd: c_int, act: c_int, value: *const termios) -> c_int {
         Self::no_pal("tcsetattr")
     }
 
+    fn tcsetpgrp(fd: c_int, pgrp: pid_t) -> c_int {
+        Self::no_pal("tcsetpgrp")
+    }
+
+    /// The CPU-time clock of the thread `tid` of this process
+    fn thread_getcpuclockid(tid: pid_t, clock_id: *mut clockid_t) -> c_int {
+        Self::no_pal("thread_getcpuclockid")
+    }
+
+    fn time(tloc: *mut time_t) -> time_t {
+        Self::no_pal("time") as time_t
+    }
+
+    fn timer_create(clock_id: clockid_t, evp: *mut sigevent, timerid: *mut c_int) -> c_int {
+        Self::no_pal("timer_create")
+    }
+
+    fn timer_delete(timerid: c_int) -> c_int {
+        Self::no_pal("timer_delete")
+    }
+
+    fn timer_getoverrun(timerid: c_int) -> c_int {
+        Self::no_pal("timer_getoverrun")
+    }
+
+    fn timer_gettime(timerid: c_int, value: *mut itimerspec) -> c_int {
+        Self::no_pal("timer_gettime")
+    }
+
+    fn timer_settime(
+        timerid: c_int,
+        flags: c_int,
+        value: *const itimerspec,
+        ovalue: *mut itimerspec,
+    ) -> c_int {
+        Self::no_pal("timer_settime")
+    }
+
     fn times(out: *mut tms) -> clock_t {
         Self::no_pal("times") as clock_t
     }
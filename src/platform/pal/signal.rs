@@ -21,4 +21,14 @@ pub trait PalSignal: Pal {
     fn sigprocmask(how: c_int, set: *const sigset_t, oset: *mut sigset_t) -> c_int {
         Self::no_pal("sigprocmask")
     }
+
+    /// Wait for one of the signals in `set` to be pending and take it,
+    /// returning its number
+    fn sigtimedwait(set: *const sigset_t, timeout: *const timespec) -> c_int {
+        Self::no_pal("sigtimedwait")
+    }
+
+    fn tkill(tid: pid_t, sig: c_int) -> c_int {
+        Self::no_pal("tkill")
+    }
 }
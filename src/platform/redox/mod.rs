@@ -2,9 +2,11 @@
 
 use alloc::btree_map::BTreeMap;
 use core::fmt::Write;
-use core::{mem, ptr, slice};
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering, ATOMIC_BOOL_INIT, ATOMIC_USIZE_INIT};
+use core::{cmp, mem, ptr, slice};
 use spin::{Mutex, MutexGuard, Once};
 use syscall::data::Stat as redox_stat;
+use syscall::data::StatVfs as redox_statvfs;
 use syscall::data::TimeSpec as redox_timespec;
 use syscall::flag::*;
 use syscall::{self, Result};
@@ -20,6 +22,28 @@ const EINVAL: c_int = 22;
 const ENOSYS: c_int = 38;
 const MAP_ANON: c_int = 1;
 
+// Clocks the kernel does not have, numbered like on Linux. The CPU-time
+// clocks are read from sys:context, see cpu_time.
+const CLOCK_PROCESS_CPUTIME_ID: clockid_t = 2;
+const CLOCK_THREAD_CPUTIME_ID: clockid_t = 3;
+const CLOCK_BOOTTIME: clockid_t = 7;
+const TIMER_ABSTIME: c_int = 1;
+
+// The CPU-time clock of another thread is named like on Linux, by the
+// complement of its id, shifted left to make room for these flags
+const CPUCLOCK_SCHED: clockid_t = 2;
+const CPUCLOCK_PERTHREAD: clockid_t = 4;
+const CPUCLOCK_MASK: clockid_t = 7;
+
+/// sys:context has the CPU time of every context in hundredths of a second
+const CPU_TIME_RESOLUTION: u64 = 10_000_000;
+
+const RLIM_INFINITY: rlim_t = !0;
+
+// The only ioctls there are, numbered like on Linux
+const TIOCGWINSZ: c_ulong = 0x5413;
+const TIOCSWINSZ: c_ulong = 0x5414;
+
 static ANONYMOUS_MAPS: Once<Mutex<BTreeMap<usize, usize>>> = Once::new();
 
 fn anonymous_maps() -> MutexGuard<'static, BTreeMap<usize, usize>> {
@@ -28,6 +52,239 @@ fn anonymous_maps() -> MutexGuard<'static, BTreeMap<usize, usize>> {
         .lock()
 }
 
+// The child tid pointers of running threads, which exit_thread clears and
+// wakes up as there is no CLONE_CHILD_CLEARTID
+static CLEAR_TIDS: Once<Mutex<BTreeMap<usize, usize>>> = Once::new();
+
+fn clear_tids() -> MutexGuard<'static, BTreeMap<usize, usize>> {
+    CLEAR_TIDS.call_once(|| Mutex::new(BTreeMap::new())).lock()
+}
+
+// The context of the main thread, once there are others, and whether it has
+// exited. Every context has its own id, so getpid only gives it in the main
+// thread.
+static PROCESS: AtomicUsize = ATOMIC_USIZE_INIT;
+static MAIN_EXITED: AtomicBool = ATOMIC_BOOL_INIT;
+
+// The CPU time of the threads of this process that have exited, which the
+// process clock keeps counting. It is only changed with CLEAR_TIDS locked,
+// together with the threads in there.
+static EXITED_CPU_TIME: AtomicUsize = ATOMIC_USIZE_INIT;
+
+fn process() -> usize {
+    match PROCESS.load(Ordering::SeqCst) {
+        0 => Sys::getpid() as usize,
+        process => process,
+    }
+}
+
+/// Add up the CPU time of the contexts that `wanted` picks out of the table
+/// in sys:context, in nanoseconds. Fails with EINVAL if there are none.
+fn cpu_time<F: Fn(usize) -> bool>(wanted: F) -> Result<u64> {
+    let fd = syscall::open("sys:context", O_RDONLY | O_CLOEXEC)?;
+    let mut buf = [0; 4096];
+    let mut len = 0;
+    let mut total = 0;
+    let mut found = false;
+    let res = loop {
+        let count = match syscall::read(fd, &mut buf[len..]) {
+            Ok(count) => count,
+            Err(err) => break Err(err),
+        };
+        len += count;
+
+        // Go through the lines that are complete, and keep the rest in front
+        // for the next read
+        let mut start = 0;
+        loop {
+            let end = match buf[start..len].iter().position(|&b| b == b'\n') {
+                Some(end) => end,
+                // The last line may not end in a newline
+                None if count == 0 && start < len => len - start,
+                None => break,
+            };
+            if let Some((id, time)) = parse_context(&buf[start..start + end]) {
+                if wanted(id) {
+                    total += time;
+                    found = true;
+                }
+            }
+            start = cmp::min(start + end + 1, len);
+        }
+        for i in start..len {
+            buf[i - start] = buf[i];
+        }
+        len -= start;
+
+        if count == 0 {
+            break Ok(());
+        } else if len == buf.len() {
+            break Err(syscall::Error::new(syscall::EIO));
+        }
+    };
+    let _ = syscall::close(fd);
+    res?;
+
+    if found {
+        Ok(total)
+    } else {
+        Err(syscall::Error::new(syscall::EINVAL))
+    }
+}
+
+/// The id and CPU time of the context on a line of sys:context. The id is in
+/// the first column, and the time in the only one that reads H:MM:SS.CC.
+fn parse_context(line: &[u8]) -> Option<(usize, u64)> {
+    let mut columns = line
+        .split(|&b| b == b' ')
+        .filter(|column| !column.is_empty());
+    let id = parse_number(columns.next()?)?;
+    let time = columns.filter_map(parse_cpu_time).next()?;
+    Some((id as usize, time))
+}
+
+fn parse_cpu_time(column: &[u8]) -> Option<u64> {
+    let mut parts = column.split(|&b| b == b':');
+    let hours = parse_number(parts.next()?)?;
+    let minutes = parse_number(parts.next()?)?;
+    let mut seconds = parts.next()?.split(|&b| b == b'.');
+    let whole = parse_number(seconds.next()?)?;
+    let hundredths = parse_number(seconds.next()?)?;
+    if parts.next().is_some() || seconds.next().is_some() {
+        return None;
+    }
+    Some(((hours * 60 + minutes) * 60 + whole) * 1_000_000_000 + hundredths * CPU_TIME_RESOLUTION)
+}
+
+fn parse_number(digits: &[u8]) -> Option<u64> {
+    if digits.is_empty() {
+        return None;
+    }
+    let mut n: u64 = 0;
+    for &digit in digits {
+        if !digit.is_ascii_digit() {
+            return None;
+        }
+        n = n.checked_mul(10)?.checked_add((digit - b'0') as u64)?;
+    }
+    Some(n)
+}
+
+/// The thread whose CPU-time clock `clk_id` is, if it is one
+fn cpu_clock_thread(clk_id: clockid_t) -> Option<usize> {
+    if clk_id == CLOCK_THREAD_CPUTIME_ID {
+        Some(Sys::gettid() as usize)
+    } else if clk_id < 0 && clk_id & CPUCLOCK_MASK == CPUCLOCK_SCHED | CPUCLOCK_PERTHREAD {
+        Some(!(clk_id >> 3) as usize)
+    } else {
+        None
+    }
+}
+
+fn is_cpu_clock(clk_id: clockid_t) -> bool {
+    clk_id == CLOCK_PROCESS_CPUTIME_ID || cpu_clock_thread(clk_id).is_some()
+}
+
+/// The time of a CPU-time clock in nanoseconds, or None if `clk_id` is not one
+fn cpu_clock(clk_id: clockid_t) -> Option<Result<u64>> {
+    if clk_id == CLOCK_PROCESS_CPUTIME_ID {
+        // Threads cannot exit while this adds them up
+        let tids = clear_tids();
+        let main = process();
+        let main_exited = MAIN_EXITED.load(Ordering::SeqCst);
+        let time = cpu_time(|id| (id == main && !main_exited) || tids.contains_key(&id));
+        let exited = EXITED_CPU_TIME.load(Ordering::SeqCst) as u64;
+        return Some(time.map(|time| time + exited));
+    }
+    cpu_clock_thread(clk_id).map(|thread| cpu_time(|id| id == thread))
+}
+
+// The kernel has no sessions, so the session of this process is kept here,
+// or 0 until it is first needed. It is inherited across fork but not exec,
+// after which the process group is taken for it again.
+static SESSION: AtomicUsize = ATOMIC_USIZE_INIT;
+
+fn session() -> usize {
+    let session = SESSION.load(Ordering::SeqCst);
+    if session != 0 {
+        return session;
+    }
+    let pgid = e(syscall::getpgid(0));
+    if pgid != !0 {
+        SESSION.store(pgid, Ordering::SeqCst);
+    }
+    pgid
+}
+
+// The child gets a copy of the registers, so it has to be put on its own
+// stack around the system call. The system call numbers are passed in by the
+// callers, from the syscall crate.
+#[cfg(target_arch = "x86_64")]
+global_asm!(
+    "
+    .global __relibc_clone
+    __relibc_clone:
+        push %rbx
+        push %r12
+        mov %rsp, %rbx
+        mov %rcx, %r12
+        mov %rsi, %rsp
+        mov %rdx, %rax
+        syscall
+        test %rax, %rax
+        jnz 1f
+        xor %ebp, %ebp
+        pop %rax
+        pop %rdi
+        call *%rax
+        xor %edi, %edi
+        mov %r12, %rax
+        syscall
+        ud2
+    1:
+        mov %rbx, %rsp
+        pop %r12
+        pop %rbx
+        ret
+
+    .global __relibc_exit_thread
+    __relibc_exit_thread:
+        mov %rsi, %rax
+        mov %rdx, %r12
+        movl $0, (%rdi)
+        mov $1, %esi # <- 1 is FUTEX_WAKE
+        mov $0x7fffffff, %edx
+        xor %r10d, %r10d
+        xor %r8d, %r8d
+        syscall
+        xor %edi, %edi
+        mov %r12, %rax
+        syscall
+        ud2
+"
+);
+
+extern "C" {
+    fn __relibc_clone(flags: usize, stack: *mut usize, clone: usize, exit: usize) -> usize;
+    /// Clear and wake up `ctid` and exit, without touching the stack of the
+    /// thread, which a joiner may unmap as soon as `ctid` is cleared
+    fn __relibc_exit_thread(ctid: *mut c_int, futex: usize, exit: usize) -> !;
+}
+
+/// What a new thread needs, left on its stack by clone_thread
+struct ThreadStart {
+    func: extern "C" fn(*mut c_void),
+    arg: *mut c_void,
+    ctid: *mut c_int,
+}
+
+extern "C" fn thread_start(start: *mut c_void) {
+    let start = unsafe { ptr::read(start as *const ThreadStart) };
+    clear_tids().insert(Sys::gettid() as usize, start.ctid as usize);
+    (start.func)(start.arg);
+    Sys::exit_thread();
+}
+
 fn e(sys: Result<usize>) -> usize {
     match sys {
         Ok(ok) => ok,
@@ -127,9 +384,63 @@ impl Pal for Sys {
         }
     }
 
+    fn clock_getcpuclockid(pid: pid_t, clock_id: *mut clockid_t) -> c_int {
+        // Only the threads of this process are known, so the clocks of other
+        // processes cannot be read
+        if pid != 0 && pid != Self::getpid() && pid as usize != process() {
+            if Self::getpgid(pid) < 0 {
+                return -1;
+            }
+            return e(Err(syscall::Error::new(syscall::EPERM))) as c_int;
+        }
+        unsafe {
+            *clock_id = CLOCK_PROCESS_CPUTIME_ID;
+        }
+        0
+    }
+
+    fn clock_getres(clk_id: clockid_t, res: *mut timespec) -> c_int {
+        let mut now = timespec::default();
+        if Self::clock_gettime(clk_id, &mut now) < 0 {
+            return -1;
+        }
+        // The kernel's clocks count in nanoseconds
+        let resolution = if is_cpu_clock(clk_id) {
+            CPU_TIME_RESOLUTION
+        } else {
+            1
+        };
+        if !res.is_null() {
+            unsafe {
+                (*res).tv_sec = 0;
+                (*res).tv_nsec = resolution as c_long;
+            }
+        }
+        0
+    }
+
     fn clock_gettime(clk_id: clockid_t, tp: *mut timespec) -> c_int {
+        if let Some(time) = cpu_clock(clk_id) {
+            return match time {
+                Ok(time) => {
+                    unsafe {
+                        (*tp).tv_sec = (time / 1_000_000_000) as time_t;
+                        (*tp).tv_nsec = (time % 1_000_000_000) as c_long;
+                    }
+                    0
+                }
+                Err(err) => e(Err(err)) as c_int,
+            };
+        }
+
+        let clk_id = match clk_id {
+            // Nothing suspends the system, so no time goes missing from the
+            // monotonic clock
+            CLOCK_BOOTTIME => syscall::CLOCK_MONOTONIC,
+            _ => clk_id as usize,
+        };
         let mut redox_tp = unsafe { redox_timespec::from(&*tp) };
-        match e(syscall::clock_gettime(clk_id as usize, &mut redox_tp)) as c_int {
+        match e(syscall::clock_gettime(clk_id, &mut redox_tp)) as c_int {
             -1 => -1,
             _ => {
                 unsafe {
@@ -141,6 +452,128 @@ impl Pal for Sys {
         }
     }
 
+    fn clock_nanosleep(
+        clk_id: clockid_t,
+        flags: c_int,
+        rqtp: *const timespec,
+        rmtp: *mut timespec,
+    ) -> c_int {
+        let rqtp = unsafe { &*rqtp };
+        let abstime = flags & TIMER_ABSTIME == TIMER_ABSTIME;
+
+        // Which also makes sure there is such a clock
+        let mut now = timespec::default();
+        if Self::clock_gettime(clk_id, &mut now) < 0 {
+            return -1;
+        }
+        if cpu_clock_thread(clk_id) == Some(Self::gettid() as usize) {
+            // The clock of the calling thread stops while it sleeps
+            return e(Err(syscall::Error::new(syscall::EINVAL))) as c_int;
+        }
+        if !abstime && !is_cpu_clock(clk_id) {
+            return Self::nanosleep(rqtp, rmtp);
+        }
+
+        // Only the kernel's own clocks can be slept on, so sleep for what is
+        // left and look at the clock again until it gets there. CPU time
+        // passes no faster than real time for each thread, so this does not
+        // sleep past it by more than the other threads run in the meantime.
+        let mut end = timespec {
+            tv_sec: rqtp.tv_sec,
+            tv_nsec: rqtp.tv_nsec,
+        };
+        if !abstime {
+            end.tv_sec += now.tv_sec;
+            end.tv_nsec += now.tv_nsec;
+            if end.tv_nsec >= 1_000_000_000 {
+                end.tv_sec += 1;
+                end.tv_nsec -= 1_000_000_000;
+            }
+        }
+        loop {
+            let mut left = timespec {
+                tv_sec: end.tv_sec - now.tv_sec,
+                tv_nsec: end.tv_nsec - now.tv_nsec,
+            };
+            if left.tv_nsec < 0 {
+                left.tv_sec -= 1;
+                left.tv_nsec += 1_000_000_000;
+            }
+            if left.tv_sec < 0 || (left.tv_sec == 0 && left.tv_nsec == 0) {
+                return 0;
+            }
+
+            if Self::nanosleep(&left, ptr::null_mut()) < 0 {
+                if !abstime && !rmtp.is_null() {
+                    unsafe {
+                        *rmtp = left;
+                    }
+                }
+                return -1;
+            }
+            if Self::clock_gettime(clk_id, &mut now) < 0 {
+                return -1;
+            }
+        }
+    }
+
+    unsafe fn clone_thread(
+        stack: *mut c_void,
+        func: extern "C" fn(*mut c_void),
+        arg: *mut c_void,
+        ctid: *mut c_int,
+    ) -> pid_t {
+        let flags =
+            syscall::CLONE_VM | syscall::CLONE_FS | syscall::CLONE_FILES | syscall::CLONE_SIGHAND;
+
+        // The first thread is started by the main thread, which is the last
+        // one that getpid tells about
+        PROCESS.compare_and_swap(0, Self::getpid() as usize, Ordering::SeqCst);
+
+        let start = (stack as *mut ThreadStart).offset(-1);
+        ptr::write(start, ThreadStart { func, arg, ctid });
+
+        // __relibc_clone pops these off the new stack in the child
+        let stack = ((start as usize & !0xF) as *mut usize).offset(-2);
+        *stack = thread_start as usize;
+        *stack.offset(1) = start as usize;
+
+        e(syscall::Error::demux(__relibc_clone(
+            flags,
+            stack,
+            syscall::number::SYS_CLONE,
+            syscall::number::SYS_EXIT,
+        ))) as pid_t
+    }
+
+    unsafe fn clone_vfork(
+        stack: *mut c_void,
+        func: extern "C" fn(*mut c_void),
+        arg: *mut c_void,
+    ) -> pid_t {
+        // The caller is suspended until the child execs or exits, so the child
+        // can use its memory in the meantime. It runs on the given stack, as
+        // the stack of the caller is shared with it.
+        let flags = syscall::CLONE_VM | syscall::CLONE_VFORK;
+
+        // __relibc_clone pops these off the new stack in the child
+        let stack = (stack as *mut usize).offset(-2);
+        *stack = func as usize;
+        *stack.offset(1) = arg as usize;
+
+        // The child may start a session of its own in what is also the memory
+        // of the caller
+        let session = SESSION.load(Ordering::SeqCst);
+        let pid = e(syscall::Error::demux(__relibc_clone(
+            flags,
+            stack,
+            syscall::number::SYS_CLONE,
+            syscall::number::SYS_EXIT,
+        ))) as pid_t;
+        SESSION.store(session, Ordering::SeqCst);
+        pid
+    }
+
     fn close(fd: c_int) -> c_int {
         e(syscall::close(fd as usize)) as c_int
     }
@@ -158,53 +591,89 @@ impl Pal for Sys {
         loop {}
     }
 
-    unsafe fn execve(
-        path: &CStr,
-        mut argv: *const *mut c_char,
-        mut envp: *const *mut c_char,
-    ) -> c_int {
-        use alloc::Vec;
+    fn exit_thread() -> ! {
+        let tid = Self::gettid() as usize;
+        let ctid = {
+            let mut tids = clear_tids();
+            let ctid = tids.remove(&tid);
+            // The process clock keeps counting the time of threads that are
+            // gone
+            let main = tid == process();
+            if ctid.is_some() || main {
+                if let Ok(time) = cpu_time(|id| id == tid) {
+                    EXITED_CPU_TIME.fetch_add(time as usize, Ordering::SeqCst);
+                }
+                if main {
+                    MAIN_EXITED.store(true, Ordering::SeqCst);
+                }
+            }
+            ctid
+        };
+        if let Some(ctid) = ctid {
+            unsafe {
+                __relibc_exit_thread(
+                    ctid as *mut c_int,
+                    syscall::number::SYS_FUTEX,
+                    syscall::number::SYS_EXIT,
+                );
+            }
+        }
+        let _ = syscall::exit(0);
+        loop {}
+    }
 
+    unsafe fn execve(path: &CStr, argv: *const *mut c_char, envp: *const *mut c_char) -> c_int {
         let fd = match RawFile::open(path, O_RDONLY as c_int, 0) {
             Ok(fd) => fd,
             Err(_) => return -1,
         };
+        Self::fexecve(*fd, argv, envp)
+    }
 
-        let mut len = 0;
-        while !(*argv.offset(len)).is_null() {
-            len += 1;
+    unsafe fn fexecve(fildes: c_int, argv: *const *mut c_char, envp: *const *mut c_char) -> c_int {
+        // The table of arguments goes in memory mapped for it rather than on
+        // the heap, as the child of posix_spawn shares the heap of its parent
+        // and must not allocate
+        let mut argc = 0;
+        while !(*argv.offset(argc)).is_null() {
+            argc += 1;
         }
-
-        let mut args: Vec<[usize; 2]> = Vec::with_capacity(len as usize);
-        while !(*argv).is_null() {
-            let arg = *argv;
-
-            let mut len = 0;
-            while *arg.offset(len) != 0 {
-                len += 1;
-            }
-            args.push([arg as usize, len as usize]);
-            argv = argv.offset(1);
+        let mut envc = 0;
+        while !(*envp.offset(envc)).is_null() {
+            envc += 1;
         }
+        let count = (argc + envc) as usize;
+        let size = cmp::max(count, 1) * mem::size_of::<[usize; 2]>();
 
-        let mut len = 0;
-        while !(*envp.offset(len)).is_null() {
-            len += 1;
+        let fd = e(syscall::open("memory:", O_CLOEXEC));
+        if fd == !0 {
+            return -1;
+        }
+        let addr = e(syscall::fmap(fd, 0, size));
+        if addr == !0 {
+            let _ = syscall::close(fd);
+            return -1;
         }
 
-        let mut envs: Vec<[usize; 2]> = Vec::with_capacity(len as usize);
-        while !(*envp).is_null() {
-            let env = *envp;
-
+        let table = slice::from_raw_parts_mut(addr as *mut [usize; 2], count);
+        for (i, entry) in table.iter_mut().enumerate() {
+            let arg = if i < argc as usize {
+                *argv.offset(i as isize)
+            } else {
+                *envp.offset(i as isize - argc)
+            };
             let mut len = 0;
-            while *env.offset(len) != 0 {
+            while *arg.offset(len) != 0 {
                 len += 1;
             }
-            envs.push([env as usize, len as usize]);
-            envp = envp.offset(1);
+            *entry = [arg as usize, len as usize];
         }
 
-        e(syscall::fexec(*fd as usize, &args, &envs)) as c_int
+        let (args, envs) = table.split_at(argc as usize);
+        let ret = e(syscall::fexec(fildes as usize, args, envs)) as c_int;
+        let _ = syscall::funmap(addr);
+        let _ = syscall::close(fd);
+        ret
     }
 
     fn fchdir(fd: c_int) -> c_int {
@@ -234,7 +703,21 @@ impl Pal for Sys {
     }
 
     fn fork() -> pid_t {
-        e(unsafe { syscall::clone(0) }) as pid_t
+        // Settle the session before the child inherits it
+        session();
+
+        // No thread can be halfway through changing the threads while the
+        // child gets its copy
+        let mut tids = clear_tids();
+        let pid = e(unsafe { syscall::clone(0) }) as pid_t;
+        if pid == 0 {
+            // Only the calling thread is in the child, as its main thread
+            tids.clear();
+            PROCESS.store(0, Ordering::SeqCst);
+            MAIN_EXITED.store(false, Ordering::SeqCst);
+            EXITED_CPU_TIME.store(0, Ordering::SeqCst);
+        }
+        pid
     }
 
     fn fstat(fildes: c_int, buf: *mut stat) -> c_int {
@@ -273,6 +756,30 @@ impl Pal for Sys {
         }
     }
 
+    fn fstatfs(fildes: c_int, buf: *mut statfs) -> c_int {
+        let mut redox_buf: redox_statvfs = redox_statvfs::default();
+        if e(syscall::fstatvfs(fildes as usize, &mut redox_buf)) == !0 {
+            return -1;
+        }
+        unsafe {
+            if !buf.is_null() {
+                // Schemes do not report a type, file counts or a name length,
+                // which are left at 0 for unknown
+                *buf = statfs::default();
+                (*buf).f_bsize = redox_buf.f_bsize as c_long;
+                (*buf).f_frsize = redox_buf.f_bsize as c_long;
+                (*buf).f_blocks = redox_buf.f_blocks as c_ulong;
+                (*buf).f_bfree = redox_buf.f_bfree as c_ulong;
+                (*buf).f_bavail = redox_buf.f_bavail as c_ulong;
+            }
+        }
+        0
+    }
+
+    fn fpath(fildes: c_int, out: &mut [u8]) -> ssize_t {
+        e(syscall::fpath(fildes as usize, out)) as ssize_t
+    }
+
     fn fsync(fd: c_int) -> c_int {
         e(syscall::fsync(fd as usize)) as c_int
     }
@@ -281,6 +788,23 @@ impl Pal for Sys {
         e(syscall::ftruncate(fd as usize, len as usize)) as c_int
     }
 
+    unsafe fn futex(addr: *mut c_int, op: c_int, val: c_int, timeout: *const timespec) -> c_int {
+        let redox_timeout = if timeout.is_null() {
+            None
+        } else {
+            Some(redox_timespec::from(&*timeout))
+        };
+        e(syscall::futex(
+            addr,
+            op as usize,
+            val,
+            redox_timeout
+                .as_ref()
+                .map_or(0, |timeout| timeout as *const redox_timespec as usize),
+            ptr::null_mut(),
+        )) as c_int
+    }
+
     fn futimens(fd: c_int, times: *const timespec) -> c_int {
         let times = [unsafe { redox_timespec::from(&*times) }, unsafe {
             redox_timespec::from(&*times.offset(1))
@@ -406,6 +930,40 @@ impl Pal for Sys {
         e(syscall::getppid()) as pid_t
     }
 
+    fn getrandom(buf: &mut [u8], flags: c_uint) -> ssize_t {
+        // rand: never blocks, so the flags make no difference
+        let fd = e(syscall::open("rand:", O_RDONLY | O_CLOEXEC));
+        if fd == !0 {
+            return -1;
+        }
+        let res = e(syscall::read(fd, buf)) as ssize_t;
+        let _ = syscall::close(fd);
+        res
+    }
+
+    fn getrlimit(_resource: c_int, rlim: *mut rlimit) -> c_int {
+        // Nothing is limited
+        unsafe {
+            (*rlim).rlim_cur = RLIM_INFINITY;
+            (*rlim).rlim_max = RLIM_INFINITY;
+        }
+        0
+    }
+
+    fn getsid(pid: pid_t) -> pid_t {
+        if pid == 0 || pid == Self::getpid() {
+            return session() as pid_t;
+        }
+        // Other processes cannot be asked for their session, so their process
+        // group stands in for it
+        Self::getpgid(pid)
+    }
+
+    fn gettid() -> pid_t {
+        // Every context has its own id on Redox
+        e(syscall::getpid()) as pid_t
+    }
+
     fn gettimeofday(tp: *mut timeval, tzp: *mut timezone) -> c_int {
         let mut redox_tp = redox_timespec::default();
         let err = e(syscall::clock_gettime(
@@ -431,6 +989,35 @@ impl Pal for Sys {
         e(syscall::getuid()) as pid_t
     }
 
+    fn ioctl(fd: c_int, request: c_ulong, out: *mut c_void) -> c_int {
+        if request != TIOCGWINSZ && request != TIOCSWINSZ {
+            return e(Err(syscall::Error::new(syscall::ENOTTY))) as c_int;
+        }
+
+        let dup = e(syscall::dup(fd as usize, b"winsize"));
+        if dup == !0 {
+            return -1;
+        }
+
+        if request == TIOCGWINSZ {
+            unsafe { *(out as *mut winsize) = winsize::default() };
+        }
+        // The terminal only knows the rows and columns, which come first
+        let buf =
+            unsafe { slice::from_raw_parts_mut(out as *mut u8, 2 * mem::size_of::<c_ushort>()) };
+        let res = if request == TIOCGWINSZ {
+            e(syscall::read(dup, buf))
+        } else {
+            e(syscall::write(dup, buf))
+        };
+        let _ = syscall::close(dup);
+
+        if res == !0 {
+            return -1;
+        }
+        0
+    }
+
     fn isatty(fd: c_int) -> c_int {
         syscall::dup(fd as usize, b"termios")
             .map(|fd| {
@@ -502,6 +1089,11 @@ impl Pal for Sys {
         }
     }
 
+    unsafe fn mprotect(_addr: *mut c_void, _len: usize, _prot: c_int) -> c_int {
+        // Memory is always mapped readable and writable
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
     unsafe fn munmap(addr: *mut c_void, _len: usize) -> c_int {
         if e(syscall::funmap(addr as usize)) == !0 {
             return !0;
@@ -553,6 +1145,28 @@ impl Pal for Sys {
         e(syscall::read(fd as usize, buf)) as ssize_t
     }
 
+    fn readlink(pathname: &CStr, out: &mut [u8]) -> ssize_t {
+        let fd = e(syscall::open(pathname.to_bytes(), O_SYMLINK | O_RDONLY));
+        if fd == !0 {
+            return -1;
+        }
+
+        let mut stat = redox_stat::default();
+        let res = if e(syscall::fstat(fd, &mut stat)) == !0 {
+            -1
+        } else if stat.st_mode & MODE_TYPE != MODE_SYMLINK {
+            unsafe {
+                errno = EINVAL;
+            }
+            -1
+        } else {
+            e(syscall::read(fd, out)) as ssize_t
+        };
+
+        let _ = syscall::close(fd);
+        res
+    }
+
     fn rename(oldpath: &CStr, newpath: &CStr) -> c_int {
         match syscall::open(oldpath.to_bytes(), O_WRONLY) {
             Ok(fd) => {
@@ -568,6 +1182,49 @@ impl Pal for Sys {
         e(syscall::rmdir(path.to_bytes())) as c_int
     }
 
+    // Redox has neither scheduling policies nor CPU affinity, so these fail
+    // explicitly instead of going through no_pal
+
+    fn sched_get_priority_max(_policy: c_int) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn sched_get_priority_min(_policy: c_int) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn sched_getaffinity(_pid: pid_t, _cpusetsize: size_t, _mask: *mut cpu_set_t) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn sched_getparam(_pid: pid_t, _param: *mut sched_param) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn sched_getscheduler(_pid: pid_t) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn sched_rr_get_interval(_pid: pid_t, _interval: *mut timespec) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn sched_setaffinity(_pid: pid_t, _cpusetsize: size_t, _mask: *const cpu_set_t) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn sched_setparam(_pid: pid_t, _param: *const sched_param) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn sched_setscheduler(_pid: pid_t, _policy: c_int, _param: *const sched_param) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn sched_yield() -> c_int {
+        e(syscall::sched_yield()) as c_int
+    }
+
     fn select(
         nfds: c_int,
         readfds: *mut fd_set,
@@ -679,6 +1336,10 @@ impl Pal for Sys {
     }
 
     fn setpgid(pid: pid_t, pgid: pid_t) -> c_int {
+        // Leaving the process group does not leave the session
+        if pid == 0 || pid == Self::getpid() {
+            session();
+        }
         e(syscall::setpgid(pid as usize, pgid as usize)) as c_int
     }
 
@@ -690,6 +1351,34 @@ impl Pal for Sys {
         e(syscall::setreuid(ruid as usize, euid as usize)) as c_int
     }
 
+    fn setrlimit(_resource: c_int, _rlim: *const rlimit) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn setsid() -> pid_t {
+        let pid = Self::getpid();
+        // A group leader cannot start a session
+        if Self::getpgid(0) == pid {
+            return e(Err(syscall::Error::new(syscall::EPERM))) as pid_t;
+        }
+        if e(syscall::setpgid(0, 0)) == !0 {
+            return -1;
+        }
+        SESSION.store(pid as usize, Ordering::SeqCst);
+        pid
+    }
+
+    fn statfs(path: &CStr, buf: *mut statfs) -> c_int {
+        match syscall::open(path.to_bytes(), O_STAT) {
+            Err(err) => e(Err(err)) as c_int,
+            Ok(fd) => {
+                let res = Self::fstatfs(fd as c_int, buf);
+                let _ = syscall::close(fd);
+                res
+            }
+        }
+    }
+
     fn tcgetattr(fd: c_int, out: *mut termios) -> c_int {
         let dup = e(syscall::dup(fd as usize, b"termios"));
         if dup == !0 {
@@ -707,6 +1396,32 @@ impl Pal for Sys {
         0
     }
 
+    fn tcgetpgrp(fd: c_int) -> pid_t {
+        let dup = e(syscall::dup(fd as usize, b"pgrp"));
+        if dup == !0 {
+            return -1;
+        }
+
+        let mut pgrp = 0usize;
+        let read = e(syscall::read(dup, unsafe {
+            slice::from_raw_parts_mut(&mut pgrp as *mut usize as *mut u8, mem::size_of::<usize>())
+        }));
+        let _ = syscall::close(dup);
+
+        if read == !0 {
+            return -1;
+        }
+        pgrp as pid_t
+    }
+
+    fn tcgetsid(fd: c_int) -> pid_t {
+        // A terminal can only be asked about by the session it controls
+        if Self::tcgetpgrp(fd) < 0 {
+            return -1;
+        }
+        Self::getsid(0)
+    }
+
     fn tcsetattr(fd: c_int, _act: c_int, value: *const termios) -> c_int {
         let dup = e(syscall::dup(fd as usize, b"termios"));
         if dup == !0 {
@@ -724,6 +1439,54 @@ impl Pal for Sys {
         0
     }
 
+    fn tcsetpgrp(fd: c_int, pgrp: pid_t) -> c_int {
+        let dup = e(syscall::dup(fd as usize, b"pgrp"));
+        if dup == !0 {
+            return -1;
+        }
+
+        let pgrp = pgrp as usize;
+        let write = e(syscall::write(dup, unsafe {
+            slice::from_raw_parts(&pgrp as *const usize as *const u8, mem::size_of::<usize>())
+        }));
+        let _ = syscall::close(dup);
+
+        if write == !0 {
+            return -1;
+        }
+        0
+    }
+
+    fn thread_getcpuclockid(tid: pid_t, clock_id: *mut clockid_t) -> c_int {
+        let ours = tid == Self::gettid()
+            || tid as usize == process()
+            || clear_tids().contains_key(&(tid as usize));
+        if !ours {
+            return e(Err(syscall::Error::new(syscall::ESRCH))) as c_int;
+        }
+        unsafe {
+            *clock_id = (!tid << 3) | CPUCLOCK_SCHED | CPUCLOCK_PERTHREAD;
+        }
+        0
+    }
+
+    fn time(tloc: *mut time_t) -> time_t {
+        let mut redox_tp = redox_timespec::default();
+        let err = e(syscall::clock_gettime(
+            syscall::CLOCK_REALTIME,
+            &mut redox_tp,
+        )) as c_int;
+        if err < 0 {
+            return -1;
+        }
+        if !tloc.is_null() {
+            unsafe {
+                *tloc = redox_tp.tv_sec;
+            }
+        }
+        redox_tp.tv_sec
+    }
+
     fn unlink(path: &CStr) -> c_int {
         e(syscall::unlink(path.to_bytes())) as c_int
     }
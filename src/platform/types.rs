@@ -96,6 +96,13 @@ pub struct itimerval {
     pub it_value: timeval,
 }
 
+#[repr(C)]
+#[derive(Default)]
+pub struct itimerspec {
+    pub it_interval: timespec,
+    pub it_value: timespec,
+}
+
 #[cfg(target_os = "redox")]
 impl<'a> From<&'a timespec> for redox_timespec {
     fn from(tp: &timespec) -> redox_timespec {
@@ -174,6 +181,17 @@ pub struct sigaction {
 
 pub type sigset_t = c_ulong;
 
+/// The kernel's sigevent, where SIGEV_THREAD_ID puts the thread to notify in
+/// place of the notify function
+#[repr(C)]
+pub struct sigevent {
+    pub sigev_value: *mut c_void,
+    pub sigev_signo: c_int,
+    pub sigev_notify: c_int,
+    pub sigev_notify_thread_id: pid_t,
+    pub __sigev_pad: [c_int; 11],
+}
+
 const UTSLENGTH: usize = 65;
 
 #[repr(C)]
@@ -204,6 +222,15 @@ pub struct winsize {
     ws_ypixel: c_ushort,
 }
 
+pub type rlim_t = u64;
+
+#[repr(C)]
+#[derive(Default)]
+pub struct rlimit {
+    pub rlim_cur: rlim_t,
+    pub rlim_max: rlim_t,
+}
+
 #[repr(C)]
 pub struct rusage {
     pub ru_utime: timeval,
@@ -224,6 +251,23 @@ pub struct rusage {
     pub ru_nivcsw: c_long,
 }
 
+#[repr(C)]
+#[derive(Default)]
+pub struct statfs {
+    pub f_type: c_long,
+    pub f_bsize: c_long,
+    pub f_blocks: c_ulong,
+    pub f_bfree: c_ulong,
+    pub f_bavail: c_ulong,
+    pub f_files: c_ulong,
+    pub f_ffree: c_ulong,
+    pub f_fsid: [c_int; 2],
+    pub f_namelen: c_long,
+    pub f_frsize: c_long,
+    pub f_flags: c_long,
+    pub f_spare: [c_long; 4],
+}
+
 #[repr(C)]
 pub struct tms {
     tms_utime: clock_t,
@@ -238,6 +282,21 @@ pub struct fd_set {
     pub fds_bits: [c_ulong; FD_SETSIZE / (8 * mem::size_of::<c_ulong>())],
 }
 
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct sched_param {
+    pub sched_priority: c_int,
+}
+
+pub const CPU_SETSIZE: usize = 1024;
+#[repr(C)]
+pub struct cpu_set_t {
+    pub __bits: [c_ulong; CPU_SETSIZE / (8 * mem::size_of::<c_ulong>())],
+}
+
+pub const FUTEX_WAIT: c_int = 0;
+pub const FUTEX_WAKE: c_int = 1;
+
 pub const F_OK: c_int = 0;
 pub const R_OK: c_int = 4;
 pub const W_OK: c_int = 2;
@@ -1,10 +1,19 @@
 use alloc::Vec;
 use core::ptr;
 
-use header::stdio;
+use header::{stdio, stdlib};
 use platform;
 use platform::types::*;
-use platform::{Pal, Sys};
+
+const MALLOC_CHECK: &[u8] = b"RELIBC_MALLOC_CHECK=";
+const LEAK_REPORT: &[u8] = b"RELIBC_LEAK_REPORT=";
+
+fn env_flag(var: &[u8], name: &[u8]) -> bool {
+    var.starts_with(name) && match &var[name.len()..] {
+        b"" | b"0" => false,
+        _ => true,
+    }
+}
 
 #[repr(C)]
 pub struct Stack {
@@ -24,23 +33,68 @@ impl Stack {
     fn envp(&self) -> *const *const c_char {
         unsafe { self.argv().offset(self.argc() + 1) }
     }
+
+    #[cfg(target_os = "linux")]
+    fn auxv(&self) -> *const c_ulong {
+        unsafe {
+            let mut envp = self.envp();
+            while !(*envp).is_null() {
+                envp = envp.offset(1);
+            }
+            envp.offset(1) as *const c_ulong
+        }
+    }
 }
 
 #[inline(never)]
 #[no_mangle]
 pub unsafe extern "C" fn relibc_start(sp: &'static Stack) -> ! {
     extern "C" {
+        static mut __stack_chk_guard: usize;
         fn main(argc: isize, argv: *const *const c_char, envp: *const *const c_char) -> c_int;
     }
 
+    // Before anything can use a thread local
+    #[cfg(target_os = "linux")]
+    {
+        use header::sys_auxv::{self, AT_PHDR, AT_PHNUM};
+
+        sys_auxv::init(sp.auxv());
+        if let (Some(phdr), Some(phnum)) = (sys_auxv::get(AT_PHDR), sys_auxv::get(AT_PHNUM)) {
+            platform::tls::init(phdr as usize, phnum as usize);
+        }
+    }
+
     let argc = sp.argc();
     let argv = sp.argv();
 
     let envp = sp.envp();
     let mut len = 0;
+    let mut leak_report = false;
     while *envp.offset(len) != ptr::null() {
+        let var = platform::c_str(*envp.offset(len));
+        // Has to be decided before anything is allocated
+        if env_flag(var, MALLOC_CHECK) {
+            platform::alloc_check_enable();
+        }
+        leak_report |= env_flag(var, LEAK_REPORT);
         len += 1;
     }
+
+    #[cfg(target_os = "linux")]
+    {
+        use header::sys_auxv;
+
+        if let Some(random) = sys_auxv::get(sys_auxv::AT_RANDOM) {
+            // The low byte stays zero, so that string functions cannot read
+            // or write past the canary
+            __stack_chk_guard = ptr::read_unaligned(random as *const usize) & !0xFF;
+        }
+        if let Some(base) = sys_auxv::get(sys_auxv::AT_SYSINFO_EHDR) {
+            platform::vdso::init(base as usize);
+        }
+    }
+
     platform::inner_environ = Vec::with_capacity(len as usize + 1);
     for i in 0..len {
         let mut item = *envp.offset(i);
@@ -58,16 +112,22 @@ pub unsafe extern "C" fn relibc_start(sp: &'static Stack) -> ! {
     platform::inner_environ.push(ptr::null_mut());
     platform::environ = platform::inner_environ.as_mut_ptr();
 
+    // Only after the environment is copied, which is never freed
+    if leak_report {
+        platform::alloc_leak_report_enable();
+    }
+
     // Initialize stdin/stdout/stderr, see https://github.com/rust-lang/rust/issues/51718
     stdio::stdin = stdio::default_stdin.get();
     stdio::stdout = stdio::default_stdout.get();
     stdio::stderr = stdio::default_stderr.get();
 
-    Sys::exit(main(
+    stdlib::exit(main(
         argc,
         argv,
         // not envp, because programs like bash try to modify this *const*
         // pointer :|
         platform::environ as *const *const c_char,
     ));
+    unreachable!();
 }
@@ -0,0 +1,140 @@
+//! Synchronization primitives for relibc internals, built on futexes
+
+use core::cell::UnsafeCell;
+use core::ops::{Deref, DerefMut};
+use core::{intrinsics, ptr};
+
+use platform::types::*;
+use platform::{Pal, Sys};
+
+const CLOCK_REALTIME: clockid_t = 0;
+
+const UNLOCKED: c_int = 0;
+const LOCKED: c_int = 1;
+const CONTENDED: c_int = 2;
+
+/// Block while `*addr == val`, until woken up or `timeout` (relative) expires
+pub unsafe fn futex_wait(addr: *mut c_int, val: c_int, timeout: Option<&timespec>) {
+    Sys::futex(
+        addr,
+        FUTEX_WAIT,
+        val,
+        timeout.map_or(ptr::null(), |timeout| timeout as *const timespec),
+    );
+}
+
+/// Wake up to `n` threads blocked in `futex_wait` on `addr`
+pub unsafe fn futex_wake(addr: *mut c_int, n: c_int) {
+    Sys::futex(addr, FUTEX_WAKE, n, ptr::null());
+}
+
+/// Time left until `abstime` on `clock`, or None if it has already passed
+pub fn remaining(clock: clockid_t, abstime: &timespec) -> Option<timespec> {
+    let mut now = timespec::default();
+    if Sys::clock_gettime(clock, &mut now) < 0 {
+        return None;
+    }
+    let mut tv_sec = abstime.tv_sec - now.tv_sec;
+    let mut tv_nsec = abstime.tv_nsec - now.tv_nsec;
+    if tv_nsec < 0 {
+        tv_sec -= 1;
+        tv_nsec += 1_000_000_000;
+    }
+    if tv_sec < 0 || (tv_sec == 0 && tv_nsec == 0) {
+        None
+    } else {
+        Some(timespec { tv_sec, tv_nsec })
+    }
+}
+
+/// Lock the futex word at `lock`, giving up once the absolute CLOCK_REALTIME
+/// `abstime` has passed. Returns false if it timed out.
+pub unsafe fn futex_lock(lock: *mut c_int, abstime: Option<&timespec>) -> bool {
+    if intrinsics::atomic_cxchg_acq(lock, UNLOCKED, LOCKED).1 {
+        return true;
+    }
+    // Mark the lock as contended, so that whoever unlocks it wakes us
+    while intrinsics::atomic_xchg_acq(lock, CONTENDED) != UNLOCKED {
+        match abstime {
+            Some(abstime) => match remaining(CLOCK_REALTIME, abstime) {
+                Some(timeout) => futex_wait(lock, CONTENDED, Some(&timeout)),
+                None => return false,
+            },
+            None => futex_wait(lock, CONTENDED, None),
+        }
+    }
+    true
+}
+
+pub unsafe fn futex_trylock(lock: *mut c_int) -> bool {
+    intrinsics::atomic_cxchg_acq(lock, UNLOCKED, LOCKED).1
+}
+
+pub unsafe fn futex_unlock(lock: *mut c_int) {
+    if intrinsics::atomic_xchg_rel(lock, UNLOCKED) == CONTENDED {
+        futex_wake(lock, 1);
+    }
+}
+
+pub struct Mutex<T> {
+    lock: UnsafeCell<c_int>,
+    content: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Send for Mutex<T> {}
+unsafe impl<T: Send> Sync for Mutex<T> {}
+
+impl<T> Mutex<T> {
+    pub const fn new(content: T) -> Self {
+        Mutex {
+            lock: UnsafeCell::new(UNLOCKED),
+            content: UnsafeCell::new(content),
+        }
+    }
+
+    pub fn lock(&self) -> MutexGuard<T> {
+        unsafe {
+            futex_lock(self.lock.get(), None);
+        }
+        MutexGuard { mutex: self }
+    }
+
+    pub fn try_lock(&self) -> Option<MutexGuard<T>> {
+        if unsafe { futex_trylock(self.lock.get()) } {
+            Some(MutexGuard { mutex: self })
+        } else {
+            None
+        }
+    }
+
+    /// Unlock a mutex whose guard was forgotten, as is done around fork
+    pub unsafe fn force_unlock(&self) {
+        futex_unlock(self.lock.get());
+    }
+}
+
+pub struct MutexGuard<'a, T: 'a> {
+    mutex: &'a Mutex<T>,
+}
+
+impl<'a, T> Deref for MutexGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.content.get() }
+    }
+}
+
+impl<'a, T> DerefMut for MutexGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.content.get() }
+    }
+}
+
+impl<'a, T> Drop for MutexGuard<'a, T> {
+    fn drop(&mut self) {
+        unsafe {
+            futex_unlock(self.mutex.lock.get());
+        }
+    }
+}
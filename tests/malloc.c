@@ -0,0 +1,32 @@
+#include <malloc.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+int main() {
+    char *ptr = malloc(100);
+    printf("usable size >= 100: %d\n", malloc_usable_size(ptr) >= 100);
+    printf("usable size of NULL: %zu\n", malloc_usable_size(NULL));
+
+    struct mallinfo2 before = mallinfo2();
+    char *big = malloc(100000);
+    struct mallinfo2 after = mallinfo2();
+    printf("in use grows by at least 100000: %d\n",
+           after.uordblks - before.uordblks >= 100000);
+    printf("system bytes cover in use bytes: %d\n",
+           after.arena + after.hblkhd >= after.uordblks);
+
+    free(big);
+    struct mallinfo2 freed = mallinfo2();
+    printf("in use shrinks after free: %d\n", freed.uordblks < after.uordblks);
+
+    struct mallinfo small = mallinfo();
+    printf("mallinfo agrees with mallinfo2: %d\n", (size_t)small.uordblks == freed.uordblks);
+
+    printf("mallopt(M_TRIM_THRESHOLD): %d\n", mallopt(M_TRIM_THRESHOLD, 128 * 1024));
+    printf("mallopt(M_GRANULARITY, 3): %d\n", mallopt(M_GRANULARITY, 3));
+    printf("mallopt(unknown): %d\n", mallopt(12345, 0));
+
+    malloc_trim(0);
+    printf("still usable after trim: %d\n", malloc_usable_size(ptr) >= 100);
+    free(ptr);
+}
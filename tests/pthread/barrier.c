@@ -0,0 +1,84 @@
+#include <pthread.h>
+#include <stdio.h>
+
+#define THREADS 4
+#define ROUNDS 3
+
+pthread_barrier_t shared;
+pthread_mutex_t counts_lock = PTHREAD_MUTEX_INITIALIZER;
+int arrived[ROUNDS];
+int early;
+int serial[ROUNDS];
+
+void *round_trip(void *arg) {
+    for (int round = 0; round < ROUNDS; round++) {
+        pthread_mutex_lock(&counts_lock);
+        arrived[round]++;
+        pthread_mutex_unlock(&counts_lock);
+
+        int ret = pthread_barrier_wait(&shared);
+
+        // Everyone has to have arrived before anyone leaves
+        pthread_mutex_lock(&counts_lock);
+        if (arrived[round] != THREADS) {
+            early++;
+        }
+        if (ret == PTHREAD_BARRIER_SERIAL_THREAD) {
+            serial[round]++;
+        } else if (ret != 0) {
+            early++;
+        }
+        pthread_mutex_unlock(&counts_lock);
+    }
+    return NULL;
+}
+
+int main() {
+    pthread_spinlock_t lock;
+    pthread_spin_init(&lock, PTHREAD_PROCESS_PRIVATE);
+    printf("spin_lock: %d\n", pthread_spin_lock(&lock));
+    printf("spin_trylock while locked: %d\n", pthread_spin_trylock(&lock));
+    printf("spin_unlock: %d\n", pthread_spin_unlock(&lock));
+    printf("spin_trylock while unlocked: %d\n", pthread_spin_trylock(&lock));
+    pthread_spin_unlock(&lock);
+    pthread_spin_destroy(&lock);
+
+    pthread_barrier_t barrier;
+    printf("barrier_init with count 0: %d\n", pthread_barrier_init(&barrier, NULL, 0));
+    pthread_barrier_init(&barrier, NULL, 1);
+    for (int i = 0; i < 3; i++) {
+        int ret = pthread_barrier_wait(&barrier);
+        printf("barrier_wait: %s\n", ret == PTHREAD_BARRIER_SERIAL_THREAD ? "serial" : "not serial");
+    }
+    pthread_barrier_destroy(&barrier);
+
+    pthread_barrier_init(&shared, NULL, THREADS);
+    pthread_t threads[THREADS];
+    for (int i = 0; i < THREADS; i++) {
+        pthread_create(&threads[i], NULL, round_trip, NULL);
+    }
+    for (int i = 0; i < THREADS; i++) {
+        pthread_join(threads[i], NULL);
+    }
+    pthread_barrier_destroy(&shared);
+    printf("left the barrier early: %d\n", early);
+    for (int round = 0; round < ROUNDS; round++) {
+        printf("round %d serial threads: %d\n", round, serial[round]);
+    }
+
+    pthread_attr_t attr;
+    pthread_attr_init(&attr);
+    int policy;
+    pthread_attr_getschedpolicy(&attr, &policy);
+    printf("default policy is SCHED_OTHER: %d\n", policy == SCHED_OTHER);
+    printf("setschedpolicy(SCHED_RR): %d\n", pthread_attr_setschedpolicy(&attr, SCHED_RR));
+    pthread_attr_getschedpolicy(&attr, &policy);
+    printf("policy is SCHED_RR: %d\n", policy == SCHED_RR);
+    printf("setschedpolicy(-1) fails: %d\n", pthread_attr_setschedpolicy(&attr, -1) != 0);
+    pthread_attr_destroy(&attr);
+
+    struct sched_param param;
+    printf("getschedparam(self): %d\n", pthread_getschedparam(pthread_self(), &policy, &param));
+    printf("self policy is SCHED_OTHER: %d\n", policy == SCHED_OTHER);
+    printf("self priority: %d\n", param.sched_priority);
+}
@@ -0,0 +1,41 @@
+#include <pthread.h>
+#include <stdio.h>
+
+void finish(long value) {
+    pthread_exit((void *)value);
+}
+
+void *exits(void *arg) {
+    finish((long)arg);
+    return NULL;
+}
+
+void *returns(void *arg) {
+    return arg;
+}
+
+int main() {
+    pthread_t threads[4];
+    for (long i = 0; i < 4; i++) {
+        pthread_create(&threads[i], NULL, i % 2 ? exits : returns, (void *)(i + 1));
+    }
+    for (int i = 0; i < 4; i++) {
+        void *value = NULL;
+        int ret = pthread_join(threads[i], &value);
+        printf("join %d: %d, value: %d\n", i, ret, (int)(long)value);
+    }
+
+    // A detached thread that exits on its own is cleaned up by the next create
+    pthread_attr_t attr;
+    pthread_attr_init(&attr);
+    pthread_attr_setdetachstate(&attr, PTHREAD_CREATE_DETACHED);
+    pthread_t detached;
+    printf("create detached: %d\n", pthread_create(&detached, &attr, exits, NULL));
+    pthread_attr_destroy(&attr);
+
+    pthread_t last;
+    pthread_create(&last, NULL, exits, (void *)5);
+    void *value = NULL;
+    int ret = pthread_join(last, &value);
+    printf("join after detached: %d, value: %d\n", ret, (int)(long)value);
+}
@@ -0,0 +1,44 @@
+#include <errno.h>
+#include <pthread.h>
+#include <stdio.h>
+
+pthread_t main_thread;
+__thread int counter = 1;
+
+void *joins_main(void *arg) {
+    counter += 10;
+    void *value = NULL;
+    int ret = pthread_join(main_thread, &value);
+    printf("join main: %d, value: %d\n", ret, (int)(long)value);
+    printf("counter in thread: %d\n", counter);
+    fflush(stdout);
+    return NULL;
+}
+
+int main() {
+    // The same as glibc, so that objects built against either agree
+    printf("mutex: %zu, mutexattr: %zu, cond: %zu, condattr: %zu\n",
+        sizeof(pthread_mutex_t), sizeof(pthread_mutexattr_t),
+        sizeof(pthread_cond_t), sizeof(pthread_condattr_t));
+
+    main_thread = pthread_self();
+    int ret = pthread_join(main_thread, NULL);
+    printf("join self: %s\n", ret == EDEADLK ? "EDEADLK" : "not EDEADLK");
+
+    pthread_attr_t attr;
+    pthread_attr_init(&attr);
+    pthread_attr_setguardsize(&attr, 3 * 4096);
+    size_t guardsize = 0;
+    pthread_attr_getguardsize(&attr, &guardsize);
+    printf("guardsize: %zu\n", guardsize);
+
+    counter = 2;
+    pthread_t thread;
+    ret = pthread_create(&thread, &attr, joins_main, NULL);
+    pthread_attr_destroy(&attr);
+    printf("create: %d\n", ret);
+    printf("counter in main: %d\n", counter);
+    fflush(stdout);
+
+    pthread_exit((void *)7);
+}
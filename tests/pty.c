@@ -0,0 +1,64 @@
+#define _GNU_SOURCE
+#include <fcntl.h>
+#include <pty.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+int main(int argc, char** argv) {
+    int master = posix_openpt(O_RDWR | O_NOCTTY);
+    if (master < 0) {
+        perror("posix_openpt");
+        return 1;
+    }
+    printf("grantpt: %d\n", grantpt(master));
+    printf("unlockpt: %d\n", unlockpt(master));
+    char *name = ptsname(master);
+    printf("ptsname is set: %d\n", name != NULL && name[0] != 0);
+    close(master);
+
+    int slave;
+    char slave_name[64];
+    if (openpty(&master, &slave, slave_name, NULL, NULL) < 0) {
+        perror("openpty");
+        return 1;
+    }
+    printf("openpty name matches: %d\n", strcmp(slave_name, ptsname(master)) == 0);
+    printf("slave isatty: %d\n", isatty(slave));
+
+    // Whatever goes into the master comes out of the slave
+    write(master, "hello\n", 6);
+    char buf[64];
+    memset(buf, 0, sizeof(buf));
+    read(slave, buf, sizeof(buf) - 1);
+    printf("read from slave: %s", buf);
+    close(slave);
+    close(master);
+
+    fflush(stdout);
+    pid_t pid = forkpty(&master, NULL, NULL, NULL);
+    if (pid < 0) {
+        perror("forkpty");
+        return 1;
+    }
+    if (pid == 0) {
+        // The terminal is all the child has for standard output
+        printf("child stdout isatty: %d\n", isatty(1));
+        fflush(stdout);
+        _exit(0);
+    }
+
+    memset(buf, 0, sizeof(buf));
+    size_t len = 0;
+    ssize_t count;
+    while (len < sizeof(buf) - 1 && (count = read(master, buf + len, sizeof(buf) - 1 - len)) > 0) {
+        len += count;
+    }
+    int status;
+    waitpid(pid, &status, 0);
+    // Output processing turns the newline into \r\n
+    printf("read from master: %s", buf);
+    printf("child status: %d\n", status);
+}
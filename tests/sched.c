@@ -0,0 +1,28 @@
+#define _GNU_SOURCE
+#include <sched.h>
+#include <stdio.h>
+
+int main() {
+    printf("sched_yield: %d\n", sched_yield());
+    printf("SCHED_OTHER priorities: %d..%d\n",
+        sched_get_priority_min(SCHED_OTHER), sched_get_priority_max(SCHED_OTHER));
+    printf("SCHED_FIFO priorities: %d..%d\n",
+        sched_get_priority_min(SCHED_FIFO), sched_get_priority_max(SCHED_FIFO));
+    printf("SCHED_RR priorities: %d..%d\n",
+        sched_get_priority_min(SCHED_RR), sched_get_priority_max(SCHED_RR));
+    printf("sched_getscheduler is SCHED_OTHER: %d\n", sched_getscheduler(0) == SCHED_OTHER);
+
+    cpu_set_t set;
+    CPU_ZERO(&set);
+    printf("sched_getaffinity: %d\n", sched_getaffinity(0, sizeof(set), &set));
+    int count = 0;
+    for (int cpu = 0; cpu < CPU_SETSIZE; cpu++) {
+        if (CPU_ISSET(cpu, &set)) {
+            count++;
+        }
+    }
+    printf("at least one cpu: %d\n", count > 0);
+
+    int cpu = sched_getcpu();
+    printf("sched_getcpu is in the set: %d\n", cpu >= 0 && CPU_ISSET(cpu, &set));
+}
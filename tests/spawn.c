@@ -0,0 +1,109 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <fcntl.h>
+#include <signal.h>
+#include <spawn.h>
+#include <stdio.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+extern char **environ;
+
+// Wait for `pid` if it was spawned and say how it ended, after anything it
+// printed
+void report(const char *name, int ret, pid_t pid) {
+    printf("%s: %d", name, ret);
+    if (ret == 0) {
+        int status;
+        waitpid(pid, &status, 0);
+        if (WIFSIGNALED(status)) {
+            printf(", signal %d", WTERMSIG(status));
+        } else {
+            printf(", exit %d", WEXITSTATUS(status));
+        }
+    }
+    printf("\n");
+    fflush(stdout);
+}
+
+int main() {
+    pid_t pid;
+    int ret;
+
+    char *echo[] = { "sh", "-c", "echo spawned", NULL };
+    ret = posix_spawn(&pid, "/bin/sh", NULL, NULL, echo, environ);
+    report("posix_spawn", ret, pid);
+
+    char *status[] = { "sh", "-c", "exit 3", NULL };
+    ret = posix_spawnp(&pid, "sh", NULL, NULL, status, environ);
+    report("posix_spawnp", ret, pid);
+
+    ret = posix_spawn(&pid, "/nonexistent", NULL, NULL, echo, environ);
+    printf("missing path: %d\n", ret == ENOENT);
+    ret = posix_spawnp(&pid, "spawn-missing", NULL, NULL, echo, environ);
+    printf("missing file: %d\n", ret == ENOENT);
+    fflush(stdout);
+
+    // Standard output to a file
+    posix_spawn_file_actions_t actions;
+    posix_spawn_file_actions_init(&actions);
+    posix_spawn_file_actions_addopen(&actions, 1, "spawn.out", O_WRONLY | O_CREAT | O_TRUNC, 0644);
+    char *to_file[] = { "sh", "-c", "echo written to a file", NULL };
+    ret = posix_spawn(&pid, "/bin/sh", &actions, NULL, to_file, environ);
+    report("addopen", ret, pid);
+    posix_spawn_file_actions_destroy(&actions);
+
+    char line[64] = { 0 };
+    FILE *f = fopen("spawn.out", "r");
+    fgets(line, sizeof(line), f);
+    fclose(f);
+    unlink("spawn.out");
+    printf("file: %s", line);
+    fflush(stdout);
+
+    posix_spawn_file_actions_init(&actions);
+    posix_spawn_file_actions_addchdir_np(&actions, "/");
+    posix_spawn_file_actions_adddup2(&actions, 1, 2);
+    char *pwd[] = { "sh", "-c", "pwd >&2", NULL };
+    ret = posix_spawn(&pid, "/bin/sh", &actions, NULL, pwd, environ);
+    report("addchdir_np and adddup2", ret, pid);
+    posix_spawn_file_actions_destroy(&actions);
+
+    posix_spawn_file_actions_init(&actions);
+    posix_spawn_file_actions_addclose(&actions, 1);
+    char *closed[] = { "sh", "-c", "echo not shown 2>/dev/null", NULL };
+    ret = posix_spawn(&pid, "/bin/sh", &actions, NULL, closed, environ);
+    report("addclose", ret, pid);
+    printf("bad fd: %d\n", posix_spawn_file_actions_addclose(&actions, -1) == EBADF);
+    fflush(stdout);
+    posix_spawn_file_actions_destroy(&actions);
+
+    // An ignored signal stays ignored unless it is reset to the default
+    signal(SIGTERM, SIG_IGN);
+    char *term[] = { "sh", "-c", "kill -TERM $$; echo still running", NULL };
+    ret = posix_spawn(&pid, "/bin/sh", NULL, NULL, term, environ);
+    report("ignored", ret, pid);
+
+    posix_spawnattr_t attr;
+    posix_spawnattr_init(&attr);
+    sigset_t set;
+    sigemptyset(&set);
+    sigaddset(&set, SIGTERM);
+    posix_spawnattr_setsigdefault(&attr, &set);
+    posix_spawnattr_setflags(&attr, POSIX_SPAWN_SETSIGDEF);
+    ret = posix_spawn(&pid, "/bin/sh", NULL, &attr, term, environ);
+    report("setsigdefault", ret, pid);
+
+    posix_spawnattr_setpgroup(&attr, 0);
+    posix_spawnattr_setflags(&attr, POSIX_SPAWN_SETPGROUP);
+    char *quiet[] = { "sh", "-c", "exit 0", NULL };
+    ret = posix_spawn(&pid, "/bin/sh", NULL, &attr, quiet, environ);
+    printf("own group: %d\n", getpgid(pid) == pid);
+    report("setpgroup", ret, pid);
+
+    short flags;
+    posix_spawnattr_getflags(&attr, &flags);
+    printf("getflags: %d\n", flags == POSIX_SPAWN_SETPGROUP);
+    printf("bad flags: %d\n", posix_spawnattr_setflags(&attr, 0x4000) == EINVAL);
+    posix_spawnattr_destroy(&attr);
+}
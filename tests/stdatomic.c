@@ -0,0 +1,82 @@
+#include <pthread.h>
+#include <stdatomic.h>
+#include <stdio.h>
+
+#define THREADS 4
+#define ITERATIONS 10000
+
+struct big {
+    long a, b, c;
+};
+
+_Atomic struct big shared;
+atomic_int shared_counter;
+atomic_int torn;
+
+// Bump every field of the shared struct through the lock table, and a counter
+// with inline atomics
+void *bump(void *arg) {
+    for (int i = 0; i < ITERATIONS; i++) {
+        struct big old = atomic_load(&shared);
+        struct big new;
+        do {
+            if (old.a != old.b || old.b != old.c) {
+                atomic_fetch_add(&torn, 1);
+            }
+            new.a = old.a + 1;
+            new.b = old.b + 1;
+            new.c = old.c + 1;
+        } while (!atomic_compare_exchange_weak(&shared, &old, new));
+        atomic_fetch_add(&shared_counter, 1);
+    }
+    return NULL;
+}
+
+int main() {
+    _Atomic struct big big;
+    struct big value = { 1, 2, 3 };
+    atomic_init(&big, value);
+
+    value = atomic_load(&big);
+    printf("load: %ld %ld %ld\n", value.a, value.b, value.c);
+
+    struct big other = { 4, 5, 6 };
+    atomic_store(&big, other);
+    value = atomic_load(&big);
+    printf("store: %ld %ld %ld\n", value.a, value.b, value.c);
+
+    struct big old = atomic_exchange(&big, ((struct big) { 7, 8, 9 }));
+    printf("exchange returned: %ld %ld %ld\n", old.a, old.b, old.c);
+
+    struct big expected = { 0, 0, 0 };
+    struct big desired = { 10, 11, 12 };
+    int res = atomic_compare_exchange_strong(&big, &expected, desired);
+    printf("compare_exchange with wrong value: %d, expected now %ld %ld %ld\n",
+           res, expected.a, expected.b, expected.c);
+    res = atomic_compare_exchange_strong(&big, &expected, desired);
+    value = atomic_load(&big);
+    printf("compare_exchange with right value: %d, now %ld %ld %ld\n",
+           res, value.a, value.b, value.c);
+
+    atomic_int counter = ATOMIC_VAR_INIT(0);
+    atomic_fetch_add(&counter, 5);
+    atomic_fetch_sub(&counter, 2);
+    printf("counter: %d\n", atomic_load(&counter));
+
+    atomic_flag flag = ATOMIC_FLAG_INIT;
+    printf("first test_and_set: %d\n", atomic_flag_test_and_set(&flag));
+    printf("second test_and_set: %d\n", atomic_flag_test_and_set(&flag));
+    atomic_flag_clear(&flag);
+    printf("test_and_set after clear: %d\n", atomic_flag_test_and_set(&flag));
+
+    pthread_t threads[THREADS];
+    for (int i = 0; i < THREADS; i++) {
+        pthread_create(&threads[i], NULL, bump, NULL);
+    }
+    for (int i = 0; i < THREADS; i++) {
+        pthread_join(threads[i], NULL);
+    }
+    value = atomic_load(&shared);
+    printf("threads: %d %d %d, counter: %d, torn: %d\n",
+           (int)value.a, (int)value.b, (int)value.c, atomic_load(&shared_counter), atomic_load(&torn));
+}
@@ -0,0 +1,22 @@
+#include <errno.h>
+#include <stdio.h>
+#include <sys/wait.h>
+
+int main() {
+    char line[64];
+    FILE *f = popen("echo popen works; exit 2", "r");
+    while (fgets(line, sizeof(line), f)) {
+        printf("read: %s", line);
+    }
+    int status = pclose(f);
+    printf("pclose: %d\n", WEXITSTATUS(status));
+    fflush(stdout);
+
+    f = popen("cat", "w");
+    fputs("written to cat\n", f);
+    status = pclose(f);
+    printf("pclose: %d\n", WEXITSTATUS(status));
+
+    f = popen("true", "x");
+    printf("bad mode: %d, EINVAL: %d\n", f == NULL, errno == EINVAL);
+}
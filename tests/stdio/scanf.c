@@ -37,4 +37,6 @@ int main(int argc, char ** argv) {
     test("%c%3c", "hello", &p, &p.c, &p.string);
     test("test: %2i%n", "test: 0xFF", &p, &p.ia, &p.ib);
     test("hello world%%", "hello world%", &p);
+    test("%4lf%d", "12345", &p, &p.da, &p.ia);
+    test("%f %lf", "inf 0x1p-2", &p, &p.fa, &p.da);
 }
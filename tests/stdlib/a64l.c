@@ -18,5 +18,11 @@ int main(int argc, char* argv[]) {
         return 1;
     }
     printf("Correct a64l: %s = %ld\n", s, l);
+
+    long values[] = { 0, 1, 63, 64, 53222, 194301926, 0x7FFFFFFF };
+    for (int i = 0; i < sizeof(values) / sizeof(long); i++) {
+        s = l64a(values[i]);
+        printf("l64a(%ld) = \"%s\", back to %ld\n", values[i], s, a64l(s));
+    }
     return 0;
 }
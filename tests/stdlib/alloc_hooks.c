@@ -0,0 +1,72 @@
+#include <malloc.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+size_t mallocs = 0;
+size_t malloc_bytes = 0;
+size_t reallocs = 0;
+size_t frees = 0;
+
+void on_malloc(void *ptr, size_t size, void *data) {
+    mallocs++;
+    malloc_bytes += size;
+}
+
+void on_realloc(void *old, void *ptr, size_t size, void *data) {
+    reallocs++;
+}
+
+void on_free(void *ptr, void *data) {
+    frees++;
+}
+
+int main(int argc, char **argv) {
+    if (getenv("RELIBC_LEAK_REPORT")) {
+        char *kept = malloc(100);
+        malloc(1234);
+        free(kept);
+        return 0;
+    }
+
+    struct relibc_alloc_hooks hooks = { on_malloc, on_realloc, on_free, NULL };
+    relibc_set_alloc_hooks(&hooks);
+
+    char *a = malloc(10);
+    char *b = calloc(4, 5);
+    a = realloc(a, 100);
+    free(a);
+    free(b);
+    free(NULL);
+
+    relibc_set_alloc_hooks(NULL);
+    free(malloc(10));
+
+    printf("mallocs: %d, bytes: %d\n", (int)mallocs, (int)malloc_bytes);
+    printf("reallocs: %d\n", (int)reallocs);
+    printf("frees: %d\n", (int)frees);
+
+    int fds[2];
+    pipe(fds);
+    pid_t pid = fork();
+    if (pid == 0) {
+        dup2(fds[1], 2);
+        char *child_argv[] = { argv[0], NULL };
+        char *child_envp[] = { "RELIBC_LEAK_REPORT=1", NULL };
+        execve(argv[0], child_argv, child_envp);
+        exit(1);
+    }
+    close(fds[1]);
+    waitpid(pid, NULL, 0);
+
+    // Only the first line, the ones after it hold addresses
+    char report[256] = { 0 };
+    read(fds[0], report, sizeof(report) - 1);
+    char *newline = strchr(report, '\n');
+    if (newline) {
+        *newline = 0;
+    }
+    printf("%s\n", report);
+}
@@ -0,0 +1,86 @@
+#include <pthread.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#define THREADS 8
+#define ROUNDS 1000
+#define SLOTS 64
+
+void *worker(void *arg) {
+    long id = (long)arg;
+    unsigned char *slots[SLOTS] = { 0 };
+    size_t sizes[SLOTS] = { 0 };
+
+    for (int round = 0; round < ROUNDS; round++) {
+        int slot = (round * 7 + id) % SLOTS;
+        if (slots[slot]) {
+            for (size_t i = 0; i < sizes[slot]; i++) {
+                if (slots[slot][i] != (unsigned char)(id + slot)) {
+                    return (void *)1;
+                }
+            }
+            if (round % 3 == 0) {
+                // Growing and shrinking in place or through the cache
+                size_t size = 1 + (round * 13 + id * 31) % 700;
+                unsigned char *ptr = realloc(slots[slot], size);
+                if (!ptr) {
+                    return (void *)1;
+                }
+                size_t kept = size < sizes[slot] ? size : sizes[slot];
+                for (size_t i = 0; i < kept; i++) {
+                    if (ptr[i] != (unsigned char)(id + slot)) {
+                        return (void *)1;
+                    }
+                }
+                memset(ptr, (unsigned char)(id + slot), size);
+                slots[slot] = ptr;
+                sizes[slot] = size;
+                continue;
+            }
+            free(slots[slot]);
+        }
+        sizes[slot] = 1 + (round * 13 + id * 31) % 700;
+        slots[slot] = malloc(sizes[slot]);
+        if (!slots[slot]) {
+            return (void *)1;
+        }
+        memset(slots[slot], (unsigned char)(id + slot), sizes[slot]);
+    }
+
+    for (int slot = 0; slot < SLOTS; slot++) {
+        free(slots[slot]);
+    }
+    return NULL;
+}
+
+int main() {
+    pthread_t threads[THREADS];
+    for (long i = 0; i < THREADS; i++) {
+        pthread_create(&threads[i], NULL, worker, (void *)i);
+    }
+
+    int failed = 0;
+    for (int i = 0; i < THREADS; i++) {
+        void *res;
+        pthread_join(threads[i], &res);
+        if (res) {
+            failed++;
+        }
+    }
+    printf("threads with corrupted allocations: %d\n", failed);
+    fflush(stdout);
+
+    pid_t pid = fork();
+    if (pid == 0) {
+        // The allocator locks must not be stuck in the child
+        void *ptr = malloc(100);
+        free(ptr);
+        exit(ptr ? 0 : 1);
+    }
+    int status;
+    waitpid(pid, &status, 0);
+    printf("malloc in forked child: %d\n", WIFEXITED(status) && WEXITSTATUS(status) == 0);
+}
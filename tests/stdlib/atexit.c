@@ -0,0 +1,19 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+void first(void) {
+    puts("first handler");
+}
+
+void second(void) {
+    puts("second handler");
+}
+
+int main() {
+    printf("atexit: %d\n", atexit(first));
+    printf("atexit: %d\n", atexit(second));
+    // Returning from main is the same as calling exit, so the handlers run
+    // in reverse order after this
+    puts("returning from main");
+    return 0;
+}
@@ -0,0 +1,47 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+struct cvt_case {
+    double value;
+    int ndigit;
+};
+
+int main() {
+    int decpt, sign;
+
+    struct cvt_case ecvts[] = {
+        { 123.456, 3 }, { -0.001234, 3 }, { 0.0, 3 }, { -0.0, 2 },
+        { 0.05, 1 }, { 9.996, 3 }, { 2.5, 20 }, { 1.0 / 0.0, 3 }
+    };
+    for (int i = 0; i < sizeof(ecvts) / sizeof(struct cvt_case); i++) {
+        char *digits = ecvt(ecvts[i].value, ecvts[i].ndigit, &decpt, &sign);
+        printf("ecvt(%d): \"%s\", decpt %d, sign %d\n", i, digits, decpt, sign);
+    }
+
+    struct cvt_case fcvts[] = {
+        { 123.456, 1 }, { -0.001234, 3 }, { 0.0001, 3 }, { 0.05, 1 },
+        { 0.0, 3 }, { 2.5, 3 }, { 12345.0, -2 }, { 99.6, -1 }
+    };
+    for (int i = 0; i < sizeof(fcvts) / sizeof(struct cvt_case); i++) {
+        char *digits = fcvt(fcvts[i].value, fcvts[i].ndigit, &decpt, &sign);
+        printf("fcvt(%d): \"%s\", decpt %d, sign %d\n", i, digits, decpt, sign);
+    }
+
+    struct cvt_case gcvts[] = {
+        { 123.456, 5 }, { 0.0001234, 3 }, { 0.00001234, 3 }, { 1e20, 5 },
+        { -1.5, 17 }, { 123456.0, 6 }, { 1234567.0, 6 }, { 100.0, 3 },
+        { 0.0, 3 }, { -0.0, 3 }, { 9.996, 3 }, { 0.1, 17 }
+    };
+    for (int i = 0; i < sizeof(gcvts) / sizeof(struct cvt_case); i++) {
+        char buf[64];
+        printf("gcvt(%d): \"%s\"\n", i, gcvt(gcvts[i].value, gcvts[i].ndigit, buf));
+    }
+
+    char buf[8];
+    int status = ecvt_r(3.14159, 3, &decpt, &sign, buf, sizeof(buf));
+    printf("ecvt_r: %d \"%s\", decpt %d, sign %d\n", status, buf, decpt, sign);
+    status = fcvt_r(-3.14159, 3, &decpt, &sign, buf, sizeof(buf));
+    printf("fcvt_r: %d \"%s\", decpt %d, sign %d\n", status, buf, decpt, sign);
+    status = fcvt_r(123456.0, 3, &decpt, &sign, buf, sizeof(buf));
+    printf("fcvt_r too long: %d\n", status);
+}
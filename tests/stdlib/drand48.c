@@ -0,0 +1,32 @@
+#include <stdlib.h>
+#include <stdio.h>
+
+int main(int argc, char** argv) {
+    // Print doubles as integers, as the values have to match exactly
+    printf("%d\n", (int)(drand48() * 1000000000));
+
+    srand48(1234);
+    for (int i = 0; i < 3; i++) {
+        int d = drand48() * 1000000000;
+        int l = lrand48();
+        int m = mrand48();
+        printf("%d %d %d\n", d, l, m);
+    }
+
+    unsigned short xsubi[3] = {1, 2, 3};
+    int e = erand48(xsubi) * 1000000000;
+    int n = nrand48(xsubi);
+    int j = jrand48(xsubi);
+    printf("%d %d %d\n", e, n, j);
+    printf("%d %d %d\n", xsubi[0], xsubi[1], xsubi[2]);
+
+    unsigned short seed[3] = {0x1234, 0x5678, 0x9abc};
+    unsigned short *old = seed48(seed);
+    printf("%d %d %d\n", old[0], old[1], old[2]);
+    printf("%d\n", (int)lrand48());
+
+    unsigned short param[7] = {1, 2, 3, 5, 0, 0, 7};
+    lcong48(param);
+    printf("%d", (int)mrand48());
+    printf(" %d\n", (int)mrand48());
+}
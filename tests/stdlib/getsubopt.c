@@ -0,0 +1,25 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+int main() {
+    char *const tokens[] = { "ro", "rw", "size", "mode", NULL };
+    char options[] = "ro,size=1024,bogus,mode=,sizes=1,rw,,size";
+    char *optionp = options;
+    char *value;
+
+    while (*optionp != '\0') {
+        int i = getsubopt(&optionp, tokens, &value);
+        if (i == -1) {
+            printf("unknown: \"%s\"\n", value);
+        } else if (value == NULL) {
+            printf("%s: no value\n", tokens[i]);
+        } else {
+            printf("%s: \"%s\"\n", tokens[i], value);
+        }
+    }
+
+    char empty[] = "";
+    optionp = empty;
+    printf("empty: %d\n", getsubopt(&optionp, tokens, &value));
+}
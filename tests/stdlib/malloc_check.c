@@ -0,0 +1,112 @@
+#include <pthread.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+pthread_barrier_t barrier;
+
+void *free_at_once(void *ptr) {
+    pthread_barrier_wait(&barrier);
+    free(ptr);
+    return NULL;
+}
+
+void run(const char *mode) {
+    char *ptr = malloc(32);
+
+    if (strcmp(mode, "valid") == 0) {
+        memset(ptr, 1, 32);
+        ptr = realloc(ptr, 64);
+        memset(ptr, 2, 64);
+        free(ptr);
+    } else if (strcmp(mode, "double_free") == 0) {
+        free(ptr);
+        free(ptr);
+    } else if (strcmp(mode, "overflow") == 0) {
+        ptr[32] = 0;
+        free(ptr);
+    } else if (strcmp(mode, "underflow") == 0) {
+        ptr[-1] = 0;
+        free(ptr);
+    } else if (strcmp(mode, "bad_pointer") == 0) {
+        free(ptr + 8);
+    } else if (strcmp(mode, "stack_pointer") == 0) {
+        // Nothing in front of these may be read
+        char local[64];
+        free(local + 48);
+    } else if (strcmp(mode, "low_pointer") == 0) {
+        free((void *)32);
+    } else if (strcmp(mode, "use_after_free") == 0) {
+        free(ptr);
+        ptr[0] = 0;
+        // Push it out of quarantine
+        for (int i = 0; i < 1000; i++) {
+            free(malloc(16));
+        }
+    } else if (strcmp(mode, "threads_double_free") == 0) {
+        pthread_barrier_init(&barrier, NULL, 2);
+        pthread_t thread;
+        pthread_create(&thread, NULL, free_at_once, ptr);
+        free_at_once(ptr);
+        pthread_join(thread, NULL);
+    }
+}
+
+void test(char *self, char *mode) {
+    int fds[2];
+    pipe(fds);
+    pid_t pid = fork();
+    if (pid == 0) {
+        close(fds[0]);
+        dup2(fds[1], 2);
+
+        char *argv[] = { self, mode, NULL };
+        char *envp[] = { "RELIBC_MALLOC_CHECK=1", NULL };
+        execve(self, argv, envp);
+        exit(2);
+    }
+
+    close(fds[1]);
+
+    char diagnostic[256];
+    size_t len = 0;
+    ssize_t count;
+    while (len < sizeof(diagnostic) - 1 &&
+           (count = read(fds[0], diagnostic + len, sizeof(diagnostic) - 1 - len)) > 0) {
+        len += count;
+    }
+    diagnostic[len] = 0;
+    close(fds[0]);
+
+    // The address at the end changes from run to run
+    char *at = strstr(diagnostic, " at ");
+    if (at) {
+        *at = 0;
+    }
+
+    int status;
+    waitpid(pid, &status, 0);
+    printf("%s: %s\n", mode, WIFSIGNALED(status) ? "aborted" : "not aborted");
+    if (len > 0) {
+        printf("    %s\n", diagnostic);
+    }
+}
+
+int main(int argc, char **argv) {
+    if (getenv("RELIBC_MALLOC_CHECK")) {
+        run(argv[1]);
+        return 0;
+    }
+
+    test(argv[0], "valid");
+    test(argv[0], "double_free");
+    test(argv[0], "overflow");
+    test(argv[0], "underflow");
+    test(argv[0], "bad_pointer");
+    test(argv[0], "stack_pointer");
+    test(argv[0], "low_pointer");
+    test(argv[0], "use_after_free");
+    test(argv[0], "threads_double_free");
+}
@@ -0,0 +1,39 @@
+#include <stdlib.h>
+#include <stdio.h>
+
+int main(int argc, char** argv) {
+    // The default state is seeded with 1
+    printf("%d\n", (int)random());
+    srandom(1);
+    printf("%d\n", (int)random());
+    srandom(42);
+    printf("%d", (int)random());
+    printf(" %d\n", (int)random());
+    // Seeds with the top bit set are negative to glibc
+    srandom(0x80000000);
+    printf("%d", (int)random());
+    printf(" %d\n", (int)random());
+    srandom(0xdeadbeef);
+    printf("%d", (int)random());
+    printf(" %d\n", (int)random());
+
+    static char small[8], large[256];
+    char *old = initstate(7, small, sizeof(small));
+    printf("%d", (int)random());
+    printf(" %d\n", (int)random());
+    initstate(7, large, sizeof(large));
+    printf("%d", (int)random());
+    printf(" %d\n", (int)random());
+
+    // Pick up the default state where it was left
+    setstate(old);
+    printf("%d\n", (int)random());
+    setstate(large);
+    printf("%d\n", (int)random());
+
+    printf("%d\n", initstate(1, small, 4) == NULL);
+
+    unsigned int seed = 1;
+    printf("%d", rand_r(&seed));
+    printf(" %d\n", rand_r(&seed));
+}
@@ -0,0 +1,70 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <limits.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+static char cwd[PATH_MAX];
+
+static void show(const char *path) {
+    char buf[PATH_MAX];
+    errno = 0;
+    char *resolved = realpath(path, buf);
+    if (resolved == NULL) {
+        printf("%s: errno %d\n", path, errno);
+        return;
+    }
+
+    // Keep the output the same wherever the tests are run from
+    size_t len = strlen(cwd);
+    if (strncmp(resolved, cwd, len) == 0) {
+        printf("%s: .%s\n", path, resolved + len);
+    } else {
+        printf("%s: %s\n", path, resolved);
+    }
+}
+
+int main(int argc, char** argv) {
+    if (getcwd(cwd, sizeof(cwd)) == NULL) {
+        perror("getcwd");
+        return 1;
+    }
+
+    show(".");
+    show("stdlib/../stdlib//realpath.c");
+    show("symlink_dir/file");
+    show("symlink_dir/link");
+    show("symlink_dir/link_to_link");
+    show("symlink_dir/self/self/self/link");
+    show("symlink_dir/up_and_back");
+    show("symlink_dir/self/..");
+    show("/");
+    show("/..");
+    show("symlink_dir/loop1");
+    show("symlink_dir/missing");
+    show("symlink_dir/file/");
+    show("symlink_dir/file/.");
+    show("symlink_dir/file/x");
+    show("");
+
+    char *allocated = realpath("symlink_dir/file", NULL);
+    char *canonical = canonicalize_file_name("symlink_dir/link");
+    printf("canonicalize_file_name matches: %d\n",
+        allocated != NULL && canonical != NULL && strcmp(allocated, canonical) == 0);
+    free(allocated);
+    free(canonical);
+
+    char target[PATH_MAX];
+    // readlink does not nul terminate what it writes
+    memset(target, 0, sizeof(target));
+    ssize_t len = readlink("symlink_dir/up_and_back", target, sizeof(target) - 1);
+    printf("readlink: %d %s\n", (int)len, target);
+    memset(target, 0, sizeof(target));
+    len = readlink("symlink_dir/up_and_back", target, 4);
+    printf("readlink truncated: %d %s\n", (int)len, target);
+    errno = 0;
+    len = readlink("symlink_dir/file", target, sizeof(target));
+    printf("readlink of a file: %d, EINVAL: %d\n", (int)len, errno == EINVAL);
+}
@@ -1,5 +1,7 @@
+#include <errno.h>
 #include <stdlib.h>
 #include <stdio.h>
+#include <string.h>
 
 int main() {
     char* endptr = 0;
@@ -14,4 +16,48 @@ int main() {
         d = strtod(inputs[i], &endptr);
         printf("d: %f Endptr: \"%s\"\n", d, endptr);
     }
+
+    char* exact[] = {
+        "0.1", "1e23", "9007199254740993", "2.2250738585072011e-308",
+        "4.9406564584124654e-324", "1.7976931348623157e308",
+        "1.00000000000000011102230246251565404236316680908203125",
+        "1.000000000000000111022302462515654042363166809082031250000000001",
+        "0x1.fffffffffffff8p0", "0X1P-3 rest", "  +.5e-1z", "-0",
+        "1e", "1e+x", "0x", "0xp1", ".e1", "-", "inf", "-Infinity!", "INFINIT"
+    };
+    for (int i = 0; i < sizeof(exact) / sizeof(char*); i += 1) {
+        errno = 0;
+        d = strtod(exact[i], &endptr);
+        unsigned long long bits;
+        memcpy(&bits, &d, sizeof(bits));
+        printf("%s: %x %x, %d characters", exact[i], (unsigned) (bits >> 32), (unsigned) bits, (int) (endptr - exact[i]));
+        printf(", errno %d\n", errno);
+    }
+
+    char* nans[] = { "nan", "-NAN", "nan()", "nan(0x1_a)b", "nan(1", "nan(-1)" };
+    for (int i = 0; i < sizeof(nans) / sizeof(char*); i += 1) {
+        d = strtod(nans[i], &endptr);
+        printf("%s: nan %d, %d characters\n", nans[i], d != d, (int) (endptr - nans[i]));
+    }
+
+    char* ranges[] = { "1e400", "-1e400", "1e-400", "0x1p1024", "1e-5000000000" };
+    for (int i = 0; i < sizeof(ranges) / sizeof(char*); i += 1) {
+        errno = 0;
+        d = strtod(ranges[i], &endptr);
+        printf("%s: %d %d, %d characters", ranges[i], d > 1, d < -1, (int) (endptr - ranges[i]));
+        printf(", errno %d\n", errno);
+    }
+
+    char* floats[] = { "0.1", "16777217", "3.4028235e38", "3.4028236e38", "1e-50", "0x1.000001p0" };
+    for (int i = 0; i < sizeof(floats) / sizeof(char*); i += 1) {
+        errno = 0;
+        float f = strtof(floats[i], &endptr);
+        unsigned bits;
+        memcpy(&bits, &f, sizeof(bits));
+        printf("%s: %x, %d characters", floats[i], bits, (int) (endptr - floats[i]));
+        printf(", errno %d\n", errno);
+    }
+
+    long double ld = strtold("2.5", &endptr);
+    printf("strtold: %d\n", (int) (ld * 2));
 }
@@ -1,6 +1,30 @@
 #include <errno.h>
+#include <inttypes.h>
+#include <limits.h>
 #include <stdlib.h>
 #include <stdio.h>
+#include <wchar.h>
+
+struct strtol_case {
+    char *s;
+    int base;
+};
+
+// Values too big for printf("%d") are printed by name
+void print_result(const char *func, const char *s, int base, long long value, int chars) {
+    printf("%s(\"%s\", %d): ", func, s, base);
+    if (value == LLONG_MAX) {
+        printf("LLONG_MAX");
+    } else if (value == LLONG_MIN) {
+        printf("LLONG_MIN");
+    } else if ((unsigned long long) value == ULLONG_MAX - 1) {
+        printf("ULLONG_MAX - 1");
+    } else {
+        printf("%d", (int) value);
+    }
+    printf(", %d characters", chars);
+    printf(", errno %d\n", errno);
+}
 
 int main(int argc, char* argv[]) {
     printf("%ld\n", strtol("         -42", NULL, 0));
@@ -26,5 +50,56 @@ int main(int argc, char* argv[]) {
         printf("errno is not 0 (%d), something went wrong\n", errno);
     }
 
+    struct strtol_case signed_cases[] = {
+        { "9223372036854775807", 10 }, { "9223372036854775808", 10 },
+        { "-9223372036854775808", 10 }, { "-9223372036854775809", 10 },
+        { "99999999999999999999999abc", 0 }, { "0x", 16 }, { "0x", 0 },
+        { "0xg", 0 }, { "  -0x1A", 0 }, { "Zz", 36 }, { "101012", 2 },
+        { "755", 8 }, { "09", 0 }, { "  +", 10 }, { "12", 1 }, { "12", 37 }
+    };
+    for (int i = 0; i < sizeof(signed_cases) / sizeof(struct strtol_case); i++) {
+        char *s = signed_cases[i].s;
+        char *end = s;
+        errno = 0;
+        long l = strtol(s, &end, signed_cases[i].base);
+        print_result("strtol", s, signed_cases[i].base, l, end - s);
+        end = s;
+        errno = 0;
+        long long ll = strtoll(s, &end, signed_cases[i].base);
+        print_result("strtoll", s, signed_cases[i].base, ll, end - s);
+        end = s;
+        errno = 0;
+        intmax_t im = strtoimax(s, &end, signed_cases[i].base);
+        print_result("strtoimax", s, signed_cases[i].base, im, end - s);
+    }
+
+    struct strtol_case unsigned_cases[] = {
+        { "-1", 10 }, { "-2", 0 }, { "18446744073709551615", 10 },
+        { "18446744073709551616", 10 }, { "-18446744073709551615", 10 },
+        { "-18446744073709551616", 10 }, { "0xFFFFFFFFFFFFFFFE", 0 }
+    };
+    for (int i = 0; i < sizeof(unsigned_cases) / sizeof(struct strtol_case); i++) {
+        char *s = unsigned_cases[i].s;
+        char *end;
+        errno = 0;
+        unsigned long ul = strtoul(s, &end, unsigned_cases[i].base);
+        print_result("strtoul", s, unsigned_cases[i].base, ul, end - s);
+        errno = 0;
+        unsigned long long ull = strtoull(s, &end, unsigned_cases[i].base);
+        print_result("strtoull", s, unsigned_cases[i].base, ull, end - s);
+        errno = 0;
+        uintmax_t um = strtoumax(s, &end, unsigned_cases[i].base);
+        print_result("strtoumax", s, unsigned_cases[i].base, um, end - s);
+    }
+
+    wchar_t *wide = L"  -123xyz";
+    wchar_t *wide_end;
+    errno = 0;
+    intmax_t im = wcstoimax(wide, &wide_end, 10);
+    print_result("wcstoimax", "  -123xyz", 10, im, wide_end - wide);
+    wide = L"0x10";
+    uintmax_t um = wcstoumax(wide, &wide_end, 0);
+    print_result("wcstoumax", "0x10", 0, um, wide_end - wide);
+
     return 0;
 }
@@ -1,6 +1,11 @@
 #include <stdlib.h>
+#include <stdio.h>
+#include <sys/wait.h>
 
 int main(int argc, char ** argv) {
     system("echo test of system");
+    int status = system("exit 3");
+    printf("exit status: %d\n", WEXITSTATUS(status));
+    printf("shell available: %d\n", system(NULL) != 0);
     return 0;
 }
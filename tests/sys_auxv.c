@@ -0,0 +1,33 @@
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/auxv.h>
+#include <unistd.h>
+
+int main(int argc, char **argv) {
+    unsigned long pagesz = getauxval(AT_PAGESZ);
+    printf("page size is a power of two: %d\n", pagesz >= 4096 && (pagesz & (pagesz - 1)) == 0);
+    printf("getpagesize agrees: %d\n", getpagesize() == (int) pagesz);
+    printf("sysconf agrees: %d\n", sysconf(_SC_PAGESIZE) == (long) pagesz);
+    printf("_SC_PAGE_SIZE agrees: %d\n", sysconf(_SC_PAGE_SIZE) == (long) pagesz);
+
+    unsigned char *random = (unsigned char *) getauxval(AT_RANDOM);
+    int nonzero = 0;
+    for (int i = 0; random && i < 16; i++) {
+        nonzero |= random[i];
+    }
+    printf("random bytes: %d\n", nonzero != 0);
+
+    printf("secure: %lu\n", getauxval(AT_SECURE));
+    printf("phdr: %d\n", getauxval(AT_PHDR) != 0);
+    const char *execfn = (const char *) getauxval(AT_EXECFN);
+    printf("execfn is argv[0]: %d\n", execfn && strcmp(execfn, argv[0]) == 0);
+
+    errno = 0;
+    getauxval(AT_HWCAP);
+    printf("hwcap found: %d\n", errno == 0);
+
+    errno = 0;
+    unsigned long missing = getauxval(12345);
+    printf("missing: %lu, ENOENT: %d\n", missing, errno == ENOENT);
+}
@@ -0,0 +1,51 @@
+#include <errno.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/random.h>
+#include <unistd.h>
+
+static int all_zero(unsigned char *buf, size_t len) {
+    for (size_t i = 0; i < len; i++) {
+        if (buf[i] != 0) {
+            return 0;
+        }
+    }
+    return 1;
+}
+
+int main(int argc, char** argv) {
+    unsigned char buf[64];
+
+    memset(buf, 0, sizeof(buf));
+    printf("getrandom: %d\n", (int)getrandom(buf, sizeof(buf), 0));
+    printf("getrandom filled: %d\n", !all_zero(buf, sizeof(buf)));
+
+    memset(buf, 0, sizeof(buf));
+    printf("getentropy: %d\n", getentropy(buf, sizeof(buf)));
+    printf("getentropy filled: %d\n", !all_zero(buf, sizeof(buf)));
+
+    static unsigned char big[257];
+    int ret = getentropy(big, sizeof(big));
+    printf("getentropy(257): %d, EIO: %d\n", ret, errno == EIO);
+
+    memset(buf, 0, sizeof(buf));
+    arc4random_buf(buf, sizeof(buf));
+    printf("arc4random_buf filled: %d\n", !all_zero(buf, sizeof(buf)));
+
+    int differ = 0;
+    uint32_t first = arc4random();
+    for (int i = 0; i < 8; i++) {
+        differ |= arc4random() != first;
+    }
+    printf("arc4random differs: %d\n", differ);
+
+    int in_range = 1;
+    for (int i = 0; i < 1000; i++) {
+        in_range &= arc4random_uniform(10) < 10;
+    }
+    printf("arc4random_uniform in range: %d\n", in_range);
+    printf("arc4random_uniform(0): %d\n", (int)arc4random_uniform(0));
+    printf("arc4random_uniform(1): %d\n", (int)arc4random_uniform(1));
+}
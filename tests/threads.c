@@ -0,0 +1,109 @@
+#include <stdio.h>
+#include <threads.h>
+
+#define THREADS 4
+#define ITERATIONS 10000
+
+mtx_t mutex;
+cnd_t cond;
+int counter = 0;
+int finished = 0;
+
+once_flag once = ONCE_FLAG_INIT;
+int once_calls = 0;
+
+tss_t key;
+int destructor_calls = 0;
+
+void init_once(void) {
+    once_calls++;
+}
+
+void destructor(void *value) {
+    mtx_lock(&mutex);
+    destructor_calls++;
+    mtx_unlock(&mutex);
+}
+
+int worker(void *arg) {
+    call_once(&once, init_once);
+    tss_set(key, arg);
+
+    for (int i = 0; i < ITERATIONS; i++) {
+        mtx_lock(&mutex);
+        counter++;
+        mtx_unlock(&mutex);
+    }
+
+    mtx_lock(&mutex);
+    finished++;
+    cnd_signal(&cond);
+    mtx_unlock(&mutex);
+
+    return (int)(long)tss_get(key);
+}
+
+void finish(int res) {
+    thrd_exit(res);
+}
+
+int exits_early(void *arg) {
+    finish(42);
+    return 0;
+}
+
+int main() {
+    printf("mtx_init: %d\n", mtx_init(&mutex, mtx_plain) == thrd_success);
+    printf("cnd_init: %d\n", cnd_init(&cond) == thrd_success);
+    printf("tss_create: %d\n", tss_create(&key, destructor) == thrd_success);
+
+    thrd_t threads[THREADS];
+    for (int i = 0; i < THREADS; i++) {
+        if (thrd_create(&threads[i], worker, (void *)(long)(i + 1)) != thrd_success) {
+            puts("thrd_create failed");
+            return 1;
+        }
+    }
+
+    mtx_lock(&mutex);
+    while (finished < THREADS) {
+        cnd_wait(&cond, &mutex);
+    }
+    mtx_unlock(&mutex);
+
+    int sum = 0;
+    for (int i = 0; i < THREADS; i++) {
+        int res;
+        thrd_join(threads[i], &res);
+        sum += res;
+    }
+
+    thrd_t early;
+    thrd_create(&early, exits_early, NULL);
+    int early_res = 0;
+    printf("thrd_join after thrd_exit: %d\n", thrd_join(early, &early_res) == thrd_success);
+    printf("thrd_exit result: %d\n", early_res);
+
+    printf("counter: %d\n", counter);
+    printf("sum of results: %d\n", sum);
+    printf("call_once calls: %d\n", once_calls);
+    printf("destructor calls: %d\n", destructor_calls);
+    printf("main tss value is NULL: %d\n", tss_get(key) == NULL);
+    printf("thrd_equal(self, self): %d\n", thrd_equal(thrd_current(), thrd_current()) != 0);
+
+    mtx_t recursive;
+    mtx_init(&recursive, mtx_plain | mtx_recursive);
+    mtx_lock(&recursive);
+    printf("recursive trylock: %d\n", mtx_trylock(&recursive) == thrd_success);
+    mtx_unlock(&recursive);
+    mtx_unlock(&recursive);
+    mtx_destroy(&recursive);
+
+    mtx_lock(&mutex);
+    printf("plain trylock while locked: %d\n", mtx_trylock(&mutex) == thrd_busy);
+    mtx_unlock(&mutex);
+
+    tss_delete(key);
+    cnd_destroy(&cond);
+    mtx_destroy(&mutex);
+}
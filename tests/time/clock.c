@@ -0,0 +1,78 @@
+#include <errno.h>
+#include <pthread.h>
+#include <stdio.h>
+#include <time.h>
+
+volatile int spinning = 1;
+
+void *spin(void *arg) {
+    while (spinning) {}
+    return NULL;
+}
+
+int after(struct timespec *a, struct timespec *b) {
+    return a->tv_sec > b->tv_sec || (a->tv_sec == b->tv_sec && a->tv_nsec >= b->tv_nsec);
+}
+
+void add_ms(struct timespec *ts, long ms) {
+    ts->tv_nsec += ms * 1000000;
+    ts->tv_sec += ts->tv_nsec / 1000000000;
+    ts->tv_nsec %= 1000000000;
+}
+
+// Sleep until 10ms from now on `clock` and check that the clock got there
+void sleep_abs(const char *name, clockid_t clock) {
+    struct timespec end, now;
+    clock_gettime(clock, &end);
+    add_ms(&end, 10);
+    int ret = clock_nanosleep(clock, TIMER_ABSTIME, &end, NULL);
+    clock_gettime(clock, &now);
+    printf("%s absolute: %d, reached: %d\n", name, ret, after(&now, &end));
+}
+
+int main() {
+    struct timespec res;
+    clockid_t clocks[] = {
+        CLOCK_REALTIME,
+        CLOCK_MONOTONIC,
+        CLOCK_BOOTTIME,
+        CLOCK_PROCESS_CPUTIME_ID,
+        CLOCK_THREAD_CPUTIME_ID,
+    };
+    for (int i = 0; i < 5; i++) {
+        res.tv_sec = res.tv_nsec = -1;
+        int ret = clock_getres(clocks[i], &res);
+        printf("getres %d: %d, sane: %d\n", i, ret, res.tv_sec == 0 && res.tv_nsec > 0);
+    }
+    int ret = clock_getres(12345, &res);
+    printf("getres bad clock: %d, EINVAL: %d\n", ret, errno == EINVAL);
+    struct timespec zero = { 0, 0 };
+    ret = clock_settime(CLOCK_MONOTONIC, &zero);
+    printf("settime monotonic: %d, EINVAL: %d\n", ret, errno == EINVAL);
+
+    struct timespec ms = { 0, 1000000 };
+    printf("monotonic relative: %d\n", clock_nanosleep(CLOCK_MONOTONIC, 0, &ms, NULL));
+    sleep_abs("monotonic", CLOCK_MONOTONIC);
+    sleep_abs("boottime", CLOCK_BOOTTIME);
+    sleep_abs("realtime", CLOCK_REALTIME);
+    printf("past: %d\n", clock_nanosleep(CLOCK_MONOTONIC, TIMER_ABSTIME, &zero, NULL));
+    struct timespec bad = { 0, 1000000000 };
+    printf("bad nsec: %d\n", clock_nanosleep(CLOCK_MONOTONIC, 0, &bad, NULL) == EINVAL);
+    printf("own thread clock: %d\n",
+        clock_nanosleep(CLOCK_THREAD_CPUTIME_ID, TIMER_ABSTIME, &ms, NULL) == EINVAL);
+
+    // CPU time only passes while something runs
+    pthread_t thread;
+    pthread_create(&thread, NULL, spin, NULL);
+    sleep_abs("process cputime", CLOCK_PROCESS_CPUTIME_ID);
+
+    clockid_t clock;
+    printf("pthread_getcpuclockid: %d\n", pthread_getcpuclockid(thread, &clock));
+    sleep_abs("thread cputime", clock);
+    spinning = 0;
+    pthread_join(thread, NULL);
+
+    printf("clock_getcpuclockid self: %d\n", clock_getcpuclockid(0, &clock));
+    printf("gettime: %d\n", clock_gettime(clock, &res));
+    printf("clock_getcpuclockid missing: %d\n", clock_getcpuclockid(0x7ffffff0, &clock) == ESRCH);
+}
@@ -0,0 +1,35 @@
+#define _GNU_SOURCE
+#include <stdio.h>
+#include <stdlib.h>
+#include <time.h>
+
+void test(const char *input, const char *format) {
+    struct tm *t = getdate(input);
+    if (t == NULL) {
+        printf("\"%s\": getdate_err %d\n", input, getdate_err);
+        return;
+    }
+    char buf[64];
+    strftime(buf, sizeof(buf), format, t);
+    printf("\"%s\": %s\n", input, buf);
+}
+
+int main() {
+    unsetenv("DATEMSK");
+    test("2018-07-17 06:25:42", "%F %T");
+
+    setenv("DATEMSK", "time/missing", 1);
+    test("2018-07-17 06:25:42", "%F %T");
+
+    setenv("DATEMSK", "time", 1);
+    test("2018-07-17 06:25:42", "%F %T");
+
+    setenv("DATEMSK", "time/datemsk", 1);
+    test("2018-07-17 06:25:42", "%F %T %a");
+    test("  2018-07-17 06:25:42  ", "%F %T");
+    test("2018-07-17 25:25:42", "%F %T");
+    test("17/07/2018", "%F %a");
+    test("31/02/2018", "%F");
+    test("Tuesday July 17 2018 06:25", "%F %T %a");
+    test("2018-07-17", "%F");
+}
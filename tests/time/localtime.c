@@ -1,4 +1,5 @@
 #include <stdio.h>
+#include <stdlib.h>
 #include <time.h>
 
 int main() {
@@ -15,4 +16,16 @@ int main() {
 
     time_t input = 1531461823;
     fputs(ctime(&input), stdout); // Omit newline
+
+    // The offset of a zone with daylight saving time comes from its rules
+    setenv("TZ", "EST5EDT,M3.2.0,M11.1.0", 1);
+    tzset();
+    time_t dates[] = { 1609459200, 1625097600 };
+    for (int i = 0; i < 2; i += 1) {
+        struct tm* t = localtime(&dates[i]);
+        printf(
+            "EST5EDT: Year %d, Month %d, Day of month: %d, %d:%d:%d, isdst %d\n",
+            t->tm_year, t->tm_mon, t->tm_mday, t->tm_hour, t->tm_min, t->tm_sec, t->tm_isdst
+        );
+    }
 }
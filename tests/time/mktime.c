@@ -34,6 +34,23 @@ int main() {
         }
     }
 
+    // The same round trips in a zone with daylight saving time
+    setenv("TZ", "EST5EDT,M3.2.0,M11.1.0", 1);
+    tzset();
+    for (int i = 0; i < 5; i += 1) {
+        if (check(inputs[i])) {
+            return -1;
+        }
+    }
+    struct tm summer = {};
+    summer.tm_year = 121;
+    summer.tm_mon = 6;
+    summer.tm_mday = 1;
+    summer.tm_hour = 12;
+    summer.tm_isdst = -1;
+    time_t noon = mktime(&summer);
+    printf("%ld, isdst %d\n", noon, summer.tm_isdst);
+
     srand(time(NULL));
 
     for (int i = 0; i < 10; i += 1) {
@@ -0,0 +1,55 @@
+#define _GNU_SOURCE
+#include <stdio.h>
+#include <string.h>
+#include <time.h>
+
+void test(const char *input, const char *format) {
+    struct tm t;
+    memset(&t, 0, sizeof(t));
+    char *end = strptime(input, format, &t);
+    if (end == NULL) {
+        printf("\"%s\" with \"%s\": no match\n", input, format);
+        return;
+    }
+    printf(
+        "\"%s\" with \"%s\": %d-%02d-%02d %02d:%02d:%02d wday %d yday %d gmtoff %ld, rest \"%s\"\n",
+        input, format,
+        t.tm_year + 1900, t.tm_mon + 1, t.tm_mday, t.tm_hour, t.tm_min, t.tm_sec,
+        t.tm_wday, t.tm_yday, t.tm_gmtoff, end
+    );
+}
+
+int main() {
+    test("2018-07-17 06:25:42", "%Y-%m-%d %H:%M:%S");
+    test("2018-07-17T06:25:42Z", "%FT%T%z");
+    test("17/Jul/2018:06:25:42 +0200", "%d/%b/%Y:%H:%M:%S %z");
+    test("Tue, 17 Jul 2018 06:25:42 -05:30", "%a, %d %b %Y %T %z");
+    test("tuesday JULY 17 2018", "%A %B %e %Y");
+    test("Tue Jul 17 06:25:42 2018", "%c");
+    test("07/17/18", "%D");
+    test("07/17/68 and more", "%x and");
+    test("07/17/69", "%D");
+    test("  7/ 4/2000", "%m/%d/%Y");
+    test("06:25:42 PM", "%r");
+    test("12:00 am", "%I:%M %p");
+    test("12:00 PM", "%I:%M %p");
+    test("18:05", "%R");
+    test("2018 198", "%Y %j");
+    test("2016 366", "%Y %j");
+    test("20 18-07-17", "%C %y-%m-%d");
+    test("19 07 04", "%C %m %d");
+    test("1531808742", "%s");
+    test("2018 28 2", "%Y %U %w");
+    test("2018 28 1", "%Y %W %u");
+    test("2018 1 0", "%Y %W %w");
+    test("2018 198%", "%Y %j%%");
+    test("2018\t\n 07 17", "%Y%n%m%t%d");
+    test("2018-07-17 UTC", "%Y-%m-%d %Z");
+    test("Wed 07/04/18", "%a %D");
+    test("2018-13-01", "%Y-%m-%d");
+    test("25:00", "%H:%M");
+    test("Jul 32", "%b %d");
+    test("2018", "%Y-%m");
+    test("Monday", "%Ea %Od");
+    test("2018-07-17 06:25", "%EY-%Om-%Od %OH:%OM");
+}
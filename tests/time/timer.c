@@ -0,0 +1,78 @@
+#include <signal.h>
+#include <stdio.h>
+#include <time.h>
+
+volatile sig_atomic_t signals = 0;
+volatile int calls = 0;
+int value = 42;
+
+void handler(int sig) {
+    signals++;
+}
+
+void notify(union sigval sv) {
+    if (*(int *) sv.sival_ptr == 42) {
+        calls++;
+    }
+}
+
+// Sleep in small steps until `count` gets to `target`, giving up after a second
+int wait_for(volatile int *count, int target) {
+    struct timespec step = { 0, 1000000 };
+    for (int i = 0; i < 1000 && *count < target; i++) {
+        nanosleep(&step, NULL);
+    }
+    return *count >= target;
+}
+
+int main() {
+    struct itimerspec spec = { { 0, 0 }, { 10, 0 } };
+    struct itimerspec cur;
+    timer_t timer;
+
+    // SIGEV_NONE: nothing happens, but the timer runs
+    struct sigevent none = { 0 };
+    none.sigev_notify = SIGEV_NONE;
+    printf("create none: %d\n", timer_create(CLOCK_MONOTONIC, &none, &timer));
+    printf("settime: %d\n", timer_settime(timer, 0, &spec, NULL));
+    printf("gettime: %d\n", timer_gettime(timer, &cur));
+    printf("value left: %d\n", cur.it_value.tv_sec > 0 && cur.it_value.tv_sec <= 10);
+    printf("no interval: %d\n", cur.it_interval.tv_sec == 0 && cur.it_interval.tv_nsec == 0);
+    printf("getoverrun: %d\n", timer_getoverrun(timer));
+    printf("delete: %d\n", timer_delete(timer));
+
+    // SIGEV_SIGNAL: a handler for every expiration
+    struct sigaction act = { 0 };
+    act.sa_handler = handler;
+    sigaction(SIGUSR1, &act, NULL);
+    struct sigevent sig = { 0 };
+    sig.sigev_notify = SIGEV_SIGNAL;
+    sig.sigev_signo = SIGUSR1;
+    printf("create signal: %d\n", timer_create(CLOCK_MONOTONIC, &sig, &timer));
+    struct itimerspec periodic = { { 0, 5000000 }, { 0, 5000000 } };
+    printf("settime: %d\n", timer_settime(timer, 0, &periodic, NULL));
+    printf("signals arrived: %d\n", wait_for((volatile int *) &signals, 3));
+    printf("delete: %d\n", timer_delete(timer));
+
+    // SIGEV_THREAD: notify runs on another thread
+    struct sigevent thread = { 0 };
+    thread.sigev_notify = SIGEV_THREAD;
+    thread.sigev_notify_function = notify;
+    thread.sigev_value.sival_ptr = &value;
+    printf("create thread: %d\n", timer_create(CLOCK_MONOTONIC, &thread, &timer));
+    printf("settime: %d\n", timer_settime(timer, 0, &periodic, NULL));
+    printf("calls arrived: %d\n", wait_for(&calls, 3));
+    printf("delete: %d\n", timer_delete(timer));
+
+    // An absolute time in the past expires right away
+    printf("create thread: %d\n", timer_create(CLOCK_REALTIME, &thread, &timer));
+    struct itimerspec past = { { 0, 0 }, { 1, 0 } };
+    int before = calls;
+    printf("settime absolute: %d\n", timer_settime(timer, TIMER_ABSTIME, &past, NULL));
+    printf("call arrived: %d\n", wait_for(&calls, before + 1));
+    printf("delete: %d\n", timer_delete(timer));
+
+    struct sigevent bad = { 0 };
+    bad.sigev_notify = 1234;
+    printf("create with bad notify: %d\n", timer_create(CLOCK_MONOTONIC, &bad, &timer));
+}
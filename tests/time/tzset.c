@@ -0,0 +1,89 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <time.h>
+#include <unistd.h>
+
+void show(time_t clock) {
+    char buf[64];
+    struct tm *t = localtime(&clock);
+    strftime(buf, sizeof(buf), "%Y-%m-%d %H:%M:%S %z %Z", t);
+    printf("%s isdst %d gmtoff %ld", buf, t->tm_isdst, t->tm_gmtoff);
+    if (mktime(t) != clock) {
+        printf(" (mktime failed)");
+    }
+    printf("\n");
+}
+
+void show_mktime(int year, int mon, int mday, int hour, int min, int isdst) {
+    char buf[64];
+    struct tm t = { 0 };
+    t.tm_year = year - 1900;
+    t.tm_mon = mon - 1;
+    t.tm_mday = mday;
+    t.tm_hour = hour;
+    t.tm_min = min;
+    t.tm_isdst = isdst;
+    time_t clock = mktime(&t);
+    strftime(buf, sizeof(buf), "%Y-%m-%d %H:%M:%S %z %Z", &t);
+    printf("mktime isdst %d: %ld -> %s\n", isdst, (long) clock, buf);
+}
+
+void use_zone(const char *tz) {
+    setenv("TZ", tz, 1);
+    tzset();
+    printf("TZ=%s: tzname %s %s, timezone %ld, daylight %d\n",
+        tz, tzname[0], tzname[1], timezone, daylight);
+}
+
+int main() {
+    use_zone("EST5EDT,M3.2.0,M11.1.0");
+    show(1609459200); // 2021-01-01 00:00:00 UTC
+    show(1625097600); // 2021-07-01 00:00:00 UTC
+    show(1615705199); // Just before DST starts
+    show(1615705200); // Just after
+    show(1636264799); // Just before DST ends
+    show(1636264800); // Just after
+    show_mktime(2021, 3, 14, 2, 30, -1);
+    show_mktime(2021, 11, 7, 1, 30, 0);
+    show_mktime(2021, 11, 7, 1, 30, 1);
+    show_mktime(2021, 7, 1, 12, 0, 0);
+    show_mktime(2021, 13, 32, 25, 61, -1);
+
+    use_zone("AEST-10AEDT,M10.1.0,M4.1.0/3");
+    show(1609459200);
+    show(1625097600);
+
+    use_zone("<+0330>-3:30");
+    show(1609459200);
+
+    use_zone("JST-9");
+    show(1609459200);
+
+    use_zone("CET-1CEST,J60/2,300/3");
+    show(1609459200);
+    show(1625097600);
+
+    use_zone("UTC0");
+    show(1609459200);
+
+    use_zone("");
+    show(1609459200);
+
+    char path[256] = ":";
+    getcwd(path + 1, sizeof(path) - 1);
+    strcat(path, "/time/zoneinfo/New_York");
+    setenv("TZ", path, 1);
+    tzset();
+    printf("TZ=:.../New_York: tzname %s %s, timezone %ld, daylight %d\n",
+        tzname[0], tzname[1], timezone, daylight);
+    show(-1000000000); // 1938
+    show(1609459200);
+    show(1625097600);
+    show(2000000000); // 2033, from the rule after the last transition
+
+    time_t clock = 4102444800; // 2100-01-01 00:00:00 UTC
+    char buf[64];
+    strftime(buf, sizeof(buf), "%Y-%m-%d %H:%M:%S %z %Z", localtime(&clock));
+    puts(buf);
+}
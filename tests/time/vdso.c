@@ -0,0 +1,32 @@
+#include <stdio.h>
+#include <sys/time.h>
+#include <time.h>
+
+// These are answered by the vDSO where there is one, so check that they all
+// tell the same time and that the monotonic clock never goes back
+int main() {
+    struct timespec ts;
+    struct timeval tv;
+    time_t t = time(NULL);
+    int ret = clock_gettime(CLOCK_REALTIME, &ts);
+    printf("clock_gettime: %d\n", ret);
+    printf("gettimeofday: %d\n", gettimeofday(&tv, NULL));
+    printf("time agrees: %d\n", ts.tv_sec - t <= 1 && tv.tv_sec - t <= 1);
+
+    time_t out = 0;
+    printf("time stores: %d\n", time(&out) == out);
+
+    struct timespec last;
+    clock_gettime(CLOCK_MONOTONIC, &last);
+    int backwards = 0;
+    for (int i = 0; i < 100000; i++) {
+        clock_gettime(CLOCK_MONOTONIC, &ts);
+        if (ts.tv_sec < last.tv_sec || (ts.tv_sec == last.tv_sec && ts.tv_nsec < last.tv_nsec)) {
+            backwards++;
+        }
+        last = ts;
+    }
+    printf("monotonic went back: %d\n", backwards);
+
+    printf("bad clock: %d\n", clock_gettime(12345, &ts));
+}
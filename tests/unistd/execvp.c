@@ -0,0 +1,99 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/stat.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+extern char **environ;
+
+// Run `child` in a new process and wait for it. If the exec in it fails, the
+// child exits with errno.
+void run(const char *name, void (*child)(void)) {
+    fflush(stdout);
+    pid_t pid = fork();
+    if (pid == 0) {
+        child();
+        _exit(errno);
+    }
+    int status;
+    waitpid(pid, &status, 0);
+    printf("%s: %d\n", name, WEXITSTATUS(status));
+    fflush(stdout);
+}
+
+void write_file(const char *path, const char *text, mode_t mode) {
+    FILE *f = fopen(path, "w");
+    fputs(text, f);
+    fclose(f);
+    chmod(path, mode);
+}
+
+void search_path(void) {
+    execlp("sh", "sh", "-c", "echo execlp works", NULL);
+}
+
+void default_path(void) {
+    unsetenv("PATH");
+    char *args[] = { "sh", "-c", "echo default path works", NULL };
+    execvp("sh", args);
+}
+
+// The file in execvp_dir cannot be run, so the search goes on to /bin
+void skip_eacces(void) {
+    setenv("PATH", "execvp_dir:/bin", 1);
+    char *args[] = { "sh", "-c", "echo skipped EACCES", NULL };
+    execvp("sh", args);
+}
+
+void only_eacces(void) {
+    setenv("PATH", "execvp_dir", 1);
+    char *args[] = { "sh", NULL };
+    execvp("sh", args);
+}
+
+void not_found(void) {
+    char *args[] = { "execvp-missing", NULL };
+    execvp("execvp-missing", args);
+}
+
+// A file without #! is run by the shell
+void script(void) {
+    setenv("PATH", "/nonexistent:execvp_dir", 1);
+    char *args[] = { "script", "with", "args", NULL };
+    execvp("script", args);
+}
+
+void script_path(void) {
+    char *args[] = { "script", "by", "path", NULL };
+    execvpe("execvp_dir/script", args, environ);
+}
+
+void fexecve_sh(void) {
+    int fd = open("/bin/sh", O_RDONLY);
+    char *args[] = { "sh", "-c", "echo fexecve works", NULL };
+    fexecve(fd, args, environ);
+}
+
+int main() {
+    mkdir("execvp_dir", 0755);
+    write_file("execvp_dir/sh", "echo not run\n", 0644);
+    write_file("execvp_dir/script", "echo script works: $0 $1 $2\n", 0755);
+
+    run("execlp", search_path);
+    run("default path", default_path);
+    run("skip EACCES", skip_eacces);
+    printf("EACCES: %d\n", EACCES);
+    run("only EACCES", only_eacces);
+    printf("ENOENT: %d\n", ENOENT);
+    run("not found", not_found);
+    run("script", script);
+    run("script by path", script_path);
+    run("fexecve", fexecve_sh);
+
+    unlink("execvp_dir/sh");
+    unlink("execvp_dir/script");
+    rmdir("execvp_dir");
+}
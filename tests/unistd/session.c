@@ -0,0 +1,90 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <fcntl.h>
+#include <pty.h>
+#include <signal.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/wait.h>
+#include <termios.h>
+#include <unistd.h>
+
+// Run `f` in a child, print what it prints and return its exit status
+int in_child(void (*f)(void)) {
+    fflush(stdout);
+    pid_t pid = fork();
+    if (pid == 0) {
+        f();
+        fflush(stdout);
+        _exit(0);
+    }
+    int status;
+    waitpid(pid, &status, 0);
+    return WEXITSTATUS(status);
+}
+
+void new_group(void) {
+    printf("setpgrp: %d\n", setpgrp());
+    printf("group leader: %d\n", getpgrp() == getpid());
+    printf("same session: %d\n", getsid(0) == getsid(getppid()));
+}
+
+void new_session(void) {
+    pid_t pid = getpid();
+    printf("setsid: %d\n", setsid() == pid);
+    printf("session leader: %d\n", getsid(0) == pid && getsid(pid) == pid);
+    printf("group leader: %d\n", getpgrp() == pid);
+    printf("setsid again EPERM: %d\n", setsid() == -1 && errno == EPERM);
+
+    // Opening a terminal makes it the controlling one of the new session
+    int master, slave;
+    if (openpty(&master, &slave, NULL, NULL, NULL) < 0) {
+        perror("openpty");
+        return;
+    }
+    int tty = open(ptsname(master), O_RDWR);
+    printf("tcgetpgrp: %d\n", tcgetpgrp(tty) == pid);
+    printf("tcgetsid: %d\n", tcgetsid(tty) == pid);
+
+    // Put another process group in the foreground
+    signal(SIGTTOU, SIG_IGN);
+    int fds[2];
+    pipe(fds);
+    pid_t job = fork();
+    if (job == 0) {
+        char c;
+        read(fds[0], &c, 1);
+        _exit(0);
+    }
+    setpgid(job, job);
+    printf("tcsetpgrp job: %d\n", tcsetpgrp(tty, job));
+    printf("foreground is job: %d\n", tcgetpgrp(tty) == job);
+    printf("tcsetpgrp back: %d\n", tcsetpgrp(tty, pid));
+    printf("foreground is leader: %d\n", tcgetpgrp(tty) == pid);
+    write(fds[1], "x", 1);
+    waitpid(job, NULL, 0);
+}
+
+int main(void) {
+    printf("getsid self: %d\n", getsid(0) == getsid(getpid()));
+    pid_t ret = getsid(0x7ffffff0);
+    printf("getsid missing: %d, ESRCH: %d\n", ret, errno == ESRCH);
+    int fds[2];
+    pipe(fds);
+    ret = tcgetpgrp(fds[0]);
+    printf("tcgetpgrp pipe: %d, ENOTTY: %d\n", ret, errno == ENOTTY);
+    ret = tcgetsid(fds[0]);
+    printf("tcgetsid pipe: %d, ENOTTY: %d\n", ret, errno == ENOTTY);
+
+    in_child(new_group);
+    in_child(new_session);
+
+    fflush(stdout);
+    pid_t pid = vfork();
+    if (pid == 0) {
+        _exit(7);
+    }
+    int status;
+    waitpid(pid, &status, 0);
+    printf("vfork child status: %d\n", WEXITSTATUS(status));
+}
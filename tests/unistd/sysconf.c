@@ -0,0 +1,51 @@
+#include <errno.h>
+#include <stdio.h>
+#include <sys/resource.h>
+#include <unistd.h>
+
+int main() {
+    printf("pagesize: %d\n", sysconf(_SC_PAGESIZE) == getpagesize());
+    printf("clk_tck: %d\n", (int) sysconf(_SC_CLK_TCK));
+    printf("host_name_max: %d\n", (int) sysconf(_SC_HOST_NAME_MAX));
+    printf("arg_max: %d\n", sysconf(_SC_ARG_MAX) >= 131072);
+    long online = sysconf(_SC_NPROCESSORS_ONLN);
+    printf("nprocessors: %d\n", online >= 1 && sysconf(_SC_NPROCESSORS_CONF) >= online);
+
+    // The space for arguments stays bounded however large the stack may get
+    struct rlimit rlim;
+    getrlimit(RLIMIT_STACK, &rlim);
+    rlim.rlim_cur = rlim.rlim_max;
+    setrlimit(RLIMIT_STACK, &rlim);
+    long arg_max = sysconf(_SC_ARG_MAX);
+    printf("arg_max with the most stack: %d\n", arg_max >= 131072);
+
+    // The soft limit on files is what sysconf reports
+    printf("getrlimit: %d\n", getrlimit(RLIMIT_NOFILE, &rlim));
+    rlim.rlim_cur = 64;
+    printf("setrlimit: %d\n", setrlimit(RLIMIT_NOFILE, &rlim));
+    printf("open_max: %d\n", (int) sysconf(_SC_OPEN_MAX));
+
+    errno = 0;
+    long ret = sysconf(-1);
+    printf("bad name: %d, EINVAL: %d\n", (int) ret, errno == EINVAL);
+
+    char buf[8];
+    printf("confstr: %d\n", (int) confstr(_CS_PATH, NULL, 0));
+    printf("confstr truncated: %d, %s\n", (int) confstr(_CS_PATH, buf, sizeof(buf)), buf);
+
+    printf("path_max: %d\n", (int) pathconf("/", _PC_PATH_MAX));
+    printf("pipe_buf: %d\n", (int) fpathconf(STDIN_FILENO, _PC_PIPE_BUF));
+    printf("name_max: %d\n", pathconf(".", _PC_NAME_MAX) > 0);
+    printf("xfer_align: %d\n", fpathconf(STDOUT_FILENO, _PC_REC_XFER_ALIGN) > 0);
+    printf("no_trunc: %d\n", (int) pathconf(".", _PC_NO_TRUNC));
+
+    errno = 0;
+    ret = pathconf("/nonexistent", _PC_NAME_MAX);
+    printf("missing path: %d, ENOENT: %d\n", (int) ret, errno == ENOENT);
+    errno = 0;
+    ret = fpathconf(-1, _PC_NAME_MAX);
+    printf("bad fd: %d, EBADF: %d\n", (int) ret, errno == EBADF);
+    errno = 0;
+    ret = pathconf(".", -1);
+    printf("bad name: %d, EINVAL: %d\n", (int) ret, errno == EINVAL);
+}